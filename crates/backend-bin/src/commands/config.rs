@@ -0,0 +1,49 @@
+// crates/backend-bin/src/commands/config.rs
+
+//! `config` commands: check the configuration without starting the server.
+use backend_lib::config::Settings;
+use clap::Subcommand;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Report settings the server cannot run with; exits with status 1 if any
+    Check,
+}
+
+impl ConfigCommand {
+    pub fn run(self, config: &Settings) -> ExitCode {
+        match self {
+            Self::Check => {
+                let mut problems = config.problems();
+                // The data directory is created on start, but must be usable if it exists
+                let data_dir = &config.storage.path;
+                match std::fs::metadata(data_dir) {
+                    Ok(meta) if !meta.is_dir() => {
+                        problems.push(format!("{} is not a directory", data_dir.display()));
+                    },
+                    Ok(meta) if meta.permissions().readonly() => {
+                        problems.push(format!("{} is read-only", data_dir.display()));
+                    },
+                    _ => {},
+                }
+
+                if !problems.is_empty() {
+                    for problem in problems {
+                        println!("{problem}");
+                    }
+                    return ExitCode::FAILURE;
+                }
+                println!(
+                    "Configuration ok: listening on {}:{}, {:?} storage in {}",
+                    config.server.host,
+                    config.server.port,
+                    config.storage.backend,
+                    data_dir.display()
+                );
+            },
+        }
+
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,336 @@
+// crates/backend-bin/src/commands/log.rs
+
+//! `log` commands: verify, dump, show, diff and replay meet update logs.
+//!
+//! `verify` checks every current meet unless meet IDs are given, and exits
+//! with status 1 if any log is damaged. With the flat-file backend each
+//! record's framing is checked first; `--repair` rewrites damaged logs with
+//! only their valid records, keeping the original next to them.
+//!
+//! `show`, `diff` and `replay` take a log source: an argument naming an
+//! existing file is read as a plaintext `updates.log`, anything else as a
+//! meet ID. `diff` and `replay` exit with status 1 if they find differences.
+use backend_lib::config::{Settings, StorageBackend};
+use backend_lib::log_inspect::{
+    decode_log, diff_client_export, diff_logs, format_update, parse_client_export, read_log_file,
+    read_meet_log, replay, show, InspectedLog, KeyDiff, UpdateFilter,
+};
+use backend_lib::meet_registry::MeetStatus;
+use backend_lib::storage::{open_storage, update_seq, FlatFileStorage, Storage};
+use clap::Subcommand;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Updates read from storage at a time while dumping
+const DUMP_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Subcommand)]
+pub enum LogCommand {
+    /// Check that every update can be read and decoded in sequence
+    Verify {
+        /// Rewrite damaged flat-file logs with only their valid records
+        #[arg(long)]
+        repair: bool,
+        /// Meets to check [default: every current meet]
+        meet_ids: Vec<String>,
+    },
+    /// Print a meet's updates as stored, one JSON line each
+    Dump {
+        meet_id: String,
+        /// Server sequence number of the first update
+        #[arg(long, default_value_t = 1)]
+        from: u64,
+        /// Maximum number of updates to print
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Print decoded updates, one line each
+    Show {
+        /// Meet ID or `updates.log` file
+        source: String,
+        /// Only updates whose key starts with this
+        #[arg(long)]
+        key: Option<String>,
+        /// Only updates sent by this client
+        #[arg(long)]
+        client: Option<String>,
+        /// Server sequence number of the first update
+        #[arg(long, default_value_t = 1)]
+        from: u64,
+        /// Server sequence number of the last update
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// Compare a log with another log, or with a client's exported updates
+    Diff {
+        /// Meet ID or `updates.log` file
+        source: String,
+        /// Meet ID or `updates.log` file to compare with
+        #[arg(required_unless_present = "client")]
+        other: Option<String>,
+        /// JSON list of updates exported by a client, to compare with instead
+        #[arg(long, conflicts_with = "other")]
+        client: Option<PathBuf>,
+    },
+    /// Rebuild a meet's state from its log, print it and report any divergence
+    Replay {
+        /// Meet ID or `updates.log` file
+        source: String,
+    },
+}
+
+impl LogCommand {
+    pub async fn run(self, config: &Settings) -> Result<ExitCode, Box<dyn Error>> {
+        let storage = open_storage(&config.storage).await?;
+
+        match self {
+            Self::Verify { repair, meet_ids } => {
+                if !verify(config, &storage, meet_ids, repair).await? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            },
+            Self::Dump {
+                meet_id,
+                from,
+                limit,
+            } => {
+                let mut next = from.max(1);
+                let mut remaining = limit.unwrap_or(usize::MAX);
+                while remaining > 0 {
+                    let page = remaining.min(DUMP_PAGE_SIZE);
+                    let lines = storage.read_updates_from(&meet_id, next, page).await?;
+                    for line in &lines {
+                        println!("{line}");
+                    }
+                    // Pages follow the numbers stored in the updates, which
+                    // skip any records lost from the log
+                    let last = lines.last().and_then(|line| update_seq(line));
+                    match last {
+                        Some(last) if lines.len() == page => next = last + 1,
+                        _ => break,
+                    }
+                    remaining -= lines.len();
+                }
+            },
+            Self::Show {
+                source,
+                key,
+                client,
+                from,
+                to,
+            } => {
+                let log = read_source(&storage, &source).await?;
+                let filter = UpdateFilter {
+                    key_prefix: key,
+                    client_id: client,
+                    seqs: from..=to.unwrap_or(u64::MAX),
+                };
+                for update in log.updates.iter().filter(|u| filter.matches(u)) {
+                    println!("{}", format_update(update));
+                }
+                for (position, err) in &log.undecodable {
+                    tracing::warn!("Record {position} does not decode: {err}");
+                }
+            },
+            Self::Diff {
+                source,
+                other,
+                client,
+            } => {
+                let same = match (client, other) {
+                    (Some(path), _) => diff_client(&storage, &source, &path).await?,
+                    (None, Some(other)) => diff_sources(&storage, &source, &other).await?,
+                    (None, None) => unreachable!("clap requires a log or --client to compare with"),
+                };
+                if !same {
+                    return Ok(ExitCode::FAILURE);
+                }
+            },
+            Self::Replay { source } => {
+                let log = read_source(&storage, &source).await?;
+                let replay = replay(&log).await?;
+                tracing::info!("Replayed {} updates of {}", replay.server_seq, source);
+                for divergence in &replay.divergence {
+                    tracing::warn!("Divergence: {divergence}");
+                }
+                println!("{}", serde_json::to_string_pretty(&replay.state)?);
+                if !replay.divergence.is_empty() {
+                    return Ok(ExitCode::FAILURE);
+                }
+            },
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Verify the logs of the given meets, or of every current meet
+/// Returns whether every log was undamaged.
+async fn verify(
+    config: &Settings,
+    storage: &impl Storage,
+    meet_ids: Vec<String>,
+    repair: bool,
+) -> Result<bool, Box<dyn Error>> {
+    let meet_ids = if meet_ids.is_empty() {
+        storage
+            .list_meets()
+            .await?
+            .into_iter()
+            .filter(|record| record.status == MeetStatus::Current)
+            .map(|record| record.meet_id)
+            .collect()
+    } else {
+        meet_ids
+    };
+    let flat = match config.storage.backend {
+        StorageBackend::Flat => Some(FlatFileStorage::new(&config.storage.path)?),
+        StorageBackend::Sqlite => None,
+    };
+
+    let mut damaged = false;
+    for meet_id in meet_ids {
+        if let Some(flat) = &flat {
+            if !verify_framing(flat, &meet_id, repair).await? {
+                damaged = true;
+                if !repair {
+                    continue;
+                }
+            }
+        }
+        damaged |= !verify_updates(storage, &meet_id).await;
+    }
+    Ok(!damaged)
+}
+
+/// Check the record framing of a flat-file log, repairing it if asked
+/// Returns whether the log was undamaged.
+async fn verify_framing(
+    flat: &FlatFileStorage,
+    meet_id: &str,
+    repair: bool,
+) -> Result<bool, Box<dyn Error>> {
+    let scan = flat.verify_updates(meet_id).await?;
+    match (scan.corrupt_at, scan.torn_tail) {
+        (None, None) => return Ok(true),
+        (Some(offset), _) => println!("{meet_id}: corrupt record at byte {offset}"),
+        (None, Some(offset)) => println!("{meet_id}: torn record at byte {offset}"),
+    }
+    if repair {
+        let salvage = flat.repair_updates(meet_id).await?;
+        println!(
+            "{}: repaired, kept {} records",
+            meet_id,
+            salvage.records.len()
+        );
+        for range in salvage.dropped {
+            println!("  dropped bytes {}..{}", range.start, range.end);
+        }
+    }
+    Ok(false)
+}
+
+/// Check that a meet's updates decode and are numbered in order
+/// Returns whether every update was readable.
+async fn verify_updates(storage: &impl Storage, meet_id: &str) -> bool {
+    let log = match storage.read_updates(meet_id).await {
+        Ok(lines) => decode_log(lines),
+        Err(err) => {
+            println!("{meet_id}: {err}");
+            return false;
+        },
+    };
+
+    for (position, err) in &log.undecodable {
+        println!("{meet_id}: update {position} cannot be decoded: {err}");
+    }
+    for (position, seq) in &log.misnumbered {
+        println!("{meet_id}: update {position} is numbered {seq}, out of order");
+    }
+    let ok = log.undecodable.is_empty() && log.misnumbered.is_empty();
+    if ok {
+        println!("{}: ok, {} updates", meet_id, log.records.len());
+    }
+    ok
+}
+
+/// Read a log from a file if `source` names one, or else from storage
+async fn read_source(storage: &impl Storage, source: &str) -> Result<InspectedLog, Box<dyn Error>> {
+    let path = Path::new(source);
+    let log = if path.is_file() {
+        read_log_file(path)
+            .await
+            .map_err(|err| format!("{}: {}", path.display(), err))?
+    } else {
+        read_meet_log(storage, source).await?
+    };
+    for range in &log.damaged {
+        tracing::warn!(
+            "Skipped damaged bytes {}..{} of {}",
+            range.start,
+            range.end,
+            source
+        );
+    }
+    Ok(log)
+}
+
+/// Print keys that end with different values
+fn print_values(values: &[KeyDiff], left: &str, right: &str) {
+    for diff in values {
+        println!(
+            "{}: {} {}, {} {}",
+            diff.key,
+            left,
+            show(diff.left.as_ref()),
+            right,
+            show(diff.right.as_ref())
+        );
+    }
+}
+
+/// Compare a log with a client's exported updates, returning whether they agree
+async fn diff_client(
+    storage: &impl Storage,
+    source: &str,
+    path: &Path,
+) -> Result<bool, Box<dyn Error>> {
+    let log = read_source(storage, source).await?;
+    let json = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    let diff = diff_client_export(&log.updates, &parse_client_export(&json)?);
+    for update in &diff.missing {
+        println!(
+            "not recorded: local {}  {} = {}",
+            update.local_seq_num, update.update_key, update.update_value
+        );
+    }
+    print_values(&diff.values, "server", "client");
+    Ok(diff.missing.is_empty() && diff.values.is_empty())
+}
+
+/// Compare two logs, returning whether they agree
+/// Lines starting `<` are only in the first log, `>` only in the second.
+async fn diff_sources(
+    storage: &impl Storage,
+    source: &str,
+    other: &str,
+) -> Result<bool, Box<dyn Error>> {
+    let log = read_source(storage, source).await?;
+    let other_log = read_source(storage, other).await?;
+    let diff = diff_logs(&log.updates, &other_log.updates);
+    for update in &diff.only_left {
+        println!("< {}", format_update(update));
+    }
+    for update in &diff.only_right {
+        println!("> {}", format_update(update));
+    }
+    for (left, right) in &diff.changed {
+        println!("< {}", format_update(left));
+        println!("> {}", format_update(right));
+    }
+    print_values(&diff.values, source, other);
+    Ok(diff.is_empty())
+}
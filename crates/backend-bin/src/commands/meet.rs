@@ -0,0 +1,142 @@
+// crates/backend-bin/src/commands/meet.rs
+
+//! `meet` commands: list, show, archive, export, import and encrypt meets.
+//!
+//! Exports write `<meet_id>.tar` unless a file is given. Imports refuse to
+//! replace an existing meet unless `--force` is given. Only `encrypt`
+//! rewrites meets still held in plaintext; the server also does so at
+//! startup.
+use super::status_name;
+use backend_lib::bundle::{export_meet, import_meet};
+use backend_lib::config::Settings;
+use backend_lib::error::AppError;
+use backend_lib::meet_registry::{MeetRegistry, MeetStatus, StorageMeetRegistry};
+use backend_lib::storage::{open_storage, open_storage_and_migrate, Storage};
+use clap::Subcommand;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum MeetCommand {
+    /// List every current and finished meet
+    List,
+    /// Show a meet's status, locations and update count
+    Show { meet_id: String },
+    /// Archive a current meet
+    Archive { meet_id: String },
+    /// Export a current meet as a bundle
+    Export {
+        meet_id: String,
+        #[arg(help = "Bundle to write [default: <meet_id>.tar]")]
+        file: Option<PathBuf>,
+    },
+    /// Import a bundle under the meet ID it was exported with
+    Import {
+        /// Replace a current meet with the same ID
+        #[arg(long)]
+        force: bool,
+        file: PathBuf,
+    },
+    /// Encrypt meets still held in plaintext
+    Encrypt,
+}
+
+impl MeetCommand {
+    pub async fn run(self, config: &Settings) -> Result<ExitCode, Box<dyn Error>> {
+        if let Self::Encrypt = self {
+            if !config.storage.encryption.enabled {
+                return Err("Encryption is not enabled in the configuration".into());
+            }
+            let (_, migrated) = open_storage_and_migrate(&config.storage).await?;
+            println!("Encrypted {migrated} plaintext meets");
+            return Ok(ExitCode::SUCCESS);
+        }
+        let storage = open_storage(&config.storage).await?;
+
+        match self {
+            Self::List => {
+                for record in storage.list_meets().await? {
+                    let last_change = storage
+                        .meet_activity(&record.meet_id)
+                        .await?
+                        .and_then(|activity| activity.last_change)
+                        .map_or_else(|| "-".to_string(), |at| at.to_rfc3339());
+                    println!(
+                        "{}\t{}\t{}",
+                        record.meet_id,
+                        status_name(record.status),
+                        last_change
+                    );
+                }
+            },
+            Self::Show { meet_id } => {
+                let registry = StorageMeetRegistry::load(storage.clone()).await?;
+                let Some(record) = registry.get(&meet_id).await else {
+                    return Err(format!("No meet {meet_id}").into());
+                };
+                println!("Meet:        {meet_id}");
+                println!("Status:      {}", status_name(record.status));
+                if let Some(activity) = storage.meet_activity(&meet_id).await? {
+                    let times = [
+                        ("Last change", activity.last_change),
+                        ("Finished", activity.finished_at),
+                        ("Published", activity.published_at),
+                        ("Purged", activity.purged_at),
+                    ];
+                    for (label, at) in times {
+                        if let Some(at) = at {
+                            println!("{:<12} {}", format!("{label}:"), at.to_rfc3339());
+                        }
+                    }
+                }
+                // Only current meets can be read
+                if record.status == MeetStatus::Current {
+                    match storage.get_meet_info(&meet_id).await {
+                        Ok(info) => {
+                            println!(
+                                "Updates:     {}",
+                                storage.read_updates(&meet_id).await?.len()
+                            );
+                            for endpoint in info.endpoints {
+                                println!(
+                                    "Location:    {} (priority {})",
+                                    endpoint.location_name, endpoint.priority
+                                );
+                            }
+                        },
+                        // IDs are reserved before anything is written
+                        Err(AppError::MeetNotFound) => println!("Data:        reserved, no data"),
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+            },
+            Self::Archive { meet_id } => {
+                let registry = StorageMeetRegistry::load(storage).await?;
+                registry.finish(&meet_id).await?;
+                println!("Archived meet {meet_id}");
+            },
+            Self::Export { meet_id, file } => {
+                let path = file.unwrap_or_else(|| PathBuf::from(format!("{meet_id}.tar")));
+                let bundle = export_meet(&storage, &meet_id).await?;
+                tokio::fs::write(&path, &bundle)
+                    .await
+                    .map_err(|err| format!("{}: {}", path.display(), err))?;
+                println!("Exported meet {} to {}", meet_id, path.display());
+            },
+            Self::Import { force, file } => {
+                let bytes = tokio::fs::read(&file)
+                    .await
+                    .map_err(|err| format!("{}: {}", file.display(), err))?;
+                let manifest = import_meet(&storage, &bytes, force).await?;
+                println!(
+                    "Imported meet {} with {} updates",
+                    manifest.meet_id, manifest.server_seq
+                );
+            },
+            Self::Encrypt => unreachable!("handled before the storage is opened"),
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,17 @@
+// crates/backend-bin/src/commands/mod.rs
+
+//! Operator commands run against the configured storage.
+pub mod config;
+pub mod log;
+pub mod meet;
+pub mod sessions;
+
+use backend_lib::meet_registry::MeetStatus;
+
+/// Name of a meet status as shown to operators
+pub fn status_name(status: MeetStatus) -> &'static str {
+    match status {
+        MeetStatus::Current => "current",
+        MeetStatus::Finished => "finished",
+    }
+}
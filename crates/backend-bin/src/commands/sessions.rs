@@ -0,0 +1,89 @@
+// crates/backend-bin/src/commands/sessions.rs
+
+//! `sessions` commands: list and purge the sessions in the configured store.
+//!
+//! Tokens are never printed. With the `memory` session backend there is
+//! nothing to work on, since sessions only live inside a running server.
+use backend_lib::config::{SessionBackend, Settings};
+use backend_lib::messages::SessionRole;
+use backend_lib::open_session_store;
+use backend_lib::storage::{open_storage, Storage};
+use clap::Subcommand;
+use std::error::Error;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsCommand {
+    /// List unexpired sessions by meet
+    List {
+        /// Only list sessions of this meet
+        meet_id: Option<String>,
+    },
+    /// Remove expired sessions, or every session of a meet
+    Purge {
+        /// Remove every session of this meet instead of only expired ones
+        meet_id: Option<String>,
+    },
+}
+
+impl SessionsCommand {
+    pub async fn run(self, config: &Settings) -> Result<ExitCode, Box<dyn Error>> {
+        if config.sessions.backend == SessionBackend::Memory {
+            return Err("The memory session backend keeps no sessions outside the server".into());
+        }
+        let sessions = open_session_store(config).await?;
+
+        match self {
+            Self::List { meet_id } => {
+                let meet_ids = if let Some(meet_id) = meet_id {
+                    vec![meet_id]
+                } else {
+                    let storage = open_storage(&config.storage).await?;
+                    storage
+                        .list_meets()
+                        .await?
+                        .into_iter()
+                        .map(|record| record.meet_id)
+                        .collect()
+                };
+                for meet_id in meet_ids {
+                    let mut list = sessions.sessions_for_meet(&meet_id).await;
+                    list.sort_by(|a, b| a.location_name.cmp(&b.location_name));
+                    for session in list {
+                        println!(
+                            "{}\t{}\t{}\t{}",
+                            meet_id,
+                            session.location_name,
+                            session.priority,
+                            role_name(session.role)
+                        );
+                    }
+                }
+            },
+            Self::Purge { meet_id: None } => {
+                let before = sessions.active_session_count().await;
+                sessions.cleanup_expired_sessions().await;
+                sessions.flush().await?;
+                let removed = before.saturating_sub(sessions.active_session_count().await);
+                println!("Removed {removed} expired sessions");
+            },
+            Self::Purge {
+                meet_id: Some(meet_id),
+            } => {
+                let removed = sessions.remove_sessions_for_meet(&meet_id, "").await;
+                sessions.flush().await?;
+                println!("Removed {} sessions of meet {}", removed.len(), meet_id);
+            },
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Name of a session role as shown to operators
+fn role_name(role: SessionRole) -> &'static str {
+    match role {
+        SessionRole::Director => "director",
+        SessionRole::Station => "station",
+    }
+}
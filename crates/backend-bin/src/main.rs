@@ -1,88 +1,138 @@
 // crates/backend-bin/src/main.rs
 
-//! Backend server for the application.
-use backend_lib::{config::Settings, storage::FlatFileStorage, ws_router, AppState};
-use std::fs;
-use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::time::{interval, Duration};
-use tracing::{debug, info};
-use tracing_subscriber::EnvFilter;
+//! Backend server for the application, and the operator commands that work
+//! on its data.
+//!
+//! Without a subcommand the server is started, as with `serve`. Every other
+//! command opens the configured storage directly, so it works while the
+//! server is stopped. Commands that change data should only be run then,
+//! since a running server does not notice changes made behind its back.
+mod commands;
+mod serve;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing with better defaults
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_target(true)
-        .init();
-
-    info!("Starting OpenLifter WebSocket server...");
+use backend_lib::config::{LogFormat, LoggingSettings, Settings};
+use clap::{Parser, Subcommand};
+use commands::{
+    config::ConfigCommand, log::LogCommand, meet::MeetCommand, sessions::SessionsCommand,
+};
+use serve::ServeArgs;
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tracing::error;
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
 
-    // Initialize configuration
-    let config = Settings::load()?;
-    debug!("Configuration loaded: {:?}", config);
-
-    // Ensure the data directory exists
-    let data_dir = "data";
-    fs::create_dir_all(data_dir).expect("Failed to create data directory");
-    fs::create_dir_all(format!("{data_dir}/current-meets"))
-        .expect("Failed to create current-meets directory");
-    fs::create_dir_all(format!("{data_dir}/finished-meets"))
-        .expect("Failed to create finished-meets directory");
-    fs::create_dir_all(format!("{data_dir}/sessions"))
-        .expect("Failed to create sessions directory");
-    debug!("Data directories created");
-
-    // Create storage
-    let storage = FlatFileStorage::new(data_dir)?;
-    info!("Storage initialized with path: {}", data_dir);
-
-    // Create application state
-    let state = Arc::new(AppState::new(storage, &config).await?);
-    info!("Application state initialized");
+#[derive(Debug, Parser)]
+#[command(version, about = "OpenLifter WebSocket server")]
+struct Cli {
+    /// Configuration file, without extension [default: config/default]
+    #[arg(long, global = true)]
+    config: Option<String>,
+    /// Data directory, overriding `storage.path`
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-    // Setup a background task for session cleanup
-    let state_clone = state.clone();
-    tokio::spawn(async move {
-        // Run cleanup every 15 minutes
-        let mut interval = interval(Duration::from_secs(15 * 60));
-        loop {
-            interval.tick().await;
-            info!("Running scheduled session cleanup");
-            state_clone.sessions.cleanup_expired_sessions().await;
-        }
-    });
-    debug!("Session cleanup task scheduled");
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Start the server
+    Serve(ServeArgs),
+    /// Inspect, archive, export and import meets
+    #[command(subcommand)]
+    Meet(MeetCommand),
+    /// Verify, dump and replay meet update logs
+    #[command(subcommand)]
+    Log(LogCommand),
+    /// List and purge sessions
+    #[command(subcommand)]
+    Sessions(SessionsCommand),
+    /// Check the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
 
-    // Setup a background task for auth rate limiter cleanup
-    let auth_rate_limiter = state.auth_rate_limiter.clone();
-    tokio::spawn(async move {
-        // Run cleanup every hour
-        let mut interval = interval(Duration::from_secs(60 * 60));
-        loop {
-            interval.tick().await;
-            info!("Running scheduled auth rate limiter cleanup");
-            auth_rate_limiter.cleanup();
+impl Cli {
+    /// Load the configuration and apply the global overrides
+    fn settings(&self) -> Result<Settings, Box<dyn Error>> {
+        let mut settings = match &self.config {
+            Some(path) => Settings::load_from(path)?,
+            None => Settings::load()?,
+        };
+        if let Some(data_dir) = &self.data_dir {
+            settings.storage.path.clone_from(data_dir);
         }
-    });
-    debug!("Auth rate limiter cleanup task scheduled");
-
-    // Create the router using the optimized WebSocket router
-    let app = ws_router::create_router(state);
-    info!("Router created");
+        Ok(settings)
+    }
+}
 
-    // Start the server
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    let listener = TcpListener::bind(&addr).await?;
-    info!("Server listening on {addr}");
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let settings = match cli.settings() {
+        Ok(settings) => settings,
+        Err(err) => {
+            init_fallback_logging();
+            error!("Error loading configuration: {err}");
+            return ExitCode::FAILURE;
+        },
+    };
+    // The server logs to stdout; commands keep stdout for their output
+    let writer = match cli.command {
+        None | Some(Command::Serve(_)) => BoxMakeWriter::new(io::stdout),
+        Some(_) => BoxMakeWriter::new(io::stderr),
+    };
+    if let Err(err) = init_logging(&settings.logging, writer) {
+        init_fallback_logging();
+        error!("Error setting up logging: {err}");
+        return ExitCode::FAILURE;
+    }
 
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await?;
+    let result = match cli.command {
+        None => serve::run(settings, ServeArgs::default()).await,
+        Some(Command::Serve(args)) => serve::run(settings, args).await,
+        Some(Command::Meet(command)) => command.run(&settings).await,
+        Some(Command::Log(command)) => command.run(&settings).await,
+        Some(Command::Sessions(command)) => command.run(&settings).await,
+        Some(Command::Config(command)) => Ok(command.run(&settings)),
+    };
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            error!("Error: {err}");
+            ExitCode::FAILURE
+        },
+    }
+}
 
+/// Install the log subscriber; a non-empty `RUST_LOG` overrides the configured levels
+fn init_logging(logging: &LoggingSettings, writer: BoxMakeWriter) -> Result<(), Box<dyn Error>> {
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(directives) if !directives.is_empty() => EnvFilter::try_new(directives)?,
+        _ => EnvFilter::try_new(logging.directives())?,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_target(true);
+    match logging.format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .init(),
+    }
     Ok(())
 }
+
+/// Log to stderr with the default levels, for errors met before the
+/// configured logging is set up
+fn init_fallback_logging() {
+    tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .with_target(true)
+        .init();
+}
@@ -0,0 +1,231 @@
+// crates/backend-bin/src/serve.rs
+
+//! The `serve` command: run the server until it is stopped.
+use axum::Router;
+use backend_lib::{
+    audit::{self, AuditLog, AUDIT_DIR},
+    config::Settings,
+    health::{Health, TASK_SESSION_CLEANUP},
+    retention::run_retention,
+    storage::open_storage_and_migrate,
+    ws_router, AppState,
+};
+use chrono::Utc;
+use clap::Args;
+use std::error::Error;
+use std::fs;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::time::{interval, sleep, Duration};
+use tracing::{debug, error, info, warn};
+
+/// How long open connections may keep the server running once the listener
+/// has closed
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Overrides of the configured server settings
+#[derive(Debug, Default, Args)]
+pub struct ServeArgs {
+    /// Address to listen on, overriding `server.host`
+    #[arg(long)]
+    host: Option<String>,
+    /// Port to listen on, overriding `server.port`
+    #[arg(long)]
+    port: Option<u16>,
+    /// Apply the retention policy, overriding `retention.enabled`
+    #[arg(long)]
+    retention: Option<bool>,
+}
+
+impl ServeArgs {
+    fn apply(self, config: &mut Settings) {
+        if let Some(host) = self.host {
+            config.server.host = host;
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(retention) = self.retention {
+            config.retention.enabled = retention;
+        }
+    }
+}
+
+pub async fn run(mut config: Settings, args: ServeArgs) -> Result<ExitCode, Box<dyn Error>> {
+    info!("Starting OpenLifter WebSocket server...");
+
+    args.apply(&mut config);
+    debug!("Configuration loaded: {:?}", config);
+
+    // Ensure the data directory exists; the storage backend creates its own layout
+    let data_dir = &config.storage.path;
+    fs::create_dir_all(data_dir).expect("Failed to create data directory");
+    fs::create_dir_all(data_dir.join("sessions")).expect("Failed to create sessions directory");
+    debug!("Data directories created");
+
+    // Keep metrics for /metrics; histograms are drained every few seconds
+    let metrics = backend_lib::metrics::install_recorder()?;
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            metrics.run_upkeep();
+        }
+    });
+
+    // Record security events before sessions are loaded
+    let audit_log = AuditLog::open(data_dir.join(AUDIT_DIR))?;
+    info!("Audit log at {}", audit_log.dir().display());
+    audit::install(audit_log);
+
+    // Create storage, encrypting meets still held in plaintext before serving
+    let (storage, migrated) = open_storage_and_migrate(&config.storage).await?;
+    if migrated > 0 {
+        info!("Encrypted {} plaintext meets", migrated);
+    }
+    info!(
+        "Storage initialized with path: {} ({:?})",
+        data_dir.display(),
+        config.storage.backend
+    );
+
+    // Create application state
+    let state = Arc::new(AppState::new(storage, &config).await?);
+    info!("Application state initialized");
+
+    // Setup a background task for session cleanup
+    let state_clone = state.clone();
+    let every = Duration::from_mins(15);
+    let heartbeat = state.health.register(TASK_SESSION_CLEANUP, every);
+    tokio::spawn(async move {
+        // Run cleanup every 15 minutes
+        let mut interval = interval(every);
+        loop {
+            interval.tick().await;
+            info!("Running scheduled session cleanup");
+            state_clone.sessions.cleanup_expired_sessions().await;
+            heartbeat.beat();
+        }
+    });
+    debug!("Session cleanup task scheduled");
+
+    // Setup a background task for auth rate limiter cleanup
+    let auth_rate_limiter = state.auth_rate_limiter.clone();
+    tokio::spawn(async move {
+        // Run cleanup every hour
+        let mut interval = interval(Duration::from_hours(1));
+        loop {
+            interval.tick().await;
+            info!("Running scheduled auth rate limiter cleanup");
+            auth_rate_limiter.cleanup();
+        }
+    });
+    debug!("Auth rate limiter cleanup task scheduled");
+
+    // Setup a background task applying the retention policy
+    if config.retention.enabled {
+        let state_clone = state.clone();
+        let every = Duration::from_mins(config.retention.interval_mins.max(1));
+        tokio::spawn(async move {
+            let mut interval = interval(every);
+            loop {
+                interval.tick().await;
+                info!("Running scheduled retention purge");
+                match run_retention(&state_clone, Utc::now()).await {
+                    Ok(report) => info!(
+                        "Retention purged {}, {} pending, {} in use, {} failed",
+                        report.purged.len(),
+                        report.pending.len(),
+                        report.in_use.len(),
+                        report.failed
+                    ),
+                    Err(err) => error!("Error running retention purge: {err}"),
+                }
+            }
+        });
+        debug!("Retention task scheduled");
+    }
+
+    // Create the router using the optimized WebSocket router
+    let app = ws_router::create_router(state.clone());
+    info!("Router created");
+
+    // Start the server
+    let listener = TcpListener::bind((config.server.host.as_str(), config.server.port)).await?;
+    info!("Server listening on {}", listener.local_addr()?);
+
+    let drain = Duration::from_secs(config.server.drain_secs);
+    serve_until_stopped(listener, app, state.health.clone(), drain).await?;
+
+    // Stop saving in the background and write out sessions not saved yet
+    state.sessions.shutdown().await?;
+    // Wait for the audit writer to catch up with the events recorded so far
+    tokio::task::spawn_blocking(audit::flush).await?;
+    info!("Server stopped");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Serve `app` until shutdown is requested, then drain and stop. Graceful
+/// shutdown waits for every connection, and WebSocket clients may never hang
+/// up, so they are dropped after `CLOSE_TIMEOUT`.
+async fn serve_until_stopped(
+    listener: TcpListener,
+    app: Router,
+    health: Arc<Health>,
+    drain: Duration,
+) -> std::io::Result<()> {
+    let (closing_tx, closing_rx) = oneshot::channel();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        drain_on_shutdown(health, drain).await;
+        let _ = closing_tx.send(());
+    });
+
+    let close_timeout = async {
+        match closing_rx.await {
+            Ok(()) => sleep(CLOSE_TIMEOUT).await,
+            Err(_) => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = server => result?,
+        () = close_timeout => warn!(
+            "Connections still open {}s after the listener closed, dropping them",
+            CLOSE_TIMEOUT.as_secs()
+        ),
+    }
+    Ok(())
+}
+
+/// Wait for Ctrl-C or SIGTERM, then report the server as draining for
+/// `drain` before letting the listener close
+async fn drain_on_shutdown(health: Arc<Health>, drain: Duration) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for Ctrl-C");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {},
+        () = terminate => {},
+    }
+    info!("Shutdown requested, draining for {}s", drain.as_secs());
+    health.start_draining();
+    tokio::time::sleep(drain).await;
+}
@@ -0,0 +1,853 @@
+// crates/backend-lib/src/audit.rs
+
+/** Append-only audit log of security events.
+Logins, session changes, lockouts and refused requests are written as JSON
+lines to `audit/audit-YYYY-MM-DD.jsonl` under the data directory, one file
+per UTC day. Each record carries the SHA-256 hash of the record before it,
+and its own hash covers that link, so editing, removing or reordering any
+record breaks the chain from that point on; `verify` finds the break. The
+chain continues across files. Since the chain cannot show records cut off its
+end, `head.json` beside the files names the newest record, and `verify`
+checks the chain ends there.
+
+Events are only written once `install` has been called, which `backend-bin`
+does at startup; until then `record` only logs them under the `security`
+tracing target. `install` hands the log to a writer thread, so `record` never
+waits for the disk; `flush` waits for what was recorded before it. Session
+tokens are never recorded. */
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread;
+
+/// Directory under the data directory holding the audit files
+pub const AUDIT_DIR: &str = "audit";
+
+/// Records returned by a query when no limit is given
+pub const DEFAULT_QUERY_LIMIT: usize = 1000;
+
+/// File in the audit directory naming the newest record
+pub const HEAD_FILE: &str = "head.json";
+
+/// `prev_hash` of the first record ever written
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The writer thread of the installed log
+static AUDIT: OnceLock<AuditWriter> = OnceLock::new();
+
+/// What the writer thread is asked to do
+enum WriterMsg {
+    Record(DateTime<Utc>, AuditEvent),
+    /// Reply once everything sent before has been written
+    Flush(mpsc::Sender<()>),
+}
+
+/// Handle of the thread writing the installed log
+struct AuditWriter {
+    tx: mpsc::Sender<WriterMsg>,
+}
+
+/// Kinds of security event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    /// A meet was created and its director signed in
+    MeetCreated,
+    LoginSucceeded,
+    /// A join was refused: wrong password, unknown meet or a locked-out address
+    LoginFailed,
+    /// An address was locked out after too many failed logins
+    LockedOut,
+    SessionCreated,
+    SessionRotated,
+    /// A session was signed out, revoked or removed with its meet
+    SessionRevoked,
+    SessionExpired,
+    /// A request was refused for its session: unknown token, another meet,
+    /// missing role or a bad CSRF token
+    PermissionDenied,
+    PasswordChanged,
+    /// The session encryption key was rotated
+    KeyRotated,
+    /// Persisted sessions could not be encrypted or decrypted
+    SessionStoreError,
+}
+
+/// A security event to be recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub kind: AuditKind,
+    pub ip: Option<IpAddr>,
+    pub meet_id: Option<String>,
+    pub location: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(kind: AuditKind) -> Self {
+        Self {
+            kind,
+            ip: None,
+            meet_id: None,
+            location: None,
+            detail: None,
+        }
+    }
+
+    /// Set the client address, when it is known
+    pub fn with_ip(mut self, ip: Option<IpAddr>) -> Self {
+        self.ip = ip;
+        self
+    }
+
+    pub fn with_meet(mut self, meet_id: impl Into<String>) -> Self {
+        self.meet_id = Some(meet_id.into());
+        self
+    }
+
+    pub fn with_location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// A written event, one line of an audit file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the chain, starting at 1
+    pub seq: u64,
+    pub time: DateTime<Utc>,
+    pub event: AuditKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<IpAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meet_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// `hash` of the previous record, or `GENESIS_HASH`
+    pub prev_hash: String,
+    /// SHA-256 of this record serialized without `hash`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// The hash this record should carry
+    pub fn compute_hash(&self) -> String {
+        let unsigned = AuditRecord {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let json = serde_json::to_vec(&unsigned).expect("audit records always serialize");
+        let mut hex = String::with_capacity(64);
+        for byte in Sha256::digest(&json) {
+            let _ = write!(hex, "{byte:02x}");
+        }
+        hex
+    }
+}
+
+/// Newest record of the chain, as kept in `HEAD_FILE`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ChainHead {
+    seq: u64,
+    hash: String,
+}
+
+impl ChainHead {
+    /// Whether a chain ending with `last` ends at this head. The last record
+    /// may be one ahead, written just before a crash.
+    fn matches(&self, last: &ChainHead, last_prev_hash: &str) -> bool {
+        self == last || (last.seq == self.seq + 1 && last_prev_hash == self.hash)
+    }
+}
+
+/// Where the next record goes
+struct ChainState {
+    seq: u64,
+    last_hash: String,
+    /// Day of the open file and the file itself
+    file: Option<(NaiveDate, File)>,
+}
+
+/// Writer of the audit files in one directory
+pub struct AuditLog {
+    dir: PathBuf,
+    state: Mutex<ChainState>,
+}
+
+impl AuditLog {
+    /// Open the audit directory, creating it if needed, and continue the
+    /// chain from the last readable record of the newest file holding one.
+    /// A partial last line, left by a crash mid-write, is cut off so the
+    /// next record starts on a line of its own. If the head names a later
+    /// record, records were removed from the end; the chain continues from
+    /// the head so the gap stays visible to `verify`.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let files = audit_files(&dir)?;
+        if let Some((_, path)) = files.last() {
+            truncate_partial_line(path)?;
+        }
+        let mut seq = 0;
+        let mut last_hash = GENESIS_HASH.to_string();
+        for (_, path) in files.iter().rev() {
+            if let Some(record) = last_record(path)? {
+                seq = record.seq;
+                last_hash = record.hash;
+                break;
+            }
+        }
+        match read_head(&dir) {
+            Ok(Some(head)) if head.seq > seq => {
+                tracing::error!(
+                    "Audit log in {} ends at record {} but its head names record {}",
+                    dir.display(),
+                    seq,
+                    head.seq
+                );
+                seq = head.seq;
+                last_hash = head.hash;
+            },
+            Ok(Some(head)) if head.seq == seq => {},
+            Ok(_) => write_head(
+                &dir,
+                &ChainHead {
+                    seq,
+                    hash: last_hash.clone(),
+                },
+            )?,
+            Err(err) => tracing::error!("Unreadable audit head in {}: {}", dir.display(), err),
+        }
+
+        Ok(Self {
+            dir,
+            state: Mutex::new(ChainState {
+                seq,
+                last_hash,
+                file: None,
+            }),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Append an event to today's file
+    /// # Returns
+    /// * `Ok(AuditRecord)` - The record as written
+    pub fn append(&self, event: AuditEvent) -> io::Result<AuditRecord> {
+        self.append_at(Utc::now(), event)
+    }
+
+    /// Append an event that happened at `time`
+    fn append_at(&self, time: DateTime<Utc>, event: AuditEvent) -> io::Result<AuditRecord> {
+        let mut state = self.state.lock().unwrap();
+        let mut record = AuditRecord {
+            seq: state.seq + 1,
+            time,
+            event: event.kind,
+            ip: event.ip,
+            meet_id: event.meet_id,
+            location: event.location,
+            detail: event.detail,
+            prev_hash: state.last_hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+
+        let day = time.date_naive();
+        let file = match &mut state.file {
+            Some((open_day, file)) if *open_day == day => file,
+            slot => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(day_path(&self.dir, day))?;
+                &mut slot.insert((day, file)).1
+            },
+        };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_data()?;
+        write_head(
+            &self.dir,
+            &ChainHead {
+                seq: record.seq,
+                hash: record.hash.clone(),
+            },
+        )?;
+
+        state.seq = record.seq;
+        state.last_hash = record.hash.clone();
+        Ok(record)
+    }
+}
+
+/// Make `log` the destination of `record`, written by a thread of its own
+/// # Returns
+/// * `false` if a log was already installed
+pub fn install(log: AuditLog) -> bool {
+    if AUDIT.get().is_some() {
+        return false;
+    }
+    let (tx, rx) = mpsc::channel();
+    let writer = AuditWriter { tx };
+    if AUDIT.set(writer).is_err() {
+        return false;
+    }
+    thread::Builder::new()
+        .name("audit-writer".to_string())
+        .spawn(move || write_records(&log, &rx))
+        .is_ok()
+}
+
+/// Write what `record` sends until the sending side goes away
+fn write_records(log: &AuditLog, rx: &mpsc::Receiver<WriterMsg>) {
+    for msg in rx {
+        match msg {
+            WriterMsg::Record(time, event) => {
+                if let Err(err) = log.append_at(time, event) {
+                    tracing::error!(
+                        "Failed to write audit record to {}: {}",
+                        log.dir.display(),
+                        err
+                    );
+                }
+            },
+            WriterMsg::Flush(done) => {
+                let _ = done.send(());
+            },
+        }
+    }
+}
+
+/// Record a security event in the installed log, and under the `security`
+/// tracing target
+pub fn record(event: AuditEvent) {
+    tracing::info!(
+        target: "security",
+        event = ?event.kind,
+        ip = event.ip.map(tracing::field::display),
+        meet_id = event.meet_id.as_deref(),
+        location = event.location.as_deref(),
+        "{}",
+        event.detail.as_deref().unwrap_or_default()
+    );
+    if let Some(writer) = AUDIT.get() {
+        if writer
+            .tx
+            .send(WriterMsg::Record(Utc::now(), event))
+            .is_err()
+        {
+            tracing::error!("Audit writer has stopped; record dropped");
+        }
+    }
+}
+
+/// Block until every event recorded so far has been written
+pub fn flush() {
+    let Some(writer) = AUDIT.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = mpsc::channel();
+    if writer.tx.send(WriterMsg::Flush(done_tx)).is_ok() {
+        let _ = done_rx.recv();
+    }
+}
+
+/// Cut `path` back to its last newline
+fn truncate_partial_line(path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    if contents.is_empty() || contents.ends_with(b"\n") {
+        return Ok(());
+    }
+    let keep = contents
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    tracing::warn!(
+        "Removing a partial audit record at the end of {}",
+        path.display()
+    );
+    file.set_len(keep as u64)?;
+    file.sync_data()
+}
+
+/// Last readable record of the file at `path`
+fn last_record(path: &Path) -> io::Result<Option<AuditRecord>> {
+    let mut last = None;
+    for line in BufReader::new(File::open(path)?).lines() {
+        if let Ok(record) = serde_json::from_str::<AuditRecord>(&line?) {
+            last = Some(record);
+        }
+    }
+    Ok(last)
+}
+
+/// The head in `dir`, if one was written
+fn read_head(dir: &Path) -> io::Result<Option<ChainHead>> {
+    match fs::read(dir.join(HEAD_FILE)) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replace the head in `dir` so that a crash leaves the old or the new one
+fn write_head(dir: &Path, head: &ChainHead) -> io::Result<()> {
+    let path = dir.join(HEAD_FILE);
+    let tmp = path.with_extension("json.tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(&serde_json::to_vec(head)?)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, &path)?;
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// File holding the records of `day`
+pub fn day_path(dir: &Path, day: NaiveDate) -> PathBuf {
+    dir.join(format!("audit-{}.jsonl", day.format("%Y-%m-%d")))
+}
+
+/// Audit files in `dir` with their days, oldest first
+fn audit_files(dir: &Path) -> io::Result<Vec<(NaiveDate, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let day = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("audit-"))
+            .and_then(|name| name.strip_suffix(".jsonl"))
+            .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok());
+        if let Some(day) = day {
+            files.push((day, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Filter of an audit query; every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    /// First day to search, inclusive
+    pub from: Option<NaiveDate>,
+    /// Last day to search, inclusive
+    pub to: Option<NaiveDate>,
+    pub event: Option<AuditKind>,
+    pub meet_id: Option<String>,
+    pub ip: Option<IpAddr>,
+    /// Most recent matches to return, `DEFAULT_QUERY_LIMIT` if unset
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, record: &AuditRecord) -> bool {
+        self.event.is_none_or(|event| event == record.event)
+            && self
+                .meet_id
+                .as_deref()
+                .is_none_or(|meet_id| record.meet_id.as_deref() == Some(meet_id))
+            && self.ip.is_none_or(|ip| record.ip == Some(ip))
+    }
+}
+
+/// Records in `dir` matching `query`, oldest first
+/// Unreadable lines are skipped; `verify` reports them.
+pub fn query(dir: &Path, query: &AuditQuery) -> io::Result<Vec<AuditRecord>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    let mut records = Vec::new();
+    for (day, path) in audit_files(dir)? {
+        if query.from.is_some_and(|from| day < from) || query.to.is_some_and(|to| day > to) {
+            continue;
+        }
+        for line in BufReader::new(File::open(path)?).lines() {
+            if let Ok(record) = serde_json::from_str::<AuditRecord>(&line?) {
+                if query.matches(&record) {
+                    records.push(record);
+                }
+            }
+        }
+    }
+    let skip = records.len().saturating_sub(limit);
+    records.drain(..skip);
+    Ok(records)
+}
+
+/// A place where the chain does not hold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainBreak {
+    /// File name within the audit directory
+    pub file: String,
+    /// Line number within the file, starting at 1
+    pub line: usize,
+    pub seq: Option<u64>,
+    pub reason: String,
+}
+
+/// Outcome of checking every audit file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainReport {
+    pub ok: bool,
+    pub files: usize,
+    pub records: u64,
+    /// Where the chain breaks, in order; checking resumes after each break
+    pub breaks: Vec<ChainBreak>,
+}
+
+/// Check the hash chain across every audit file in `dir`, and that it ends
+/// at the record named by the head
+pub fn verify(dir: &Path) -> io::Result<ChainReport> {
+    let files = if dir.exists() {
+        audit_files(dir)?
+    } else {
+        Vec::new()
+    };
+    let mut report = ChainReport {
+        ok: true,
+        files: files.len(),
+        records: 0,
+        breaks: Vec::new(),
+    };
+
+    let mut expected_seq = 1;
+    let mut expected_hash = GENESIS_HASH.to_string();
+    let mut last_prev_hash = String::new();
+    for (_, path) in &files {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            let mut broken = |seq, reason: &str| {
+                report.breaks.push(ChainBreak {
+                    file: file.clone(),
+                    line: index + 1,
+                    seq,
+                    reason: reason.to_string(),
+                })
+            };
+            let record = match serde_json::from_str::<AuditRecord>(&line) {
+                Ok(record) => record,
+                Err(_) => {
+                    broken(None, "unreadable record");
+                    continue;
+                },
+            };
+
+            if record.compute_hash() != record.hash {
+                broken(Some(record.seq), "record does not match its hash");
+            } else if record.prev_hash != expected_hash {
+                broken(
+                    Some(record.seq),
+                    "previous hash does not match; records before it were changed or removed",
+                );
+            } else if record.seq != expected_seq {
+                broken(Some(record.seq), "sequence number out of order");
+            }
+            report.records += 1;
+            expected_seq = record.seq + 1;
+            expected_hash = record.hash;
+            last_prev_hash = record.prev_hash;
+        }
+    }
+
+    let last = ChainHead {
+        seq: expected_seq - 1,
+        hash: expected_hash,
+    };
+    let reason = match read_head(dir) {
+        Ok(Some(head)) if head.matches(&last, &last_prev_hash) => None,
+        Ok(Some(head)) if head.seq > last.seq => Some(format!(
+            "the log ends at record {} but the head names record {}; the newest records were removed",
+            last.seq, head.seq
+        )),
+        Ok(Some(_)) => Some("the last record does not match the head".to_string()),
+        Ok(None) if report.records == 0 => None,
+        Ok(None) => Some("head is missing".to_string()),
+        Err(_) => Some("unreadable head".to_string()),
+    };
+    if let Some(reason) = reason {
+        report.breaks.push(ChainBreak {
+            file: HEAD_FILE.to_string(),
+            line: 1,
+            seq: Some(last.seq),
+            reason,
+        });
+    }
+    report.ok = report.breaks.is_empty();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn login_failed(meet_id: &str, ip: &str) -> AuditEvent {
+        AuditEvent::new(AuditKind::LoginFailed)
+            .with_ip(Some(ip.parse().unwrap()))
+            .with_meet(meet_id)
+            .with_location("Platform A")
+            .with_detail("incorrect password")
+    }
+
+    fn today_path(dir: &Path) -> PathBuf {
+        day_path(dir, Utc::now().date_naive())
+    }
+
+    #[test]
+    fn test_chain_continues_after_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(AUDIT_DIR);
+
+        let log = AuditLog::open(&dir).unwrap();
+        let first = log.append(login_failed("meet-a", "10.0.0.1")).unwrap();
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        drop(log);
+
+        let log = AuditLog::open(&dir).unwrap();
+        let second = log
+            .append(AuditEvent::new(AuditKind::LoginSucceeded).with_meet("meet-a"))
+            .unwrap();
+        assert_eq!(second.seq, 2);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let report = verify(&dir).unwrap();
+        assert!(report.ok, "{:?}", report.breaks);
+        assert_eq!(report.records, 2);
+        assert_eq!(report.files, 1);
+
+        let text = fs::read_to_string(today_path(&dir)).unwrap();
+        let line: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(line["event"], "login_failed");
+        assert_eq!(line["ip"], "10.0.0.1");
+        assert_eq!(line["location"], "Platform A");
+    }
+
+    #[test]
+    fn test_partial_last_line_is_cut_on_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        let first = log.append(login_failed("meet-a", "10.0.0.1")).unwrap();
+        drop(log);
+
+        // A crash mid-write leaves the start of a record without its newline
+        let path = today_path(&dir);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"seq":2,"time":"#).unwrap();
+        drop(file);
+
+        let log = AuditLog::open(&dir).unwrap();
+        let second = log.append(login_failed("meet-a", "10.0.0.2")).unwrap();
+        assert_eq!(second.seq, 2);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let report = verify(&dir).unwrap();
+        assert!(report.ok, "{:?}", report.breaks);
+        assert_eq!(report.records, 2);
+    }
+
+    #[test]
+    fn test_chain_skips_empty_newest_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        let first = log
+            .append_at(yesterday, login_failed("meet-a", "10.0.0.1"))
+            .unwrap();
+        drop(log);
+
+        // Today's file was created but its first write failed
+        File::create(today_path(&dir)).unwrap();
+
+        let log = AuditLog::open(&dir).unwrap();
+        let second = log.append(login_failed("meet-a", "10.0.0.2")).unwrap();
+        assert_eq!(second.seq, 2);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let report = verify(&dir).unwrap();
+        assert!(report.ok, "{:?}", report.breaks);
+        assert_eq!(report.files, 2);
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        for n in 0..4 {
+            log.append(login_failed("meet-a", &format!("10.0.0.{n}")))
+                .unwrap();
+        }
+        let path = today_path(&dir);
+        let original = fs::read_to_string(&path).unwrap();
+
+        // Editing a record breaks its own hash
+        fs::write(&path, original.replacen("10.0.0.1", "10.0.0.9", 1)).unwrap();
+        let report = verify(&dir).unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].line, 2);
+        assert_eq!(report.breaks[0].seq, Some(2));
+
+        // Removing a record breaks the link of the next one
+        let lines: Vec<&str> = original.lines().collect();
+        let without_second = [lines[0], lines[2], lines[3]].join("\n") + "\n";
+        fs::write(&path, without_second).unwrap();
+        let report = verify(&dir).unwrap();
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].seq, Some(3));
+
+        // Truncating a record makes it unreadable
+        fs::write(&path, format!("{}{}\n", original, &lines[0][..20])).unwrap();
+        let report = verify(&dir).unwrap();
+        assert_eq!(report.breaks[0].reason, "unreadable record");
+        assert_eq!(report.records, 4);
+    }
+
+    #[test]
+    fn test_verify_detects_cut_off_tail() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        log.append_at(yesterday, login_failed("meet-a", "10.0.0.1"))
+            .unwrap();
+        for n in 2..=4 {
+            log.append(login_failed("meet-a", &format!("10.0.0.{n}")))
+                .unwrap();
+        }
+        drop(log);
+        assert!(verify(&dir).unwrap().ok);
+
+        // Dropping the newest records leaves a chain that is sound up to there
+        let path = today_path(&dir);
+        let original = fs::read_to_string(&path).unwrap();
+        let first_line = original.lines().next().unwrap();
+        fs::write(&path, format!("{first_line}\n")).unwrap();
+        let report = verify(&dir).unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].file, HEAD_FILE);
+        assert_eq!(report.breaks[0].seq, Some(2));
+
+        // So does deleting the newest file
+        fs::remove_file(&path).unwrap();
+        let report = verify(&dir).unwrap();
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].seq, Some(1));
+
+        // Reopening does not paper over the gap
+        let log = AuditLog::open(&dir).unwrap();
+        let next = log.append(login_failed("meet-a", "10.0.0.5")).unwrap();
+        assert_eq!(next.seq, 5);
+        let report = verify(&dir).unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.breaks[0].seq, Some(5));
+
+        // Neither does removing the head
+        fs::remove_file(dir.join(HEAD_FILE)).unwrap();
+        assert!(!verify(&dir).unwrap().ok);
+    }
+
+    #[test]
+    fn test_record_without_head_update_is_accepted() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        log.append(login_failed("meet-a", "10.0.0.1")).unwrap();
+        let head = fs::read(dir.join(HEAD_FILE)).unwrap();
+        log.append(login_failed("meet-a", "10.0.0.2")).unwrap();
+        drop(log);
+
+        // A crash after a record was written but before the head was moved
+        fs::write(dir.join(HEAD_FILE), head).unwrap();
+        assert!(verify(&dir).unwrap().ok);
+        let log = AuditLog::open(&dir).unwrap();
+        assert_eq!(
+            log.append(login_failed("meet-a", "10.0.0.3")).unwrap().seq,
+            3
+        );
+        assert!(verify(&dir).unwrap().ok);
+    }
+
+    #[test]
+    fn test_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let log = AuditLog::open(&dir).unwrap();
+        log.append(login_failed("meet-a", "10.0.0.1")).unwrap();
+        log.append(login_failed("meet-b", "10.0.0.2")).unwrap();
+        log.append(login_failed("meet-a", "10.0.0.2")).unwrap();
+        log.append(
+            AuditEvent::new(AuditKind::LockedOut).with_ip(Some("10.0.0.2".parse().unwrap())),
+        )
+        .unwrap();
+
+        let all = query(&dir, &AuditQuery::default()).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let filter = AuditQuery {
+            event: Some(AuditKind::LoginFailed),
+            ip: Some("10.0.0.2".parse().unwrap()),
+            ..Default::default()
+        };
+        let seqs: Vec<u64> = query(&dir, &filter)
+            .unwrap()
+            .iter()
+            .map(|r| r.seq)
+            .collect();
+        assert_eq!(seqs, vec![2, 3]);
+
+        let filter = AuditQuery {
+            meet_id: Some("meet-a".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let seqs: Vec<u64> = query(&dir, &filter)
+            .unwrap()
+            .iter()
+            .map(|r| r.seq)
+            .collect();
+        assert_eq!(seqs, vec![3]);
+
+        let tomorrow = Utc::now().date_naive().succ_opt().unwrap();
+        let filter = AuditQuery {
+            from: Some(tomorrow),
+            ..Default::default()
+        };
+        assert!(query(&dir, &filter).unwrap().is_empty());
+    }
+}
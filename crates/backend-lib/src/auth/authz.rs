@@ -0,0 +1,316 @@
+// crates/backend-lib/src/auth/authz.rs
+
+//! Per-connection authorization.
+//!
+//! A session token is issued for exactly one meet. Each WebSocket connection
+//! keeps a `ConnectionAuthz` that resolves the presented token once, caches the
+//! resulting session, and refuses any message that names a different meet.
+//! Tokens that match no session are audited with the client address, at
+//! most once a minute per address so guessing tokens can't flood the log.
+use super::AuthService;
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::messages::Session;
+use crate::metrics as metric_names;
+use dashmap::{mapref::entry::Entry, DashMap};
+use metrics::counter;
+use std::net::IpAddr;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Shortest time between two unknown-token records for one address
+const UNKNOWN_TOKEN_AUDIT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Addresses tracked before those not seen for an interval are dropped
+const UNKNOWN_TOKEN_MAX_ADDRESSES: usize = 4096;
+
+/// Per address, when an unknown token was last recorded and how many have
+/// been presented since
+static UNKNOWN_TOKENS: LazyLock<DashMap<Option<IpAddr>, (Instant, u64)>> =
+    LazyLock::new(DashMap::new);
+
+/// Count an unknown token presented from `ip`
+/// # Returns
+/// * `Some(count)` - Tokens to report now, including this one
+/// * `None` - One was recorded for this address less than an interval ago
+fn note_unknown_token(ip: Option<IpAddr>) -> Option<u64> {
+    let now = Instant::now();
+    if UNKNOWN_TOKENS.len() >= UNKNOWN_TOKEN_MAX_ADDRESSES {
+        UNKNOWN_TOKENS
+            .retain(|_, (last, _)| now.duration_since(*last) < UNKNOWN_TOKEN_AUDIT_INTERVAL);
+    }
+    match UNKNOWN_TOKENS.entry(ip) {
+        Entry::Occupied(mut entry) => {
+            let (last, count) = entry.get_mut();
+            *count += 1;
+            if now.duration_since(*last) < UNKNOWN_TOKEN_AUDIT_INTERVAL {
+                return None;
+            }
+            let seen = *count;
+            *entry.get_mut() = (now, 0);
+            Some(seen)
+        },
+        Entry::Vacant(entry) => {
+            entry.insert((now, 0));
+            Some(1)
+        },
+    }
+}
+
+/// Audit a token that matches no live session, subject to the rate limit
+fn record_unknown_token(ip: Option<IpAddr>) {
+    let detail = match note_unknown_token(ip) {
+        None => return,
+        Some(1) => "Unknown or expired session token presented".to_string(),
+        Some(seen) => format!("{seen} unknown or expired session tokens presented"),
+    };
+    audit::record(
+        AuditEvent::new(AuditKind::PermissionDenied)
+            .with_ip(ip)
+            .with_detail(detail),
+    );
+}
+
+/// Reasons a message is refused
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AuthzError {
+    #[error("Session is invalid or has expired")]
+    InvalidSession,
+
+    #[error("Session is not valid for this meet")]
+    WrongMeet,
+}
+
+/// Authorization state for a single connection
+#[derive(Debug, Default)]
+pub struct ConnectionAuthz {
+    session: Option<Session>,
+    /// Address of the client, for the audit log
+    client_ip: Option<IpAddr>,
+}
+
+impl ConnectionAuthz {
+    /// Create authorization state with no resolved session
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the client address recorded with refused requests
+    pub fn set_client_ip(&mut self, ip: IpAddr) {
+        self.client_ip = Some(ip);
+    }
+
+    /// Resolve `token` to a live session.
+    ///
+    /// The session is looked up once per token and cached for the rest of the
+    /// connection; its validity is still re-checked on every call so expiry and
+    /// revocation take effect immediately.
+    pub async fn resolve(
+        &mut self,
+        auth: &dyn AuthService,
+        token: &str,
+    ) -> Result<&Session, AuthzError> {
+        if !auth.validate_session(token).await {
+            if self.session(token).is_some() {
+                self.session = None;
+            } else {
+                record_unknown_token(self.client_ip);
+            }
+            return Err(AuthzError::InvalidSession);
+        }
+
+        if self.session(token).is_none() {
+            self.session = Some(
+                auth.get_session(token)
+                    .await
+                    .ok_or(AuthzError::InvalidSession)?,
+            );
+        }
+        self.session.as_ref().ok_or(AuthzError::InvalidSession)
+    }
+
+    /// Check that `token` is a live session bound to `meet_id`
+    pub async fn authorize(
+        &mut self,
+        auth: &dyn AuthService,
+        meet_id: &str,
+        token: &str,
+    ) -> Result<&Session, AuthzError> {
+        let client_ip = self.client_ip;
+        let session = self.resolve(auth, token).await?;
+
+        if session.meet_id != meet_id {
+            counter!(metric_names::AUTH_MEET_MISMATCH).increment(1);
+            audit::record(
+                AuditEvent::new(AuditKind::PermissionDenied)
+                    .with_ip(client_ip)
+                    .with_meet(meet_id)
+                    .with_location(&session.location_name)
+                    .with_detail(format!(
+                        "Session for meet {} used for this meet",
+                        session.meet_id
+                    )),
+            );
+            return Err(AuthzError::WrongMeet);
+        }
+
+        Ok(session)
+    }
+
+    /// The cached session, if it was resolved from `token`
+    pub fn session(&self, token: &str) -> Option<&Session> {
+        self.session.as_ref().filter(|s| s.token == token)
+    }
+
+    /// Forget the cached session
+    pub fn clear(&mut self) {
+        self.session = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::SessionRole;
+    use async_trait::async_trait;
+    use std::any::Any;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Auth service holding a single session and counting lookups
+    struct OneSession {
+        session: Mutex<Option<Session>>,
+        lookups: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AuthService for OneSession {
+        async fn new_session(
+            &self,
+            meet_id: String,
+            location_name: String,
+            priority: u8,
+        ) -> String {
+            let session = Session::new(meet_id, location_name, priority);
+            let token = session.token.clone();
+            *self.session.lock().unwrap() = Some(session);
+            token
+        }
+
+        async fn get_session(&self, token: &str) -> Option<Session> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            self.session
+                .lock()
+                .unwrap()
+                .clone()
+                .filter(|s| s.token == token)
+        }
+
+        async fn validate_session(&self, token: &str) -> bool {
+            self.session
+                .lock()
+                .unwrap()
+                .as_ref()
+                .is_some_and(|s| s.token == token)
+        }
+
+        async fn new_session_with_role(
+            &self,
+            meet_id: String,
+            location_name: String,
+            priority: u8,
+            _role: SessionRole,
+        ) -> String {
+            self.new_session(meet_id, location_name, priority).await
+        }
+
+        async fn remove_session(&self, _token: &str) {
+            *self.session.lock().unwrap() = None;
+        }
+
+        async fn rotate_session(&self, _token: &str) -> Option<String> {
+            None
+        }
+
+        async fn sessions_for_meet(&self, _meet_id: &str) -> Vec<Session> {
+            self.session.lock().unwrap().iter().cloned().collect()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn service() -> OneSession {
+        OneSession {
+            session: Mutex::new(None),
+            lookups: AtomicUsize::new(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_resolved_once() {
+        let auth = service();
+        let token = auth
+            .new_session("meet-a".to_string(), "Table".to_string(), 1)
+            .await;
+        let mut authz = ConnectionAuthz::new();
+
+        for _ in 0..3 {
+            let session = authz.authorize(&auth, "meet-a", &token).await.unwrap();
+            assert_eq!(session.location_name, "Table");
+        }
+        assert_eq!(auth.lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_meet_is_forbidden() {
+        let auth = service();
+        let token = auth
+            .new_session("meet-a".to_string(), "Table".to_string(), 1)
+            .await;
+        let mut authz = ConnectionAuthz::new();
+
+        assert_eq!(
+            authz.authorize(&auth, "meet-b", &token).await,
+            Err(AuthzError::WrongMeet)
+        );
+        assert!(authz.authorize(&auth, "meet-a", &token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revoked_session_is_dropped_from_cache() {
+        let auth = service();
+        let token = auth
+            .new_session("meet-a".to_string(), "Table".to_string(), 1)
+            .await;
+        let mut authz = ConnectionAuthz::new();
+        authz.authorize(&auth, "meet-a", &token).await.unwrap();
+
+        *auth.session.lock().unwrap() = None;
+
+        assert_eq!(
+            authz.authorize(&auth, "meet-a", &token).await,
+            Err(AuthzError::InvalidSession)
+        );
+        assert!(authz.session(&token).is_none());
+    }
+
+    #[test]
+    fn test_unknown_tokens_recorded_once_per_interval() {
+        let ip: Option<IpAddr> = Some("198.51.100.7".parse().unwrap());
+        let other: Option<IpAddr> = Some("198.51.100.8".parse().unwrap());
+
+        assert_eq!(note_unknown_token(ip), Some(1));
+        assert_eq!(note_unknown_token(ip), None);
+        assert_eq!(note_unknown_token(ip), None);
+        assert_eq!(note_unknown_token(other), Some(1));
+
+        // The next record reports the tokens held back in between
+        UNKNOWN_TOKENS.alter(&ip, |_, (last, count)| {
+            (last - UNKNOWN_TOKEN_AUDIT_INTERVAL, count)
+        });
+        assert_eq!(note_unknown_token(ip), Some(3));
+        assert_eq!(note_unknown_token(ip), None);
+    }
+}
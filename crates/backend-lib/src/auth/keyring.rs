@@ -0,0 +1,418 @@
+// crates/backend-lib/src/auth/keyring.rs
+
+//! Versioned encryption keys for persisted sessions and meet data.
+//!
+//! Every key has an ID that is written into the header of `sessions.dat`, so a
+//! file can always be decrypted with the key that produced it while new writes
+//! use the active key. Keys either live in a keyring file next to the sessions
+//! or are derived from operator-supplied secrets, in which case no key material
+//! is stored on disk at all.
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::{rngs::OsRng, TryRngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// File holding the keyring inside the sessions directory
+pub const KEYRING_FILE: &str = "session_keyring.json";
+
+/// Raw 32-byte key file written by earlier versions
+const LEGACY_KEY_FILE: &str = "session_key";
+
+/// Marks data written with a key ID header
+const MAGIC: &[u8; 4] = b"OLSK";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 4;
+
+/// Salt for keys derived from operator secrets
+const DERIVATION_SALT: &[u8] = b"openlifter-session-keyring-v1";
+
+#[derive(Serialize, Deserialize)]
+struct KeyringFile {
+    active: u32,
+    keys: Vec<StoredKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredKey {
+    id: u32,
+    /// Base64-encoded key
+    key: String,
+}
+
+/// Set of session encryption keys, one of which is used for new writes
+#[derive(Clone)]
+pub struct Keyring {
+    keys: BTreeMap<u32, [u8; KEY_LEN]>,
+    active: u32,
+    /// Where the keyring is persisted; `None` for keys derived from secrets
+    path: Option<PathBuf>,
+}
+
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print key material
+        f.debug_struct("Keyring")
+            .field("key_ids", &self.key_ids())
+            .field("active", &self.active)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Keyring {
+    /** Load the keyring stored in `dir`, creating it if needed.
+    A raw `session_key` file left by earlier versions is imported as key 1 and
+    removed once the keyring has been written. */
+    pub fn load_or_create<P: AsRef<Path>>(dir: P) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        let path = dir.join(KEYRING_FILE);
+        let legacy_path = dir.join(LEGACY_KEY_FILE);
+
+        if path.exists() || !legacy_path.exists() {
+            return Self::load_or_create_file(path);
+        }
+
+        let key = <[u8; KEY_LEN]>::try_from(fs::read(&legacy_path)?)
+            .map_err(|_| anyhow!("Invalid encryption key length"))?;
+        let keyring = Self {
+            keys: BTreeMap::from([(1, key)]),
+            active: 1,
+            path: Some(path),
+        };
+        keyring.save()?;
+        fs::remove_file(&legacy_path)?;
+
+        Ok(keyring)
+    }
+
+    /// Load the keyring file at `path`, creating it with one random key if it
+    /// does not exist yet
+    pub fn load_or_create_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        if path.exists() {
+            let file: KeyringFile = serde_json::from_slice(&fs::read(&path)?)
+                .with_context(|| format!("Invalid keyring file {}", path.display()))?;
+            let mut keys = BTreeMap::new();
+            for stored in file.keys {
+                let key = BASE64
+                    .decode(stored.key)
+                    .ok()
+                    .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+                    .ok_or_else(|| {
+                        anyhow!("Invalid encryption key length for key {}", stored.id)
+                    })?;
+                keys.insert(stored.id, key);
+            }
+            if !keys.contains_key(&file.active) {
+                bail!("Active key {} is missing from the keyring", file.active);
+            }
+            return Ok(Self {
+                keys,
+                active: file.active,
+                path: Some(path),
+            });
+        }
+
+        let keyring = Self {
+            keys: BTreeMap::from([(1, random_key())]),
+            active: 1,
+            path: Some(path),
+        };
+        keyring.save()?;
+
+        Ok(keyring)
+    }
+
+    /** Derive keys from operator-supplied secrets.
+    `secret` becomes the active key; `previous` secrets are only used to read
+    data written before the secret was changed. Nothing is written to disk. */
+    pub fn from_secrets(secret: &str, previous: &[String]) -> anyhow::Result<Self> {
+        let (active, key) = derive_key(secret)?;
+        let mut keys = BTreeMap::from([(active, key)]);
+
+        for old in previous {
+            let (id, key) = derive_key(old)?;
+            if keys.insert(id, key).is_some_and(|existing| existing != key) {
+                bail!("Two session secrets derive the same key ID; choose another secret");
+            }
+        }
+
+        Ok(Self {
+            keys,
+            active,
+            path: None,
+        })
+    }
+
+    /// ID of the key used for new writes
+    pub fn active_id(&self) -> u32 {
+        self.active
+    }
+
+    /// IDs of every key in the keyring
+    pub fn key_ids(&self) -> Vec<u32> {
+        self.keys.keys().copied().collect()
+    }
+
+    /** Add a fresh random key, make it active and persist the keyring.
+    Older keys are kept so existing data can still be decrypted. */
+    pub fn rotate(&mut self) -> anyhow::Result<u32> {
+        if self.path.is_none() {
+            bail!("Keys derived from a secret are rotated by changing the secret");
+        }
+
+        let id = self.keys.keys().next_back().map_or(1, |last| last + 1);
+        self.keys.insert(id, random_key());
+        let previous = std::mem::replace(&mut self.active, id);
+
+        if let Err(err) = self.save() {
+            self.keys.remove(&id);
+            self.active = previous;
+            return Err(err);
+        }
+        Ok(id)
+    }
+
+    /// Encrypt `plaintext` with the active key, prefixed by the key ID header
+    pub fn encrypt(&self, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let header = header(self.active);
+        let cipher = Aes256Gcm::new_from_slice(&self.keys[&self.active])?;
+        let nonce_bytes = random_nonce();
+
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: plaintext,
+                    aad: &header,
+                },
+            )
+            .map_err(|err| anyhow!("Encryption failed: {err}"))?;
+
+        let mut data = Vec::with_capacity(HEADER_LEN + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&nonce_bytes);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    /** Decrypt data produced by `encrypt`, returning the ID of the key used.
+    Data without a header predates key IDs and is tried against every key. */
+    pub fn decrypt(&self, data: &[u8]) -> anyhow::Result<(u32, Vec<u8>)> {
+        if let Some(rest) = data.strip_prefix(MAGIC) {
+            if rest.len() < 4 + NONCE_LEN {
+                bail!("Invalid session file");
+            }
+            let id = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]);
+            let key = self
+                .keys
+                .get(&id)
+                .ok_or_else(|| anyhow!("Session file was encrypted with unknown key {id}"))?;
+            let (nonce, ciphertext) = rest[4..].split_at(NONCE_LEN);
+
+            let plaintext = Aes256Gcm::new_from_slice(key)?
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: &data[..HEADER_LEN],
+                    },
+                )
+                .map_err(|err| anyhow!("Decryption failed: {err}"))?;
+            return Ok((id, plaintext));
+        }
+
+        if data.len() < NONCE_LEN {
+            bail!("Invalid session file");
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        for (id, key) in &self.keys {
+            if let Ok(plaintext) =
+                Aes256Gcm::new_from_slice(key)?.decrypt(Nonce::from_slice(nonce), ciphertext)
+            {
+                return Ok((*id, plaintext));
+            }
+        }
+        bail!("Decryption failed: no key in the keyring matches")
+    }
+
+    /// Write the keyring file atomically and durably, readable by the owner only
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let file = KeyringFile {
+            active: self.active,
+            keys: self
+                .keys
+                .iter()
+                .map(|(id, key)| StoredKey {
+                    id: *id,
+                    key: BASE64.encode(key),
+                })
+                .collect(),
+        };
+
+        let tmp = path.with_extension("json.tmp");
+        let mut tmp_file = fs::File::create(&tmp)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tmp_file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        tmp_file.write_all(&serde_json::to_vec_pretty(&file)?)?;
+        tmp_file.sync_all()?;
+        drop(tmp_file);
+        fs::rename(&tmp, path)?;
+
+        // Sync the directory so the rename itself survives a crash
+        #[cfg(unix)]
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+/// Header identifying the key that encrypted the data
+fn header(id: u32) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()..].copy_from_slice(&id.to_be_bytes());
+    header
+}
+
+/// Derive a key and its ID from a secret
+fn derive_key(secret: &str) -> anyhow::Result<(u32, [u8; KEY_LEN])> {
+    if secret.is_empty() {
+        bail!("Session secret must not be empty");
+    }
+
+    // The extra four bytes give every secret a stable key ID
+    let mut output = [0u8; KEY_LEN + 4];
+    scrypt::scrypt(
+        secret.as_bytes(),
+        DERIVATION_SALT,
+        &scrypt::Params::recommended(),
+        &mut output,
+    )
+    .map_err(|err| anyhow!("Key derivation failed: {err}"))?;
+
+    let mut key = [0u8; KEY_LEN];
+    key.copy_from_slice(&output[..KEY_LEN]);
+    let id = u32::from_be_bytes([
+        output[KEY_LEN],
+        output[KEY_LEN + 1],
+        output[KEY_LEN + 2],
+        output[KEY_LEN + 3],
+    ]);
+    Ok((id, key))
+}
+
+fn random_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    OsRng
+        .try_fill_bytes(&mut key)
+        .expect("OS random number generator failed");
+    key
+}
+
+fn random_nonce() -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng
+        .try_fill_bytes(&mut nonce)
+        .expect("OS random number generator failed");
+    nonce
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotation_keeps_old_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keyring = Keyring::load_or_create(temp_dir.path()).unwrap();
+        let old = keyring.encrypt(b"sessions").unwrap();
+
+        assert_eq!(keyring.rotate().unwrap(), 2);
+        let new = keyring.encrypt(b"sessions").unwrap();
+
+        // Reloading gives the same keyring back
+        let reloaded = Keyring::load_or_create(temp_dir.path()).unwrap();
+        assert_eq!(reloaded.active_id(), 2);
+        assert_eq!(reloaded.decrypt(&old).unwrap(), (1, b"sessions".to_vec()));
+        assert_eq!(reloaded.decrypt(&new).unwrap(), (2, b"sessions".to_vec()));
+    }
+
+    #[test]
+    fn test_legacy_key_file_is_imported() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = [7u8; KEY_LEN];
+        fs::write(temp_dir.path().join(LEGACY_KEY_FILE), key).unwrap();
+
+        // Data in the old format: nonce followed by ciphertext, no header
+        let nonce = [1u8; NONCE_LEN];
+        let ciphertext = Aes256Gcm::new_from_slice(&key)
+            .unwrap()
+            .encrypt(Nonce::from_slice(&nonce), b"legacy".as_slice())
+            .unwrap();
+        let legacy = [nonce.as_slice(), &ciphertext].concat();
+
+        let keyring = Keyring::load_or_create(temp_dir.path()).unwrap();
+        assert_eq!(keyring.decrypt(&legacy).unwrap(), (1, b"legacy".to_vec()));
+        assert!(!temp_dir.path().join(LEGACY_KEY_FILE).exists());
+        assert!(temp_dir.path().join(KEYRING_FILE).exists());
+    }
+
+    #[test]
+    fn test_header_is_authenticated() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keyring = Keyring::load_or_create(temp_dir.path()).unwrap();
+        keyring.rotate().unwrap();
+        let mut data = keyring.encrypt(b"sessions").unwrap();
+
+        // Pointing the header at another key must not go unnoticed
+        data[HEADER_LEN - 1] = 1;
+        assert!(keyring.decrypt(&data).is_err());
+    }
+
+    #[test]
+    fn test_keys_derived_from_secrets() {
+        let old = Keyring::from_secrets("first operator secret", &[]).unwrap();
+        let data = old.encrypt(b"sessions").unwrap();
+
+        // The same secret always derives the same key
+        let again = Keyring::from_secrets("first operator secret", &[]).unwrap();
+        assert_eq!(again.decrypt(&data).unwrap().1, b"sessions");
+
+        // After changing the secret, the previous one still reads old data
+        let mut changed = Keyring::from_secrets(
+            "second operator secret",
+            &["first operator secret".to_string()],
+        )
+        .unwrap();
+        assert_ne!(changed.active_id(), old.active_id());
+        assert_eq!(
+            changed.decrypt(&data).unwrap(),
+            (old.active_id(), b"sessions".to_vec())
+        );
+        assert!(changed.rotate().is_err());
+        assert!(Keyring::from_secrets("", &[]).is_err());
+    }
+}
@@ -1,14 +1,21 @@
 // crates/backend-lib/src/auth/mod.rs
 
 //! Authentication module.
+pub mod authz;
+pub mod keyring;
 pub mod password;
 pub mod persistent_session;
 pub mod rate_limit;
 mod service;
 mod service_impl;
 pub mod session;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_session;
+pub mod store;
 pub mod token_generator;
 
+pub use authz::{AuthzError, ConnectionAuthz};
+pub use keyring::Keyring;
 pub use password::{
     hash_password, validate_password_strength, verify_password, PasswordRequirements,
     MIN_PASSWORD_LENGTH,
@@ -18,3 +25,6 @@ pub use rate_limit::AuthRateLimiter;
 pub use service::AuthService;
 pub use service_impl::DefaultAuth;
 pub use session::{SessionManager, SESSION_ABSOLUTE_TTL, SESSION_IDLE_TTL};
+#[cfg(feature = "sqlite")]
+pub use sqlite_session::SqliteSessionStore;
+pub use store::SessionStore;
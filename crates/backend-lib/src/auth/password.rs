@@ -1,10 +1,9 @@
-
 // crates/backend-lib/src/auth/password.rs
 
 //! Password hashing and verification.
 use argon2::Argon2;
 use scrypt::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, SaltString},
     Scrypt,
 };
 use zeroize::Zeroize;
@@ -41,13 +40,14 @@ pub fn hash_password(plain: &str) -> anyhow::Result<String> {
     Ok(hash)
 }
 
-/// Verify a password against a hash
+/// Verify a password against a hash.
+/// Hashes are produced with scrypt; Argon2 hashes are still accepted.
 pub fn verify_password(hash: &str, password: &str) -> bool {
     let Ok(parsed_hash) = PasswordHash::new(hash) else {
         return false;
     };
-    Argon2::default()
-        .verify_password(password.as_bytes(), &parsed_hash)
+    parsed_hash
+        .verify_password(&[&Scrypt, &Argon2::default()], password.as_bytes())
         .is_ok()
 }
 
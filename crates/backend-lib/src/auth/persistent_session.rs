@@ -3,47 +3,33 @@
 /** Persistent session storage with encryption
 This module extends the SessionManager with persistent storage capabilities,
 allowing sessions to survive server restarts. */
-use super::{session::SessionManager, AuthService};
-use crate::messages::Session;
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
+use super::{
+    keyring::Keyring,
+    session::{SessionEntry, SessionManager},
+    AuthService, SessionStore,
 };
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::health::Heartbeat;
+use crate::messages::{Session, SessionRole};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::{
     any::Any,
     collections::HashMap,
     fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant, SystemTime},
 };
-use tokio::{fs as tokio_fs, sync::RwLock, time};
-
-/// Security event types for logging
-#[derive(Debug, Clone, Copy)]
-enum SecurityEvent {
-    SessionLoaded,
-    SessionSaved,
-    SessionEncryptionFailed,
-    SessionDecryptionFailed,
-}
+use tokio::{fs as tokio_fs, sync::RwLock, task::JoinHandle, time};
 
-/// Log a security event
-fn log_security_event(event: SecurityEvent, details: &str) {
-    let timestamp = chrono::Local::now()
-        .format("%Y-%m-%d %H:%M:%S%.3f")
-        .to_string();
-    let event_str = format!("{:?}", event);
-    println!("[SECURITY] [{timestamp}] [{event_str}] {details}");
-}
+/// How often the auto-save task checks whether to save
+const AUTO_SAVE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Session entry that can be serialized
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct PersistentSessionEntry {
+pub(crate) struct PersistentSessionEntry {
     /// The session data
     session: Session,
     /// When the session was created (as UTC timestamp for serialization)
@@ -54,6 +40,44 @@ struct PersistentSessionEntry {
     csrf_token: String,
 }
 
+impl PersistentSessionEntry {
+    /// Capture an in-memory entry with wall-clock timestamps
+    pub(crate) fn from_entry(entry: &SessionEntry) -> Self {
+        // Convert Instant to DateTime
+        let created_at = SystemTime::now() - entry.created_at_duration;
+        let last_active = SystemTime::now() - entry.last_active_duration;
+
+        Self {
+            session: entry.session.clone(),
+            created_at: DateTime::from(created_at),
+            last_active: DateTime::from(last_active),
+            csrf_token: entry.csrf_token.clone(),
+        }
+    }
+
+    /// Add the entry to `manager`, skipping entries with timestamps in the future
+    pub(crate) async fn restore(self, manager: &SessionManager) -> Result<(), anyhow::Error> {
+        let now = Utc::now();
+        let created_duration = now.signed_duration_since(self.created_at).to_std();
+        let last_active_duration = now.signed_duration_since(self.last_active).to_std();
+
+        if let (Ok(created_at_duration), Ok(last_active_duration)) =
+            (created_duration, last_active_duration)
+        {
+            manager
+                .add_session(
+                    self.session.token.clone(),
+                    self.session,
+                    created_at_duration,
+                    last_active_duration,
+                    self.csrf_token,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
 /// Persistent session manager with encryption
 #[derive(Debug, Clone)]
 pub struct PersistentSessionManager {
@@ -61,65 +85,26 @@ pub struct PersistentSessionManager {
     inner: SessionManager,
     /// Path to store sessions
     storage_path: PathBuf,
-    /// Encryption key
-    encryption_key: [u8; 32],
+    /// Versioned encryption keys
+    keyring: Arc<RwLock<Keyring>>,
     /// Auto-save interval
     save_interval: Duration,
     /// Last save timestamp
     last_save: Arc<RwLock<Instant>>,
+    /// Beaten by the auto-save task after each check that did not fail
+    auto_save: Heartbeat,
+    /// The auto-save task, taken when the manager shuts down
+    auto_save_task: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 impl PersistentSessionManager {
     /** Create a new persistent session manager
     # Arguments
-    * `storage_path` - Path to store sessions
-    * `encryption_key` - 32-byte key for AES-GCM encryption (if None, a random key will be generated and saved) */
+    * `storage_path` - Path to store sessions; its keyring is created if missing */
     pub async fn new<P: AsRef<Path>>(storage_path: P) -> Result<Self, anyhow::Error> {
-        // Create directory if it doesn't exist
-        let storage_path = storage_path.as_ref().to_path_buf();
         fs::create_dir_all(&storage_path)?;
-
-        // Load or generate encryption key
-        let key_path = storage_path.join("session_key");
-        let encryption_key = if key_path.exists() {
-            // Load existing key
-            let key_data = fs::read(&key_path)?;
-            let mut key = [0u8; 32];
-            if key_data.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid encryption key length"));
-            }
-            key.copy_from_slice(&key_data);
-            key
-        } else {
-            // Generate new key
-            let mut key = [0u8; 32];
-            OsRng.fill_bytes(&mut key);
-            fs::write(&key_path, &key)?;
-            key
-        };
-
-        // Create session manager
-        let inner = SessionManager::new();
-
-        // Create persistent manager
-        let manager = Self {
-            inner,
-            storage_path,
-            encryption_key,
-            save_interval: Duration::from_secs(60),
-            last_save: Arc::new(RwLock::new(Instant::now())),
-        };
-
-        // Load sessions
-        manager.load_sessions().await?;
-
-        // Start auto-save task
-        let cloned = manager.clone();
-        tokio::spawn(async move {
-            cloned.auto_save_task().await;
-        });
-
-        Ok(manager)
+        let keyring = Keyring::load_or_create(&storage_path)?;
+        Self::new_with_keyring(storage_path, keyring).await
     }
 
     /** Create a new persistent session manager with custom timeouts
@@ -132,39 +117,45 @@ impl PersistentSessionManager {
         absolute_ttl: Duration,
         idle_ttl: Duration,
     ) -> Result<Self, anyhow::Error> {
-        // Create directory if it doesn't exist
-        let storage_path = storage_path.as_ref().to_path_buf();
         fs::create_dir_all(&storage_path)?;
+        let keyring = Keyring::load_or_create(&storage_path)?;
+        let inner = SessionManager::new_with_timeouts(absolute_ttl, idle_ttl);
+        Self::start(storage_path.as_ref().to_path_buf(), keyring, inner).await
+    }
 
-        // Load or generate encryption key
-        let key_path = storage_path.join("session_key");
-        let encryption_key = if key_path.exists() {
-            // Load existing key
-            let key_data = fs::read(&key_path)?;
-            let mut key = [0u8; 32];
-            if key_data.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid encryption key length"));
-            }
-            key.copy_from_slice(&key_data);
-            key
-        } else {
-            // Generate new key
-            let mut key = [0u8; 32];
-            OsRng.fill_bytes(&mut key);
-            fs::write(&key_path, &key)?;
-            key
-        };
+    /** Create a new persistent session manager using the given keyring
+    # Arguments
+    * `storage_path` - Path to store sessions
+    * `keyring` - Keys used to encrypt and decrypt `sessions.dat` */
+    pub async fn new_with_keyring<P: AsRef<Path>>(
+        storage_path: P,
+        keyring: Keyring,
+    ) -> Result<Self, anyhow::Error> {
+        Self::start(
+            storage_path.as_ref().to_path_buf(),
+            keyring,
+            SessionManager::new(),
+        )
+        .await
+    }
 
-        // Create session manager with custom timeouts
-        let inner = SessionManager::new_with_timeouts(absolute_ttl, idle_ttl);
+    /// Load persisted sessions and start auto-saving
+    async fn start(
+        storage_path: PathBuf,
+        keyring: Keyring,
+        inner: SessionManager,
+    ) -> Result<Self, anyhow::Error> {
+        fs::create_dir_all(&storage_path)?;
 
         // Create persistent manager
         let manager = Self {
             inner,
             storage_path,
-            encryption_key,
+            keyring: Arc::new(RwLock::new(keyring)),
             save_interval: Duration::from_secs(60),
             last_save: Arc::new(RwLock::new(Instant::now())),
+            auto_save: Heartbeat::new(),
+            auto_save_task: Arc::new(Mutex::new(None)),
         };
 
         // Load sessions
@@ -172,18 +163,33 @@ impl PersistentSessionManager {
 
         // Start auto-save task
         let cloned = manager.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             cloned.auto_save_task().await;
         });
+        *manager.auto_save_task.lock().unwrap() = Some(task);
 
         Ok(manager)
     }
 
+    /** Switch to a new encryption key and re-encrypt `sessions.dat` with it.
+    Returns the ID of the new key. Older keys stay in the keyring. */
+    pub async fn rotate_encryption_key(&self) -> Result<u32, anyhow::Error> {
+        let key_id = self.keyring.write().await.rotate()?;
+        self.save_sessions().await?;
+
+        audit::record(
+            AuditEvent::new(AuditKind::KeyRotated)
+                .with_detail(format!("Session encryption key rotated to key {}", key_id)),
+        );
+        Ok(key_id)
+    }
+
     /// Save sessions to disk
     pub async fn save_sessions(&self) -> Result<(), anyhow::Error> {
         // Get sessions from inner manager
         let sessions = self.inner.get_all_sessions().await?;
-        if sessions.is_empty() {
+        let sessions_file = self.storage_path.join("sessions.dat");
+        if sessions.is_empty() && !sessions_file.exists() {
             return Ok(());
         }
 
@@ -192,61 +198,45 @@ impl PersistentSessionManager {
         let session_count = sessions.len();
 
         for (token, entry) in &sessions {
-            // Convert Instant to DateTime
-            let created_at = SystemTime::now() - entry.created_at_duration;
-            let last_active = SystemTime::now() - entry.last_active_duration;
-
-            // Create persistent entry
-            let persistent_entry = PersistentSessionEntry {
-                session: entry.session.clone(),
-                created_at: DateTime::from(created_at),
-                last_active: DateTime::from(last_active),
-                csrf_token: entry.csrf_token.clone(),
-            };
-
-            persistent_entries.insert(token.clone(), persistent_entry);
+            persistent_entries.insert(token.clone(), PersistentSessionEntry::from_entry(entry));
         }
 
         // Serialize
         let json = serde_json::to_string(&persistent_entries)?;
 
-        // Encrypt
-        let cipher = Aes256Gcm::new_from_slice(&self.encryption_key)?;
-        let nonce_bytes = generate_nonce();
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        let encrypted_data = match cipher.encrypt(nonce, json.as_bytes()) {
+        // Encrypt with the active key; the key ID goes in the file header
+        let combined = match self.keyring.read().await.encrypt(json.as_bytes()) {
             Ok(data) => data,
             Err(err) => {
-                log_security_event(
-                    SecurityEvent::SessionEncryptionFailed,
-                    &format!("Failed to encrypt sessions: {}", err),
+                audit::record(
+                    AuditEvent::new(AuditKind::SessionStoreError)
+                        .with_detail(format!("Failed to encrypt sessions: {}", err)),
                 );
                 return Err(anyhow::anyhow!("Encryption failed"));
             },
         };
 
-        // Combine nonce and encrypted data
-        let mut combined = Vec::with_capacity(nonce_bytes.len() + encrypted_data.len());
-        combined.extend_from_slice(&nonce_bytes);
-        combined.extend_from_slice(&encrypted_data);
-
-        // Save to file
-        let sessions_file = self.storage_path.join("sessions.dat");
-        tokio_fs::write(&sessions_file, &combined).await?;
+        // Replace the file atomically so a crash never leaves it torn
+        crate::storage::write_atomic(&sessions_file, &combined).await?;
 
         // Update last save timestamp
         *self.last_save.write().await = Instant::now();
 
-        // Log session save
-        log_security_event(
-            SecurityEvent::SessionSaved,
-            &format!("Saved {} sessions to disk", session_count),
-        );
+        tracing::debug!("Saved {} sessions to disk", session_count);
 
         Ok(())
     }
 
+    /// Stop auto-saving and write out every session
+    pub async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        let task = self.auto_save_task.lock().unwrap().take();
+        if let Some(task) = task {
+            task.abort();
+            let _ = task.await;
+        }
+        self.save_sessions().await
+    }
+
     /// Load sessions from disk
     async fn load_sessions(&self) -> Result<(), anyhow::Error> {
         let sessions_file = self.storage_path.join("sessions.dat");
@@ -256,25 +246,14 @@ impl PersistentSessionManager {
 
         // Read file
         let combined = tokio_fs::read(&sessions_file).await?;
-        if combined.len() < 12 {
-            // Nonce is 12 bytes
-            return Err(anyhow::anyhow!("Invalid session file"));
-        }
-
-        // Split nonce and encrypted data
-        let nonce_bytes = &combined[..12];
-        let encrypted_data = &combined[12..];
 
-        // Decrypt
-        let cipher = Aes256Gcm::new_from_slice(&self.encryption_key)?;
-        let nonce = Nonce::from_slice(nonce_bytes);
-
-        let decrypted_data = match cipher.decrypt(nonce, encrypted_data) {
-            Ok(data) => data,
+        // Decrypt with whichever key wrote the file
+        let (key_id, decrypted_data) = match self.keyring.read().await.decrypt(&combined) {
+            Ok(decrypted) => decrypted,
             Err(err) => {
-                log_security_event(
-                    SecurityEvent::SessionDecryptionFailed,
-                    &format!("Failed to decrypt sessions: {}", err),
+                audit::record(
+                    AuditEvent::new(AuditKind::SessionStoreError)
+                        .with_detail(format!("Failed to decrypt sessions: {}", err)),
                 );
                 return Err(anyhow::anyhow!("Decryption failed"));
             },
@@ -287,34 +266,22 @@ impl PersistentSessionManager {
         let entry_count = persistent_entries.len();
 
         // Import sessions to inner manager
-        for (token, entry) in persistent_entries {
-            // Skip expired sessions
-            let now = Utc::now();
-            let created_duration = now.signed_duration_since(entry.created_at);
-            let last_active_duration = now.signed_duration_since(entry.last_active);
-
-            if created_duration.to_std().is_ok() && last_active_duration.to_std().is_ok() {
-                let created_at_duration = created_duration.to_std()?;
-                let last_active_duration = last_active_duration.to_std()?;
-
-                // Add to inner manager
-                self.inner
-                    .add_session(
-                        token,
-                        entry.session,
-                        created_at_duration,
-                        last_active_duration,
-                        entry.csrf_token,
-                    )
-                    .await?;
-            }
+        for entry in persistent_entries.into_values() {
+            entry.restore(&self.inner).await?;
         }
 
-        // Log session load
-        log_security_event(
-            SecurityEvent::SessionLoaded,
-            &format!("Loaded {} sessions from disk", entry_count),
-        );
+        tracing::info!("Loaded {} sessions from disk", entry_count);
+
+        // Move data written under an older key onto the active one
+        let active_id = self.keyring.read().await.active_id();
+        if key_id != active_id {
+            self.save_sessions().await?;
+            tracing::info!(
+                "Re-encrypted sessions from key {} to key {}",
+                key_id,
+                active_id
+            );
+        }
 
         Ok(())
     }
@@ -323,7 +290,7 @@ impl PersistentSessionManager {
     async fn auto_save_task(&self) {
         loop {
             // Sleep for a while
-            time::sleep(Duration::from_secs(10)).await;
+            time::sleep(AUTO_SAVE_CHECK_INTERVAL).await;
 
             // Check if we should save
             let last_save = *self.last_save.read().await;
@@ -331,9 +298,11 @@ impl PersistentSessionManager {
             if now.duration_since(last_save) > self.save_interval {
                 // Save sessions
                 if let Err(err) = self.save_sessions().await {
-                    eprintln!("Error saving sessions: {}", err);
+                    tracing::error!("Error saving sessions: {}", err);
+                    continue;
                 }
             }
+            self.auto_save.beat();
         }
     }
 
@@ -353,7 +322,28 @@ impl PersistentSessionManager {
 
         // Save sessions after creation
         if let Err(err) = self.save_sessions().await {
-            eprintln!("Error saving sessions after creation: {}", err);
+            tracing::error!("Error saving sessions after creation: {}", err);
+        }
+
+        session
+    }
+
+    /// Create a new session with the given role
+    pub async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session {
+        let session = self
+            .inner
+            .create_session_with_role(meet_id, location_name, priority, role)
+            .await;
+
+        // Save sessions after creation
+        if let Err(err) = self.save_sessions().await {
+            tracing::error!("Error saving sessions after creation: {}", err);
         }
 
         session
@@ -380,7 +370,7 @@ impl PersistentSessionManager {
 
         // Save sessions after removal
         if let Err(err) = self.save_sessions().await {
-            eprintln!("Error saving sessions after removal: {}", err);
+            tracing::error!("Error saving sessions after removal: {}", err);
         }
     }
 
@@ -391,23 +381,45 @@ impl PersistentSessionManager {
         // Save sessions after rotation
         if result.is_some() {
             if let Err(err) = self.save_sessions().await {
-                eprintln!("Error saving sessions after rotation: {}", err);
+                tracing::error!("Error saving sessions after rotation: {}", err);
             }
         }
 
         result
     }
 
+    /// Remove every session of a meet except `keep_token`, returning the removed sessions
+    pub async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        let removed = self
+            .inner
+            .remove_sessions_for_meet(meet_id, keep_token)
+            .await;
+
+        // Save sessions after removal
+        if !removed.is_empty() {
+            if let Err(err) = self.save_sessions().await {
+                tracing::error!("Error saving sessions after removal: {}", err);
+            }
+        }
+
+        removed
+    }
+
     /// Cleanup task that runs periodically to remove expired sessions
     pub async fn cleanup_expired_sessions(&self) {
         self.inner.cleanup_expired_sessions().await;
 
         // Save sessions after cleanup
         if let Err(err) = self.save_sessions().await {
-            eprintln!("Error saving sessions after cleanup: {}", err);
+            tracing::error!("Error saving sessions after cleanup: {}", err);
         }
     }
 
+    /// Unexpired sessions belonging to a meet
+    pub async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        self.inner.sessions_for_meet(meet_id).await
+    }
+
     /// Return count of active sessions
     pub async fn active_session_count(&self) -> usize {
         self.inner.active_session_count().await
@@ -421,13 +433,6 @@ impl PersistentSessionManager {
     }
 }
 
-/// Generate a random nonce for AES-GCM
-fn generate_nonce() -> [u8; 12] {
-    let mut nonce = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce);
-    nonce
-}
-
 #[async_trait]
 impl AuthService for PersistentSessionManager {
     async fn new_session(&self, meet_id: String, location_name: String, priority: u8) -> String {
@@ -443,11 +448,113 @@ impl AuthService for PersistentSessionManager {
         self.validate_session(token).await
     }
 
+    async fn new_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> String {
+        self.create_session_with_role(meet_id, location_name, priority, role)
+            .await
+            .token
+    }
+
+    async fn remove_session(&self, token: &str) {
+        self.remove_session(token).await;
+    }
+
+    async fn rotate_session(&self, token: &str) -> Option<String> {
+        self.rotate_session(token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        self.sessions_for_meet(meet_id).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+#[async_trait]
+impl SessionStore for PersistentSessionManager {
+    async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session {
+        PersistentSessionManager::create_session_with_role(
+            self,
+            meet_id,
+            location_name,
+            priority,
+            role,
+        )
+        .await
+    }
+
+    async fn get_session(&self, token: &str) -> Option<Session> {
+        PersistentSessionManager::get_session(self, token).await
+    }
+
+    async fn validate_session(&self, token: &str) -> bool {
+        PersistentSessionManager::validate_session(self, token).await
+    }
+
+    async fn remove_session(&self, token: &str) {
+        PersistentSessionManager::remove_session(self, token).await;
+    }
+
+    async fn rotate_session(&self, old_token: &str) -> Option<String> {
+        PersistentSessionManager::rotate_session(self, old_token).await
+    }
+
+    async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        PersistentSessionManager::remove_sessions_for_meet(self, meet_id, keep_token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        PersistentSessionManager::sessions_for_meet(self, meet_id).await
+    }
+
+    async fn get_csrf_token(&self, token: &str) -> Option<String> {
+        PersistentSessionManager::get_csrf_token(self, token).await
+    }
+
+    async fn verify_csrf_token(&self, session_token: &str, csrf_token: &str) -> bool {
+        PersistentSessionManager::verify_csrf_token(self, session_token, csrf_token).await
+    }
+
+    async fn cleanup_expired_sessions(&self) {
+        PersistentSessionManager::cleanup_expired_sessions(self).await;
+    }
+
+    async fn active_session_count(&self) -> usize {
+        PersistentSessionManager::active_session_count(self).await
+    }
+
+    async fn flush(&self) -> Result<(), anyhow::Error> {
+        self.save_sessions().await
+    }
+
+    async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        PersistentSessionManager::shutdown(self).await
+    }
+
+    async fn rotate_encryption_key(&self) -> Result<Option<u32>, anyhow::Error> {
+        PersistentSessionManager::rotate_encryption_key(self)
+            .await
+            .map(Some)
+    }
+
+    fn auto_save_heartbeat(&self) -> Option<(Heartbeat, Duration)> {
+        Some((self.auto_save.clone(), AUTO_SAVE_CHECK_INTERVAL))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -462,24 +569,8 @@ mod tests {
         let storage_path = temp_dir.path().to_path_buf();
         fs::create_dir_all(&storage_path)?;
 
-        // Load or generate encryption key
-        let key_path = storage_path.join("session_key");
-        let encryption_key = if key_path.exists() {
-            // Load existing key
-            let key_data = fs::read(&key_path)?;
-            let mut key = [0u8; 32];
-            if key_data.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid encryption key length"));
-            }
-            key.copy_from_slice(&key_data);
-            key
-        } else {
-            // Generate new key
-            let mut key = [0u8; 32];
-            OsRng.fill_bytes(&mut key);
-            fs::write(&key_path, key)?;
-            key
-        };
+        // Load or generate the keyring
+        let keyring = Keyring::load_or_create(&storage_path)?;
 
         // Create session manager
         let inner = SessionManager::new_with_timeouts(
@@ -491,9 +582,11 @@ mod tests {
         let manager = PersistentSessionManager {
             inner,
             storage_path,
-            encryption_key,
+            keyring: Arc::new(RwLock::new(keyring)),
             save_interval: Duration::from_secs(600), // 10 minutes - effectively disable auto-save
             last_save: Arc::new(RwLock::new(Instant::now())),
+            auto_save: Heartbeat::new(),
+            auto_save_task: Arc::new(Mutex::new(None)),
         };
 
         // Load sessions
@@ -571,6 +664,27 @@ mod tests {
         .expect("Test timed out");
     }
 
+    #[tokio::test]
+    async fn test_encryption_key_rotation() {
+        let temp_dir = TempDir::new().unwrap();
+        let sessions_file = temp_dir.path().join("sessions.dat");
+
+        let manager = setup_test_manager(&temp_dir).await.unwrap();
+        let session = manager
+            .create_session("test-meet".to_string(), "Test Location".to_string(), 5)
+            .await;
+        let before = fs::read(&sessions_file).unwrap();
+
+        assert_eq!(manager.rotate_encryption_key().await.unwrap(), 2);
+
+        // The file now names the new key in its header and still loads
+        let after = fs::read(&sessions_file).unwrap();
+        assert_ne!(before[4..8], after[4..8]);
+        assert_eq!(after[4..8], 2u32.to_be_bytes());
+        let reloaded = setup_test_manager(&temp_dir).await.unwrap();
+        assert!(reloaded.get_session(&session.token).await.is_some());
+    }
+
     #[tokio::test]
     async fn test_session_removal() {
         timeout(Duration::from_secs(5), async {
@@ -2,6 +2,7 @@
 
 //! Rate limiting for authentication attempts.
 
+use crate::audit::{self, AuditEvent, AuditKind};
 use dashmap::DashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
@@ -98,15 +99,14 @@ impl AuthRateLimiter {
 
             entry.lockout_expiry = Some(now + lockout_duration);
 
-            // Log the lockout with severity based on attempts
-            if attempts_over_max > 0 {
-                println!(
-                    "SECURITY WARNING: IP {} locked out for {} seconds after {} failed attempts",
-                    ip, lockout_secs, entry.failed_attempts
-                );
-            } else {
-                println!("IP {} locked out for {} seconds", ip, lockout_secs);
-            }
+            audit::record(
+                AuditEvent::new(AuditKind::LockedOut)
+                    .with_ip(Some(ip))
+                    .with_detail(format!(
+                        "Locked out for {} seconds after {} failed attempts",
+                        lockout_secs, entry.failed_attempts
+                    )),
+            );
         }
     }
 
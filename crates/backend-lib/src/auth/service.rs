@@ -1,7 +1,7 @@
 // crates/backend-lib/src/auth/service.rs
 
 //! This module defines the `AuthService` trait, which is used for authentication
-use crate::messages::Session;
+use crate::messages::{Session, SessionRole};
 use async_trait::async_trait;
 use std::any::Any;
 
@@ -10,6 +10,18 @@ pub trait AuthService: Send + Sync {
     async fn new_session(&self, meet_id: String, location_name: String, priority: u8) -> String;
     async fn get_session(&self, token: &str) -> Option<Session>;
     async fn validate_session(&self, token: &str) -> bool;
+    async fn new_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> String;
+    async fn remove_session(&self, token: &str);
+    /// Replace a session's token, returning the new one
+    async fn rotate_session(&self, token: &str) -> Option<String>;
+    /// Unexpired sessions belonging to a meet
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session>;
 
     /// Convert self to Any for downcasting
     fn as_any(&self) -> &dyn Any;
@@ -1,34 +1,39 @@
 // crates/backend-lib/src/auth/service_impl.rs
 
 //! Authentication service implementation
-use crate::auth::{AuthRateLimiter, AuthService, PersistentSessionManager};
+use crate::auth::{AuthRateLimiter, AuthService, SessionStore};
 use crate::error::AppError;
-use crate::messages::Session;
+use crate::messages::{Session, SessionRole};
 use async_trait::async_trait;
 use std::any::Any;
 use std::net::IpAddr;
 use std::sync::Arc;
 
 pub struct DefaultAuth {
-    sm: PersistentSessionManager,
+    sm: Arc<dyn SessionStore>,
     rate_limiter: Arc<AuthRateLimiter>,
 }
 
 impl DefaultAuth {
-    pub fn new(sm: PersistentSessionManager) -> Self {
+    pub fn new<T: SessionStore + 'static>(sm: T) -> Self {
         Self {
-            sm,
+            sm: Arc::new(sm),
             rate_limiter: Arc::new(AuthRateLimiter::default()),
         }
     }
 
     pub fn new_with_rate_limiter(
-        sm: PersistentSessionManager,
+        sm: Arc<dyn SessionStore>,
         rate_limiter: Arc<AuthRateLimiter>,
     ) -> Self {
         Self { sm, rate_limiter }
     }
 
+    /// The session store behind this service
+    pub fn store(&self) -> &Arc<dyn SessionStore> {
+        &self.sm
+    }
+
     /// Check if authentication is allowed for this IP
     pub fn check_auth_rate_limit(&self, ip: IpAddr) -> Result<(), AppError> {
         if !self.rate_limiter.check_rate_limit(ip) {
@@ -66,6 +71,32 @@ impl AuthService for DefaultAuth {
         self.sm.validate_session(token).await
     }
 
+    async fn new_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> String {
+        let session = self
+            .sm
+            .create_session_with_role(meet_id, location_name, priority, role)
+            .await;
+        session.token
+    }
+
+    async fn remove_session(&self, token: &str) {
+        self.sm.remove_session(token).await;
+    }
+
+    async fn rotate_session(&self, token: &str) -> Option<String> {
+        self.sm.rotate_session(token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        self.sm.sessions_for_meet(meet_id).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
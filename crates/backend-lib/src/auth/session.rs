@@ -1,10 +1,10 @@
 // crates/backend-lib/src/auth/session.rs
 
 //! Session token handling and management.
-use super::{token_generator::generate_secure_token, AuthService};
-use crate::messages::Session;
+use super::{token_generator::generate_secure_token, AuthService, SessionStore};
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::messages::{Session, SessionRole};
 use async_trait::async_trait;
-use chrono;
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -17,26 +17,11 @@ pub const SESSION_ABSOLUTE_TTL: std::time::Duration = std::time::Duration::from_
 /// Idle timeout for sessions
 pub const SESSION_IDLE_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60); // 1 hour
 
-/// Security event types for logging
-#[derive(Debug, Clone, Copy)]
-enum SecurityEvent {
-    SessionCreated,
-    SessionValidated,
-    SessionExpired,
-    SessionRemoved,
-    SessionRotated,
-    InvalidSessionAccess,
-    CsrfValidationFailed,
-    CsrfValidationSuccess,
-}
-
-/// Log a security event
-fn log_security_event(event: SecurityEvent, details: &str) {
-    let timestamp = chrono::Local::now()
-        .format("%Y-%m-%d %H:%M:%S%.3f")
-        .to_string();
-    let event_str = format!("{:?}", event);
-    println!("[SECURITY] [{timestamp}] [{event_str}] {details}");
+/// Audit event about one session; never includes its token
+fn session_event(kind: AuditKind, session: &Session) -> AuditEvent {
+    AuditEvent::new(kind)
+        .with_meet(&session.meet_id)
+        .with_location(&session.location_name)
 }
 
 /// Session entry with enhanced security features
@@ -98,6 +83,18 @@ impl SessionManager {
         meet_id: String,
         location_name: String,
         priority: u8,
+    ) -> Session {
+        self.create_session_with_role(meet_id, location_name, priority, SessionRole::Station)
+            .await
+    }
+
+    /// Create a new session with the given role
+    pub async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
     ) -> Session {
         // Create a new session with a secure token instead of UUID
         let token = generate_secure_token();
@@ -108,6 +105,7 @@ impl SessionManager {
             meet_id: meet_id.clone(),
             location_name: location_name.clone(),
             priority,
+            role,
         };
 
         let now = Instant::now();
@@ -125,13 +123,9 @@ impl SessionManager {
             },
         );
 
-        // Log session creation
-        log_security_event(
-            SecurityEvent::SessionCreated,
-            &format!(
-                "Created new session for meet: {}, location: {}",
-                meet_id, location_name
-            ),
+        audit::record(
+            session_event(AuditKind::SessionCreated, &session)
+                .with_detail(format!("{:?} session, priority {}", role, priority)),
         );
 
         session
@@ -164,13 +158,10 @@ impl SessionManager {
             },
         );
 
-        // Log session creation
-        log_security_event(
-            SecurityEvent::SessionCreated,
-            &format!(
-                "Restored session for meet: {}, location: {}",
-                session.meet_id, session.location_name
-            ),
+        tracing::debug!(
+            "Restored session for meet: {}, location: {}",
+            session.meet_id,
+            session.location_name
         );
 
         Ok(())
@@ -203,6 +194,18 @@ impl SessionManager {
         Ok(result)
     }
 
+    /// Get a single session with up-to-date durations (for persistent storage)
+    pub async fn get_entry(&self, token: &str) -> Option<SessionEntry> {
+        let sessions = self.sessions.read().await;
+        let now = Instant::now();
+
+        sessions.get(token).map(|entry| SessionEntry {
+            created_at_duration: now.duration_since(entry.created_at),
+            last_active_duration: now.duration_since(entry.last_active),
+            ..entry.clone()
+        })
+    }
+
     /// Get CSRF token for a session
     pub async fn get_csrf_token(&self, token: &str) -> Option<String> {
         // Acquire a write lock immediately to avoid read->write deadlock
@@ -214,12 +217,6 @@ impl SessionManager {
             return Some(entry.csrf_token.clone());
         }
 
-        // Log invalid session access
-        log_security_event(
-            SecurityEvent::InvalidSessionAccess,
-            &format!("Attempted to get CSRF token for invalid session: {}", token),
-        );
-
         None
     }
 
@@ -235,32 +232,16 @@ impl SessionManager {
             if now.duration_since(entry.created_at) > self.absolute_ttl
                 || now.duration_since(entry.last_active) > self.idle_ttl
             {
-                // Log session expiration
-                log_security_event(
-                    SecurityEvent::SessionExpired,
-                    &format!("Session expired for meet: {}", entry.session.meet_id),
-                );
+                audit::record(session_event(AuditKind::SessionExpired, &entry.session));
                 return None;
             }
 
             // Update last active time (sliding window)
             entry.last_active = now;
 
-            // Log successful session validation
-            log_security_event(
-                SecurityEvent::SessionValidated,
-                &format!("Session validated for meet: {}", entry.session.meet_id),
-            );
-
             return Some(entry.session.clone());
         }
 
-        // Log invalid session access
-        log_security_event(
-            SecurityEvent::InvalidSessionAccess,
-            &format!("Attempted to get invalid session: {}", token),
-        );
-
         None
     }
 
@@ -276,11 +257,7 @@ impl SessionManager {
             if now.duration_since(entry.created_at) > self.absolute_ttl
                 || now.duration_since(entry.last_active) > self.idle_ttl
             {
-                // Log session expiration
-                log_security_event(
-                    SecurityEvent::SessionExpired,
-                    &format!("Session expired for meet: {}", entry.session.meet_id),
-                );
+                audit::record(session_event(AuditKind::SessionExpired, &entry.session));
                 return false;
             }
 
@@ -290,30 +267,14 @@ impl SessionManager {
             return true;
         }
 
-        // Log invalid session access
-        log_security_event(
-            SecurityEvent::InvalidSessionAccess,
-            &format!("Attempted to validate invalid session: {}", token),
-        );
-
         false
     }
 
     /// Remove a session by token
     pub async fn remove_session(&self, token: &str) {
-        let meet_id = if let Some(entry) = self.sessions.read().await.get(token) {
-            entry.session.meet_id.clone()
-        } else {
-            "unknown".to_string()
-        };
-
-        self.sessions.write().await.remove(token);
-
-        // Log session removal
-        log_security_event(
-            SecurityEvent::SessionRemoved,
-            &format!("Session removed for meet: {}", meet_id),
-        );
+        if let Some(entry) = self.sessions.write().await.remove(token) {
+            audit::record(session_event(AuditKind::SessionRevoked, &entry.session));
+        }
     }
 
     /// Rotate the session token for enhanced security
@@ -332,6 +293,7 @@ impl SessionManager {
                 meet_id: entry.session.meet_id.clone(),
                 location_name: entry.session.location_name.clone(),
                 priority: entry.session.priority,
+                role: entry.session.role,
             };
 
             // Create new entry with updated fields
@@ -348,24 +310,52 @@ impl SessionManager {
             // Insert new session
             sessions.insert(new_token.clone(), new_entry);
 
-            // Log session rotation
-            log_security_event(
-                SecurityEvent::SessionRotated,
-                &format!("Session rotated for meet: {}", entry.session.meet_id),
-            );
+            audit::record(session_event(AuditKind::SessionRotated, &new_session));
 
             return Some(new_token);
         }
 
-        // Log invalid session access
-        log_security_event(
-            SecurityEvent::InvalidSessionAccess,
-            &format!("Attempted to rotate invalid session: {}", old_token),
-        );
-
         None
     }
 
+    /// Remove every session of a meet except `keep_token`, returning the removed sessions
+    pub async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        let mut sessions = self.sessions.write().await;
+        let tokens: Vec<String> = sessions
+            .iter()
+            .filter(|(token, entry)| entry.session.meet_id == meet_id && *token != keep_token)
+            .map(|(token, _)| token.clone())
+            .collect();
+
+        let removed: Vec<Session> = tokens
+            .iter()
+            .filter_map(|token| sessions.remove(token))
+            .map(|entry| entry.session)
+            .collect();
+
+        for session in &removed {
+            audit::record(session_event(AuditKind::SessionRevoked, session));
+        }
+
+        removed
+    }
+
+    /// Unexpired sessions belonging to a meet
+    pub async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        let sessions = self.sessions.read().await;
+        let now = Instant::now();
+
+        sessions
+            .values()
+            .filter(|entry| {
+                entry.session.meet_id == meet_id
+                    && now.duration_since(entry.created_at) <= self.absolute_ttl
+                    && now.duration_since(entry.last_active) <= self.idle_ttl
+            })
+            .map(|entry| entry.session.clone())
+            .collect()
+    }
+
     /// Cleanup task that runs periodically to remove expired sessions
     pub async fn cleanup_expired_sessions(&self) {
         let mut sessions = self.sessions.write().await;
@@ -380,13 +370,14 @@ impl SessionManager {
             let retain = !absolute_expired && !idle_expired;
             if !retain {
                 expired_count += 1;
+                audit::record(session_event(AuditKind::SessionExpired, &entry.session));
             }
 
             retain
         });
 
         // Log the number of active sessions after cleanup
-        println!(
+        tracing::info!(
             "Session cleanup complete: {} sessions expired, {} active sessions remain",
             expired_count,
             sessions.len()
@@ -410,13 +401,7 @@ impl SessionManager {
             if now.duration_since(entry.created_at) > self.absolute_ttl
                 || now.duration_since(entry.last_active) > self.idle_ttl
             {
-                log_security_event(
-                    SecurityEvent::SessionExpired,
-                    &format!(
-                        "Session expired during CSRF validation for meet: {}",
-                        entry.session.meet_id
-                    ),
-                );
+                audit::record(session_event(AuditKind::SessionExpired, &entry.session));
                 return false;
             }
 
@@ -426,32 +411,16 @@ impl SessionManager {
             // Verify CSRF token with constant-time comparison to prevent timing attacks
             let is_valid = constant_time_compare(&entry.csrf_token, csrf_token);
 
-            if is_valid {
-                log_security_event(
-                    SecurityEvent::CsrfValidationSuccess,
-                    &format!("CSRF token validated for meet: {}", entry.session.meet_id),
-                );
-            } else {
-                log_security_event(
-                    SecurityEvent::CsrfValidationFailed,
-                    &format!(
-                        "CSRF token validation failed for meet: {}",
-                        entry.session.meet_id
-                    ),
+            if !is_valid {
+                audit::record(
+                    session_event(AuditKind::PermissionDenied, &entry.session)
+                        .with_detail("CSRF token mismatch"),
                 );
             }
 
             return is_valid;
         }
 
-        log_security_event(
-            SecurityEvent::InvalidSessionAccess,
-            &format!(
-                "Attempted to verify CSRF token for invalid session: {}",
-                session_token
-            ),
-        );
-
         false
     }
 }
@@ -490,11 +459,88 @@ impl AuthService for SessionManager {
         SessionManager::validate_session(self, token).await
     }
 
+    async fn new_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> String {
+        self.create_session_with_role(meet_id, location_name, priority, role)
+            .await
+            .token
+    }
+
+    async fn remove_session(&self, token: &str) {
+        SessionManager::remove_session(self, token).await;
+    }
+
+    async fn rotate_session(&self, token: &str) -> Option<String> {
+        SessionManager::rotate_session(self, token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        SessionManager::sessions_for_meet(self, meet_id).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+#[async_trait]
+impl SessionStore for SessionManager {
+    async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session {
+        SessionManager::create_session_with_role(self, meet_id, location_name, priority, role).await
+    }
+
+    async fn get_session(&self, token: &str) -> Option<Session> {
+        SessionManager::get_session(self, token).await
+    }
+
+    async fn validate_session(&self, token: &str) -> bool {
+        SessionManager::validate_session(self, token).await
+    }
+
+    async fn remove_session(&self, token: &str) {
+        SessionManager::remove_session(self, token).await;
+    }
+
+    async fn rotate_session(&self, old_token: &str) -> Option<String> {
+        SessionManager::rotate_session(self, old_token).await
+    }
+
+    async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        SessionManager::remove_sessions_for_meet(self, meet_id, keep_token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        SessionManager::sessions_for_meet(self, meet_id).await
+    }
+
+    async fn get_csrf_token(&self, token: &str) -> Option<String> {
+        SessionManager::get_csrf_token(self, token).await
+    }
+
+    async fn verify_csrf_token(&self, session_token: &str, csrf_token: &str) -> bool {
+        SessionManager::verify_csrf_token(self, session_token, csrf_token).await
+    }
+
+    async fn cleanup_expired_sessions(&self) {
+        SessionManager::cleanup_expired_sessions(self).await;
+    }
+
+    async fn active_session_count(&self) -> usize {
+        SessionManager::active_session_count(self).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,534 @@
+// crates/backend-lib/src/auth/sqlite_session.rs
+
+/** Session store backed by an embedded SQLite database
+Sessions are served from memory like `PersistentSessionManager`, but each change
+is written as a single row instead of rewriting every session. Rows are keyed by
+a SHA-256 digest of the token and hold the session encrypted with the session
+keyring, so the database never contains a usable token. Idle timers are only
+written back by `flush`, which runs periodically and on cleanup. */
+use super::{
+    keyring::Keyring,
+    persistent_session::PersistentSessionEntry,
+    session::{SessionEntry, SessionManager},
+    SessionStore,
+};
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::health::Heartbeat;
+use crate::messages::{Session, SessionRole};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard},
+    time::Duration,
+};
+use tokio::{task::JoinHandle, time};
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS sessions (
+    id      TEXT PRIMARY KEY,
+    meet_id TEXT NOT NULL,
+    data    BLOB NOT NULL
+)";
+
+const UPSERT: &str = "INSERT INTO sessions (id, meet_id, data) VALUES (?1, ?2, ?3)
+    ON CONFLICT (id) DO UPDATE SET meet_id = excluded.meet_id, data = excluded.data";
+
+/// How often idle timers are written back
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An encrypted session row
+struct Row {
+    id: String,
+    meet_id: String,
+    data: Vec<u8>,
+}
+
+/// Database key for a session token
+fn token_id(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// SQLite-backed session store
+#[derive(Debug, Clone)]
+pub struct SqliteSessionStore {
+    /// Inner session manager
+    inner: SessionManager,
+    /// Open database connection
+    conn: Arc<Mutex<Connection>>,
+    /// Encryption keys for session rows
+    keyring: Arc<RwLock<Keyring>>,
+    /// Beaten by the flush task after each flush that did not fail
+    flushed: Heartbeat,
+    /// The flush task, taken when the store shuts down
+    flush_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl SqliteSessionStore {
+    /** Open the session database, creating it if needed
+    # Arguments
+    * `path` - Database file
+    * `keyring` - Keys used to encrypt and decrypt session rows */
+    pub async fn open<P: AsRef<Path>>(path: P, keyring: Keyring) -> Result<Self, anyhow::Error> {
+        Self::start(path.as_ref(), keyring, SessionManager::new()).await
+    }
+
+    /** Open the session database with custom timeouts
+    # Arguments
+    * `path` - Database file
+    * `keyring` - Keys used to encrypt and decrypt session rows
+    * `absolute_ttl` - Absolute session timeout
+    * `idle_ttl` - Idle session timeout */
+    pub async fn open_with_timeouts<P: AsRef<Path>>(
+        path: P,
+        keyring: Keyring,
+        absolute_ttl: Duration,
+        idle_ttl: Duration,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = SessionManager::new_with_timeouts(absolute_ttl, idle_ttl);
+        Self::start(path.as_ref(), keyring, inner).await
+    }
+
+    /// Load stored sessions and start flushing idle timers
+    async fn start(
+        path: &Path,
+        keyring: Keyring,
+        inner: SessionManager,
+    ) -> Result<Self, anyhow::Error> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let path = path.to_path_buf();
+        let conn = tokio::task::spawn_blocking(move || -> rusqlite::Result<Connection> {
+            let conn = Connection::open(path)?;
+            conn.pragma_update(None, "journal_mode", "WAL")?;
+            conn.execute(SCHEMA, [])?;
+            Ok(conn)
+        })
+        .await??;
+
+        let store = Self {
+            inner,
+            conn: Arc::new(Mutex::new(conn)),
+            keyring: Arc::new(RwLock::new(keyring)),
+            flushed: Heartbeat::new(),
+            flush_task: Arc::new(Mutex::new(None)),
+        };
+        store.load_sessions().await?;
+
+        let cloned = store.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = time::interval(FLUSH_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match cloned.flush().await {
+                    Ok(()) => cloned.flushed.beat(),
+                    Err(err) => tracing::error!("Error flushing sessions: {}", err),
+                }
+            }
+        });
+        *store.flush_task.lock().unwrap() = Some(task);
+
+        Ok(store)
+    }
+
+    /// Stop the flush task and write back every session
+    pub async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        let task = self.flush_task.lock().unwrap().take();
+        if let Some(task) = task {
+            task.abort();
+            let _ = task.await;
+        }
+        self.flush().await
+    }
+
+    /// Run `f` against the connection on the blocking thread pool
+    async fn with_conn<T, F>(&self, f: F) -> Result<T, anyhow::Error>
+    where
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow!("Session database lock poisoned"))?;
+            f(&mut conn).map_err(anyhow::Error::from)
+        })
+        .await?
+    }
+
+    /// The session keyring
+    fn keys(&self) -> Result<RwLockReadGuard<'_, Keyring>, anyhow::Error> {
+        self.keyring
+            .read()
+            .map_err(|_| anyhow!("Session keyring lock poisoned"))
+    }
+
+    /// Encrypt an in-memory entry into a row
+    fn encode(&self, token: &str, entry: &SessionEntry) -> Result<Row, anyhow::Error> {
+        let json = serde_json::to_vec(&PersistentSessionEntry::from_entry(entry))?;
+        Ok(Row {
+            id: token_id(token),
+            meet_id: entry.session.meet_id.clone(),
+            data: self.keys()?.encrypt(&json)?,
+        })
+    }
+
+    /** Switch to a new encryption key and re-encrypt every row with it.
+    Returns the ID of the new key. Older keys stay in the keyring. */
+    pub async fn rotate_encryption_key(&self) -> Result<u32, anyhow::Error> {
+        let key_id = self
+            .keyring
+            .write()
+            .map_err(|_| anyhow!("Session keyring lock poisoned"))?
+            .rotate()?;
+        self.flush().await?;
+
+        audit::record(
+            AuditEvent::new(AuditKind::KeyRotated)
+                .with_detail(format!("Session encryption key rotated to key {}", key_id)),
+        );
+        Ok(key_id)
+    }
+
+    /// Restore every stored session into memory
+    async fn load_sessions(&self) -> Result<(), anyhow::Error> {
+        let rows = self
+            .with_conn(|conn| {
+                let mut stmt = conn.prepare("SELECT id, data FROM sessions")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<(String, Vec<u8>)>>>()?;
+                Ok(rows)
+            })
+            .await?;
+
+        let active_id = self.keys()?.active_id();
+        let mut stale_key = false;
+        for (id, data) in &rows {
+            let decrypted = self.keys()?.decrypt(data);
+            let (key_id, json) = match decrypted {
+                Ok(decrypted) => decrypted,
+                Err(err) => {
+                    audit::record(
+                        AuditEvent::new(AuditKind::SessionStoreError)
+                            .with_detail(format!("Failed to decrypt session row {}: {}", id, err)),
+                    );
+                    return Err(anyhow!("Decryption failed"));
+                },
+            };
+            stale_key |= key_id != active_id;
+
+            let entry: PersistentSessionEntry = serde_json::from_slice(&json)?;
+            entry.restore(&self.inner).await?;
+        }
+
+        tracing::info!("Loaded {} sessions from the session database", rows.len());
+
+        // Move rows written under an older key onto the active one
+        if stale_key {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Write one session's row
+    async fn write_session(&self, token: &str) -> Result<(), anyhow::Error> {
+        let Some(entry) = self.inner.get_entry(token).await else {
+            return Ok(());
+        };
+        let row = self.encode(token, &entry)?;
+        self.with_conn(move |conn| {
+            conn.execute(UPSERT, params![row.id, row.meet_id, row.data])
+                .map(|_| ())
+        })
+        .await
+    }
+
+    /// Delete the rows of the given tokens
+    async fn delete_sessions(&self, tokens: Vec<String>) -> Result<(), anyhow::Error> {
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            for token in &tokens {
+                tx.execute("DELETE FROM sessions WHERE id = ?1", [token_id(token)])?;
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    /// Create a new session with the given role
+    pub async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session {
+        let session = self
+            .inner
+            .create_session_with_role(meet_id, location_name, priority, role)
+            .await;
+
+        if let Err(err) = self.write_session(&session.token).await {
+            tracing::error!("Error saving session after creation: {}", err);
+        }
+
+        session
+    }
+
+    /// Remove a session by token
+    pub async fn remove_session(&self, token: &str) {
+        self.inner.remove_session(token).await;
+
+        if let Err(err) = self.delete_sessions(vec![token.to_string()]).await {
+            tracing::error!("Error deleting session after removal: {}", err);
+        }
+    }
+
+    /// Rotate the session token, replacing its row in one transaction
+    pub async fn rotate_session(&self, old_token: &str) -> Option<String> {
+        let new_token = self.inner.rotate_session(old_token).await?;
+
+        let old_id = token_id(old_token);
+        let result = match self.inner.get_entry(&new_token).await {
+            Some(entry) => match self.encode(&new_token, &entry) {
+                Ok(row) => {
+                    self.with_conn(move |conn| {
+                        let tx = conn.transaction()?;
+                        tx.execute("DELETE FROM sessions WHERE id = ?1", [old_id])?;
+                        tx.execute(UPSERT, params![row.id, row.meet_id, row.data])?;
+                        tx.commit()
+                    })
+                    .await
+                },
+                Err(err) => Err(err),
+            },
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            tracing::error!("Error saving session after rotation: {}", err);
+        }
+
+        Some(new_token)
+    }
+
+    /// Remove every session of a meet except `keep_token`, returning the removed sessions
+    pub async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        let removed = self
+            .inner
+            .remove_sessions_for_meet(meet_id, keep_token)
+            .await;
+
+        let tokens = removed
+            .iter()
+            .map(|session| session.token.clone())
+            .collect();
+        if let Err(err) = self.delete_sessions(tokens).await {
+            tracing::error!("Error deleting sessions after removal: {}", err);
+        }
+
+        removed
+    }
+
+    /// Remove expired sessions from memory and the database
+    pub async fn cleanup_expired_sessions(&self) {
+        self.inner.cleanup_expired_sessions().await;
+
+        if let Err(err) = self.flush().await {
+            tracing::error!("Error flushing sessions after cleanup: {}", err);
+        }
+    }
+
+    /// Rewrite every live session and drop rows of sessions that are gone
+    pub async fn flush(&self) -> Result<(), anyhow::Error> {
+        let sessions = self.inner.get_all_sessions().await?;
+        let rows = sessions
+            .iter()
+            .map(|(token, entry)| self.encode(token, entry))
+            .collect::<Result<Vec<Row>, _>>()?;
+
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let live: HashSet<&str> = rows.iter().map(|row| row.id.as_str()).collect();
+            let stored = {
+                let mut stmt = tx.prepare("SELECT id FROM sessions")?;
+                let ids = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                ids
+            };
+            for id in stored.iter().filter(|id| !live.contains(id.as_str())) {
+                tx.execute("DELETE FROM sessions WHERE id = ?1", [id])?;
+            }
+            for row in &rows {
+                tx.execute(UPSERT, params![row.id, row.meet_id, row.data])?;
+            }
+            tx.commit()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session {
+        SqliteSessionStore::create_session_with_role(self, meet_id, location_name, priority, role)
+            .await
+    }
+
+    async fn get_session(&self, token: &str) -> Option<Session> {
+        self.inner.get_session(token).await
+    }
+
+    async fn validate_session(&self, token: &str) -> bool {
+        self.inner.validate_session(token).await
+    }
+
+    async fn remove_session(&self, token: &str) {
+        SqliteSessionStore::remove_session(self, token).await;
+    }
+
+    async fn rotate_session(&self, old_token: &str) -> Option<String> {
+        SqliteSessionStore::rotate_session(self, old_token).await
+    }
+
+    async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session> {
+        SqliteSessionStore::remove_sessions_for_meet(self, meet_id, keep_token).await
+    }
+
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session> {
+        self.inner.sessions_for_meet(meet_id).await
+    }
+
+    async fn get_csrf_token(&self, token: &str) -> Option<String> {
+        self.inner.get_csrf_token(token).await
+    }
+
+    async fn verify_csrf_token(&self, session_token: &str, csrf_token: &str) -> bool {
+        self.inner
+            .verify_csrf_token(session_token, csrf_token)
+            .await
+    }
+
+    async fn cleanup_expired_sessions(&self) {
+        SqliteSessionStore::cleanup_expired_sessions(self).await;
+    }
+
+    async fn active_session_count(&self) -> usize {
+        self.inner.active_session_count().await
+    }
+
+    async fn flush(&self) -> Result<(), anyhow::Error> {
+        SqliteSessionStore::flush(self).await
+    }
+
+    async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        SqliteSessionStore::shutdown(self).await
+    }
+
+    fn auto_save_heartbeat(&self) -> Option<(Heartbeat, Duration)> {
+        Some((self.flushed.clone(), FLUSH_INTERVAL))
+    }
+
+    async fn rotate_encryption_key(&self) -> Result<Option<u32>, anyhow::Error> {
+        SqliteSessionStore::rotate_encryption_key(self)
+            .await
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::store::tests::check_store;
+    use tempfile::TempDir;
+
+    fn keyring() -> Keyring {
+        Keyring::from_secrets("sqlite session tests", &[]).unwrap()
+    }
+
+    async fn row_count(store: &SqliteSessionStore) -> i64 {
+        store
+            .with_conn(|conn| conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0)))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(temp_dir.path().join("sessions.db"), keyring())
+            .await
+            .unwrap();
+        check_store(&store).await;
+        assert_eq!(row_count(&store).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sessions.db");
+
+        let store = SqliteSessionStore::open(&path, keyring()).await.unwrap();
+        let session = store
+            .create_session("meet-a".to_string(), "Platform 1".to_string(), 5)
+            .await;
+        let removed = store
+            .create_session("meet-a".to_string(), "Table".to_string(), 5)
+            .await;
+        let csrf = store.get_csrf_token(&session.token).await.unwrap();
+        store.remove_session(&removed.token).await;
+        assert_eq!(row_count(&store).await, 1);
+
+        // Each change was written as it happened; no flush needed
+        let reopened = SqliteSessionStore::open(&path, keyring()).await.unwrap();
+        let restored = reopened.get_session(&session.token).await.unwrap();
+        assert_eq!(restored.location_name, "Platform 1");
+        assert!(reopened.verify_csrf_token(&session.token, &csrf).await);
+        assert!(reopened.get_session(&removed.token).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rows_hide_tokens() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sessions.db");
+        let store = SqliteSessionStore::open(&path, keyring()).await.unwrap();
+        let session = store
+            .create_session("meet-a".to_string(), "Platform 1".to_string(), 5)
+            .await;
+
+        let (id, data): (String, Vec<u8>) = store
+            .with_conn(|conn| {
+                conn.query_row("SELECT id, data FROM sessions", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+            })
+            .await
+            .unwrap();
+        assert_ne!(id, session.token);
+        assert!(!data
+            .windows(session.token.len())
+            .any(|window| window == session.token.as_bytes()));
+
+        // A different secret cannot read the rows
+        let other = Keyring::from_secrets("another secret", &[]).unwrap();
+        assert!(SqliteSessionStore::open(&path, other).await.is_err());
+    }
+}
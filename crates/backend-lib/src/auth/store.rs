@@ -0,0 +1,154 @@
+// crates/backend-lib/src/auth/store.rs
+
+/** Pluggable session storage.
+`DefaultAuth` talks to sessions only through the `SessionStore` trait, so the
+backend can be chosen at startup:
+- `SessionManager` keeps sessions in memory only, which suits tests
+- `PersistentSessionManager` rewrites an encrypted `sessions.dat` on every change
+- `SqliteSessionStore` (feature `sqlite`) writes each change as a single row */
+use crate::health::Heartbeat;
+use crate::messages::{Session, SessionRole};
+use async_trait::async_trait;
+use std::time::Duration;
+
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Create a new session with the given role
+    async fn create_session_with_role(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        role: SessionRole,
+    ) -> Session;
+
+    /// Create a new station session
+    async fn create_session(
+        &self,
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+    ) -> Session {
+        self.create_session_with_role(meet_id, location_name, priority, SessionRole::Station)
+            .await
+    }
+
+    /// Get an unexpired session by token, refreshing its idle timer
+    async fn get_session(&self, token: &str) -> Option<Session>;
+
+    /// Check a session token, refreshing its idle timer
+    async fn validate_session(&self, token: &str) -> bool;
+
+    /// Remove a session by token
+    async fn remove_session(&self, token: &str);
+
+    /// Replace a session's token, returning the new one
+    async fn rotate_session(&self, old_token: &str) -> Option<String>;
+
+    /// Remove every session of a meet except `keep_token`, returning the removed sessions
+    async fn remove_sessions_for_meet(&self, meet_id: &str, keep_token: &str) -> Vec<Session>;
+
+    /// Unexpired sessions belonging to a meet
+    async fn sessions_for_meet(&self, meet_id: &str) -> Vec<Session>;
+
+    /// Get CSRF token for a session
+    async fn get_csrf_token(&self, token: &str) -> Option<String>;
+
+    /// Verify a CSRF token for a session
+    async fn verify_csrf_token(&self, session_token: &str, csrf_token: &str) -> bool;
+
+    /// Remove expired sessions
+    async fn cleanup_expired_sessions(&self);
+
+    /// Return count of active sessions
+    async fn active_session_count(&self) -> usize;
+
+    /// Write out anything not yet persisted; stores without buffering do nothing
+    async fn flush(&self) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    /// Stop background saving and write out anything not yet persisted
+    async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        self.flush().await
+    }
+
+    /// Switch to a fresh encryption key and re-encrypt stored sessions with it,
+    /// returning the new key's ID; stores that keep nothing on disk return `None`
+    async fn rotate_encryption_key(&self) -> Result<Option<u32>, anyhow::Error> {
+        Ok(None)
+    }
+
+    /// Heartbeat of the store's background save task and how often it beats;
+    /// stores without one return `None`
+    fn auto_save_heartbeat(&self) -> Option<(Heartbeat, Duration)> {
+        None
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::auth::{PersistentSessionManager, SessionManager};
+    use tempfile::TempDir;
+
+    /// Behaviour every session store must share
+    pub(crate) async fn check_store(store: &dyn SessionStore) {
+        let station = store
+            .create_session("meet-a".to_string(), "Platform 1".to_string(), 5)
+            .await;
+        let director = store
+            .create_session_with_role(
+                "meet-a".to_string(),
+                "Director".to_string(),
+                10,
+                SessionRole::Director,
+            )
+            .await;
+        let other = store
+            .create_session("meet-b".to_string(), "Table".to_string(), 5)
+            .await;
+        assert_eq!(store.active_session_count().await, 3);
+
+        let found = store.get_session(&director.token).await.unwrap();
+        assert_eq!(found.role, SessionRole::Director);
+        assert!(store.validate_session(&station.token).await);
+        assert!(!store.validate_session("unknown").await);
+
+        let csrf = store.get_csrf_token(&station.token).await.unwrap();
+        assert!(store.verify_csrf_token(&station.token, &csrf).await);
+        assert!(!store.verify_csrf_token(&station.token, "wrong").await);
+
+        let rotated = store.rotate_session(&station.token).await.unwrap();
+        assert!(!store.validate_session(&station.token).await);
+        assert!(store.validate_session(&rotated).await);
+        assert_eq!(store.sessions_for_meet("meet-a").await.len(), 2);
+
+        let removed = store
+            .remove_sessions_for_meet("meet-a", &director.token)
+            .await;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].token, rotated);
+        assert!(store.validate_session(&director.token).await);
+
+        store.remove_session(&other.token).await;
+        assert!(store.get_session(&other.token).await.is_none());
+        assert_eq!(store.active_session_count().await, 1);
+        store.flush().await.unwrap();
+        store.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        check_store(&SessionManager::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_file_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = PersistentSessionManager::new(temp_dir.path())
+            .await
+            .unwrap();
+        check_store(&store).await;
+    }
+}
@@ -5,7 +5,7 @@ use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 /** Secure token generation for authentication
 This module provides cryptographically secure token generation
 for session tokens and CSRF tokens. */
-use rand::{rngs::OsRng, RngCore};
+use rand::{rngs::OsRng, TryRngCore};
 
 /// Default token size in bytes (32 bytes = 256 bits of entropy)
 const DEFAULT_TOKEN_BYTES: usize = 32;
@@ -26,7 +26,9 @@ pub fn generate_secure_token() -> String {
 A base64 URL-safe encoded string without padding */
 pub fn generate_secure_token_with_size(bytes: usize) -> String {
     let mut buffer = vec![0u8; bytes];
-    OsRng.fill_bytes(&mut buffer);
+    OsRng
+        .try_fill_bytes(&mut buffer)
+        .expect("OS random number generator failed");
     URL_SAFE_NO_PAD.encode(buffer)
 }
 
@@ -0,0 +1,557 @@
+// crates/backend-lib/src/bundle.rs
+
+/** Portable meet bundles.
+A bundle is a tar archive holding everything needed to move a current meet
+to another server, or to hand it to a federation:
+
+```text
+manifest.json      Format version, meet ID, export time and file checksums
+meet-info.json     Password hash and endpoints
+updates.jsonl      The update log, one JSON update per line
+snapshot.json      Meet state after the last update, for readers without a server
+meet.csv           Published results (only if published)
+return-email.txt   Email the results go to (only if published)
+```
+
+Every file but the manifest is listed in it with its size and SHA-256, and a
+bundle is refused if any file is missing, altered or unexpected. The snapshot
+is informational: an import rebuilds the state from `updates.jsonl`.
+
+Bundles are built and read through `Storage`, so they work with every
+backend, and imports never overwrite an existing meet unless forced. */
+use crate::{
+    error::AppError,
+    meet_actor::{MeetActor, MeetExport},
+    meet_registry::MeetStatus,
+    storage::{update_seq, MeetData, Storage},
+    validation::validate_meet_id,
+};
+use chrono::{DateTime, Utc};
+use openlifter_common::MeetInfo;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use tokio::sync::broadcast;
+
+/// Version of the bundle format written by this server
+pub const BUNDLE_FORMAT: u32 = 1;
+
+/// Largest bundle accepted for import
+pub const MAX_BUNDLE_SIZE: usize = 64 * 1024 * 1024;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MEET_INFO_FILE: &str = "meet-info.json";
+const UPDATES_FILE: &str = "updates.jsonl";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const CSV_FILE: &str = "meet.csv";
+const EMAIL_FILE: &str = "return-email.txt";
+
+/// A file listed in a bundle manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub name: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the contents
+    pub sha256: String,
+}
+
+/// Description of a bundle, stored as `manifest.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub meet_id: String,
+    pub exported_at: DateTime<Utc>,
+    /// Server sequence number of the last update in the log
+    pub server_seq: u64,
+    pub files: Vec<BundleFile>,
+}
+
+/// Contents of `snapshot.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    server_seq: u64,
+    state: serde_json::Value,
+}
+
+/// A meet read from storage or from a verified bundle
+#[derive(Debug, Clone)]
+pub struct MeetBundle {
+    pub manifest: BundleManifest,
+    pub info: MeetInfo,
+    /// Update log, one JSON update per entry
+    pub updates: Vec<String>,
+    /// Published CSV and return email
+    pub csv: Option<(String, String)>,
+    snapshot: Snapshot,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn invalid(reason: impl Into<String>) -> AppError {
+    AppError::InvalidInput(format!("Invalid meet bundle: {}", reason.into()))
+}
+
+impl MeetBundle {
+    /// Read a current meet from storage
+    /// # Arguments
+    /// * `storage` - Storage holding the meet
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(MeetBundle)` - The meet, ready to be written as a bundle
+    /// * `Err(AppError::MeetNotFound)` if there is no current meet with this ID
+    pub async fn from_storage<S: Storage + Clone>(
+        storage: &S,
+        meet_id: &str,
+    ) -> Result<Self, AppError> {
+        // Replay the log the same way the meet actor does
+        let mut actor = MeetActor::new(
+            meet_id.to_string(),
+            storage.clone(),
+            broadcast::channel(1).0,
+        );
+        actor.load().await?;
+        Ok(Self::from_export(meet_id, actor.export().await?))
+    }
+
+    /// Build the bundle of a meet from what its actor exported
+    pub fn from_export(meet_id: &str, export: MeetExport) -> Self {
+        Self {
+            manifest: BundleManifest {
+                format: BUNDLE_FORMAT,
+                meet_id: meet_id.to_string(),
+                exported_at: Utc::now(),
+                server_seq: export.server_seq,
+                files: Vec::new(),
+            },
+            info: export.info,
+            updates: export.updates,
+            csv: export.csv,
+            snapshot: Snapshot {
+                server_seq: export.server_seq,
+                state: export.state,
+            },
+        }
+    }
+
+    /// Files of the bundle other than the manifest, in archive order
+    fn files(&self) -> Result<Vec<(&'static str, Vec<u8>)>, AppError> {
+        let mut updates = self.updates.join("\n");
+        if !updates.is_empty() {
+            updates.push('\n');
+        }
+        let mut files = vec![
+            (MEET_INFO_FILE, serde_json::to_vec_pretty(&self.info)?),
+            (UPDATES_FILE, updates.into_bytes()),
+            (SNAPSHOT_FILE, serde_json::to_vec_pretty(&self.snapshot)?),
+        ];
+        if let Some((opl_csv, return_email)) = &self.csv {
+            files.push((CSV_FILE, opl_csv.clone().into_bytes()));
+            files.push((EMAIL_FILE, return_email.clone().into_bytes()));
+        }
+        Ok(files)
+    }
+
+    /// Write the bundle as a tar archive
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AppError> {
+        let files = self.files()?;
+        let mut manifest = self.manifest.clone();
+        manifest.files = files
+            .iter()
+            .map(|(name, bytes)| BundleFile {
+                name: (*name).to_string(),
+                size: bytes.len() as u64,
+                sha256: sha256_hex(bytes),
+            })
+            .collect();
+
+        let mtime = u64::try_from(manifest.exported_at.timestamp()).unwrap_or(0);
+        let mut builder = tar::Builder::new(Vec::new());
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        for (name, bytes) in std::iter::once((MANIFEST_FILE, manifest_bytes)).chain(files) {
+            let mut header = tar::Header::new_ustar();
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            builder.append_data(&mut header, name, bytes.as_slice())?;
+        }
+        Ok(builder.into_inner()?)
+    }
+
+    /// Read and verify a bundle
+    /// # Arguments
+    /// * `bytes` - Tar archive as written by `to_bytes`
+    /// # Returns
+    /// * `Ok(MeetBundle)` - The verified meet
+    /// * `Err(AppError::InvalidInput)` if the bundle is malformed, incomplete or altered
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AppError> {
+        if bytes.len() > MAX_BUNDLE_SIZE {
+            return Err(invalid("too large"));
+        }
+
+        let unreadable = |err: std::io::Error| invalid(format!("unreadable archive: {err}"));
+        let mut files = BTreeMap::new();
+        let mut archive = tar::Archive::new(Cursor::new(bytes));
+        for entry in archive.entries().map_err(unreadable)? {
+            let mut entry = entry.map_err(unreadable)?;
+            if entry.header().entry_type() != tar::EntryType::Regular {
+                return Err(invalid("only regular files are allowed"));
+            }
+            let name = entry
+                .path()
+                .map_err(unreadable)?
+                .to_string_lossy()
+                .into_owned();
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).map_err(unreadable)?;
+            if files.insert(name.clone(), contents).is_some() {
+                return Err(invalid(format!("{name} appears twice")));
+            }
+        }
+
+        let manifest: BundleManifest = serde_json::from_slice(
+            &files
+                .remove(MANIFEST_FILE)
+                .ok_or_else(|| invalid("manifest is missing"))?,
+        )
+        .map_err(|err| invalid(format!("unreadable manifest: {err}")))?;
+        if manifest.format != BUNDLE_FORMAT {
+            return Err(invalid(format!("unsupported format {}", manifest.format)));
+        }
+        validate_meet_id(&manifest.meet_id).map_err(|err| invalid(err.to_string()))?;
+
+        for listed in &manifest.files {
+            let contents = files
+                .get(&listed.name)
+                .ok_or_else(|| invalid(format!("{} is missing", listed.name)))?;
+            if contents.len() as u64 != listed.size || sha256_hex(contents) != listed.sha256 {
+                return Err(invalid(format!(
+                    "{} does not match its checksum",
+                    listed.name
+                )));
+            }
+        }
+        if let Some(name) = files
+            .keys()
+            .find(|name| !manifest.files.iter().any(|listed| listed.name == **name))
+        {
+            return Err(invalid(format!("{name} is not listed in the manifest")));
+        }
+
+        let mut text = |name: &str| -> Result<Option<String>, AppError> {
+            files
+                .remove(name)
+                .map(|bytes| {
+                    String::from_utf8(bytes).map_err(|_| invalid(format!("{name} is not UTF-8")))
+                })
+                .transpose()
+        };
+        let required = |file: Option<String>, name: &str| {
+            file.ok_or_else(|| invalid(format!("{name} is missing")))
+        };
+
+        let info: MeetInfo =
+            serde_json::from_str(&required(text(MEET_INFO_FILE)?, MEET_INFO_FILE)?)
+                .map_err(|err| invalid(format!("unreadable {MEET_INFO_FILE}: {err}")))?;
+        let snapshot: Snapshot =
+            serde_json::from_str(&required(text(SNAPSHOT_FILE)?, SNAPSHOT_FILE)?)
+                .map_err(|err| invalid(format!("unreadable {SNAPSHOT_FILE}: {err}")))?;
+        let updates: Vec<String> = required(text(UPDATES_FILE)?, UPDATES_FILE)?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        if let Some(line) = updates
+            .iter()
+            .position(|line| serde_json::from_str::<serde_json::Value>(line).is_err())
+        {
+            return Err(invalid(format!(
+                "line {} of {UPDATES_FILE} is not JSON",
+                line + 1
+            )));
+        }
+        let last_seq = match updates.last() {
+            Some(line) => update_seq(line),
+            None => Some(0),
+        };
+        if last_seq != Some(manifest.server_seq) {
+            return Err(invalid(format!(
+                "{UPDATES_FILE} does not end with update {}",
+                manifest.server_seq
+            )));
+        }
+        let csv = match (text(CSV_FILE)?, text(EMAIL_FILE)?) {
+            (Some(opl_csv), Some(return_email)) => Some((opl_csv, return_email)),
+            (None, None) => None,
+            _ => return Err(invalid(format!("{CSV_FILE} and {EMAIL_FILE} go together"))),
+        };
+
+        Ok(Self {
+            manifest,
+            info,
+            updates,
+            csv,
+            snapshot,
+        })
+    }
+
+    /// Write the meet into storage as a current meet, replacing what is there
+    /// in one step. The meet ID must already be reserved.
+    pub async fn write_to<S: Storage>(&self, storage: &S, meet_id: &str) -> Result<(), AppError> {
+        let data = MeetData {
+            updates: self.updates.clone(),
+            info: Some(self.info.clone()),
+            csv: self.csv.clone(),
+        };
+        storage.replace_meet(meet_id, &data).await
+    }
+}
+
+/// Export a current meet as a bundle
+/// # Arguments
+/// * `storage` - Storage holding the meet
+/// * `meet_id` - ID of the meet
+/// # Returns
+/// * `Ok(Vec<u8>)` - The bundle as a tar archive
+/// * `Err(AppError::MeetNotFound)` if there is no current meet with this ID
+pub async fn export_meet<S: Storage + Clone>(
+    storage: &S,
+    meet_id: &str,
+) -> Result<Vec<u8>, AppError> {
+    MeetBundle::from_storage(storage, meet_id).await?.to_bytes()
+}
+
+/// Import a bundle as a current meet under the meet ID it was exported with
+/// # Arguments
+/// * `storage` - Storage to import into
+/// * `bytes` - The bundle
+/// * `force` - Replace a current meet that already uses the ID
+/// # Returns
+/// * `Ok(BundleManifest)` - Manifest of the imported bundle
+/// * `Err(AppError::MeetExists)` if the ID is taken and `force` is not set,
+///   or if it belongs to a finished meet
+/// * `Err(AppError::InvalidInput)` if the bundle is malformed or altered
+pub async fn import_meet<S: Storage>(
+    storage: &S,
+    bytes: &[u8],
+    force: bool,
+) -> Result<BundleManifest, AppError> {
+    let bundle = MeetBundle::from_bytes(bytes)?;
+    let meet_id = bundle.manifest.meet_id.as_str();
+
+    let reserved = storage.reserve_meet_id(meet_id).await?;
+    if !reserved {
+        let current = storage
+            .list_meets()
+            .await?
+            .iter()
+            .any(|record| record.meet_id == meet_id && record.status == MeetStatus::Current);
+        if !force || !current {
+            return Err(AppError::MeetExists(meet_id.to_string()));
+        }
+    }
+
+    if let Err(err) = bundle.write_to(storage, meet_id).await {
+        // A new meet that could not be written must not keep its ID
+        if reserved {
+            storage.release_meet_id(meet_id).await?;
+        }
+        return Err(err);
+    }
+    Ok(bundle.manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, StorageOp};
+    use openlifter_common::EndpointPriority;
+    use std::io::ErrorKind;
+
+    fn update(seq: u64, key: &str, value: i64) -> String {
+        serde_json::json!({
+            "update_key": key,
+            "update_value": value,
+            "local_seq_num": seq,
+            "after_server_seq_num": seq - 1,
+            "serverSeqNum": seq,
+        })
+        .to_string()
+    }
+
+    async fn meet(storage: &MemoryStorage, meet_id: &str, published: bool) {
+        storage.reserve_meet_id(meet_id).await.unwrap();
+        storage
+            .store_meet_info(
+                meet_id,
+                "hash",
+                &[EndpointPriority {
+                    location_name: "Platform A".to_string(),
+                    priority: 1,
+                }],
+            )
+            .await
+            .unwrap();
+        for (seq, value) in [(1, 100), (2, 105), (3, 110)] {
+            storage
+                .append_update(meet_id, &update(seq, "lifter.1.squat", value))
+                .await
+                .unwrap();
+        }
+        if published {
+            storage
+                .store_meet_csv(meet_id, "Name,Total\nA,110\n", "results@example.com")
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = MemoryStorage::new();
+        meet(&source, "meet-1", true).await;
+        let bytes = export_meet(&source, "meet-1").await.unwrap();
+
+        let bundle = MeetBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(bundle.manifest.server_seq, 3);
+        assert_eq!(bundle.manifest.files.len(), 5);
+        assert_eq!(bundle.snapshot.state["lifter.1.squat"], 110);
+
+        let target = MemoryStorage::new();
+        let manifest = import_meet(&target, &bytes, false).await.unwrap();
+        assert_eq!(manifest.meet_id, "meet-1");
+        assert_eq!(
+            target.read_updates("meet-1").await.unwrap(),
+            source.read_updates("meet-1").await.unwrap()
+        );
+        assert_eq!(
+            target.get_meet_info("meet-1").await.unwrap().password_hash,
+            "hash"
+        );
+        assert_eq!(
+            target.get_meet_csv("meet-1").await.unwrap(),
+            source.get_meet_csv("meet-1").await.unwrap()
+        );
+
+        // Finished meets are only in storage as IDs, so they can't be exported
+        source.archive_meet("meet-1").await.unwrap();
+        assert!(matches!(
+            export_meet(&source, "meet-1").await,
+            Err(AppError::MeetNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_import_refuses_existing_meet() {
+        let source = MemoryStorage::new();
+        meet(&source, "meet-1", false).await;
+        let bytes = export_meet(&source, "meet-1").await.unwrap();
+
+        let target = MemoryStorage::new();
+        meet(&target, "meet-1", true).await;
+        target
+            .append_update("meet-1", &update(4, "x", 1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            import_meet(&target, &bytes, false).await,
+            Err(AppError::MeetExists(_))
+        ));
+        assert_eq!(target.read_updates("meet-1").await.unwrap().len(), 4);
+
+        // Forcing replaces the meet, including dropping its published results
+        import_meet(&target, &bytes, true).await.unwrap();
+        assert_eq!(target.read_updates("meet-1").await.unwrap().len(), 3);
+        assert_eq!(target.get_meet_csv("meet-1").await.unwrap(), None);
+
+        // Finished meets are never overwritten
+        target.archive_meet("meet-1").await.unwrap();
+        assert!(matches!(
+            import_meet(&target, &bytes, true).await,
+            Err(AppError::MeetExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_import_releases_meet_id() {
+        let source = MemoryStorage::new();
+        meet(&source, "meet-1", false).await;
+        let bytes = export_meet(&source, "meet-1").await.unwrap();
+
+        let plan = FaultPlan::new().on_call(
+            StorageOp::ReplaceMeet,
+            1,
+            Fault::Error(ErrorKind::StorageFull),
+        );
+        let target = FaultyStorage::new(MemoryStorage::new(), plan);
+        assert!(matches!(
+            import_meet(&target, &bytes, false).await,
+            Err(AppError::Io(_))
+        ));
+        assert!(target.list_meets().await.unwrap().is_empty());
+
+        // The ID is free for the next attempt
+        import_meet(&target, &bytes, false).await.unwrap();
+        assert_eq!(target.read_updates("meet-1").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_keeps_stored_numbers() {
+        let source = MemoryStorage::new();
+        source.reserve_meet_id("meet-1").await.unwrap();
+        source.store_meet_info("meet-1", "hash", &[]).await.unwrap();
+        // Update 3 was lost from the log, say by a repair
+        for seq in [1, 2, 4] {
+            source
+                .append_update("meet-1", &update(seq, "x", 1))
+                .await
+                .unwrap();
+        }
+
+        let bytes = export_meet(&source, "meet-1").await.unwrap();
+        let bundle = MeetBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(bundle.manifest.server_seq, 4);
+        assert_eq!(bundle.updates.len(), 3);
+
+        let mut short = bundle.clone();
+        short.updates.pop();
+        let err = MeetBundle::from_bytes(&short.to_bytes().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("does not end with update 4"));
+    }
+
+    #[tokio::test]
+    async fn test_altered_bundle_is_refused() {
+        let source = MemoryStorage::new();
+        meet(&source, "meet-1", false).await;
+        let bundle = MeetBundle::from_storage(&source, "meet-1").await.unwrap();
+
+        // Rewrite one update without touching the manifest
+        let bytes = bundle.to_bytes().unwrap();
+        let needle = b"\"update_value\":105";
+        let at = bytes
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap();
+        let mut altered = bytes.clone();
+        altered[at + needle.len() - 1] = b'9';
+        let err = MeetBundle::from_bytes(&altered).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("updates.jsonl does not match its checksum"));
+
+        // Truncated bundles and bundles with an unknown format are refused too
+        assert!(matches!(
+            MeetBundle::from_bytes(&bytes[..bytes.len() / 2]),
+            Err(AppError::InvalidInput(_))
+        ));
+        let mut future = bundle.clone();
+        future.manifest.format = BUNDLE_FORMAT + 1;
+        let err = MeetBundle::from_bytes(&future.to_bytes().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("unsupported format"));
+    }
+}
@@ -9,6 +9,8 @@ The configuration is loaded in that order, with later sources taking precedence
 use anyhow::Result;
 use config::{Config, ConfigError};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
 
 /// Server configuration
@@ -17,17 +19,132 @@ pub struct Settings {
     pub server: ServerSettings,
     pub storage: StorageSettings,
     pub rate_limit: RateLimitSettings,
+    #[serde(default)]
+    pub websocket: WebSocketSettings,
+    #[serde(default)]
+    pub sessions: SessionSettings,
+    #[serde(default)]
+    pub retention: RetentionSettings,
+    #[serde(default)]
+    pub admin: AdminSettings,
+    #[serde(default)]
+    pub logging: LoggingSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerSettings {
     pub host: String,
     pub port: u16,
+    /// Seconds between a shutdown signal and closing the listener, during
+    /// which readiness reports the server as draining. Connections still
+    /// open 10 seconds after that are dropped.
+    #[serde(default = "default_drain_secs")]
+    pub drain_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StorageSettings {
     pub path: PathBuf,
+    /// Storage implementation used under `path`
+    pub backend: StorageBackend,
+    /// When appended updates are synced to disk
+    pub durability: Durability,
+    /// Longest a sync with `Durability::Group` waits for appends still being
+    /// written to join it
+    pub group_commit_ms: u64,
+    /// Encryption of meet data at rest
+    pub encryption: EncryptionSettings,
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self {
+            path: default_data_dir(),
+            backend: StorageBackend::default(),
+            durability: Durability::default(),
+            group_commit_ms: default_group_commit_ms(),
+            encryption: EncryptionSettings::default(),
+        }
+    }
+}
+
+/// Where meet data is kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// One directory per meet under `current-meets` and `finished-meets`
+    #[default]
+    Flat,
+    /// A single `meets.db` database (feature `sqlite`)
+    Sqlite,
+}
+
+/// Environment variable that overrides `storage.encryption.key_secret`
+pub const STORAGE_SECRET_ENV: &str = "OPENLIFTER_STORAGE_SECRET";
+
+/// Debug stand-in for secrets: shows whether one is set, never its value
+struct Redacted<'a>(&'a Option<String>);
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(<redacted>)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// Encryption of meet data at rest
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncryptionSettings {
+    /// Encrypt meet data with per-meet keys. Plaintext meets are migrated when
+    /// the server starts.
+    pub enabled: bool,
+    /// Secret the master key is derived from. When unset, a master keyring is
+    /// generated in the `keys` directory under the storage path.
+    pub key_secret: Option<String>,
+    /// Earlier secrets, kept only to unwrap data keys made before a change
+    pub previous_key_secrets: Vec<String>,
+}
+
+impl fmt::Debug for EncryptionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionSettings")
+            .field("enabled", &self.enabled)
+            .field("key_secret", &Redacted(&self.key_secret))
+            .field(
+                "previous_key_secrets",
+                &format_args!("<{} redacted>", self.previous_key_secrets.len()),
+            )
+            .finish()
+    }
+}
+
+impl EncryptionSettings {
+    /// The key secret, preferring the environment over the config file
+    pub fn key_secret(&self) -> Option<String> {
+        std::env::var(STORAGE_SECRET_ENV)
+            .ok()
+            .or_else(|| self.key_secret.clone())
+            .filter(|secret| !secret.is_empty())
+    }
+}
+
+/// Sync policy for appended updates. Whole-file writes such as meet info are
+/// always synced, whatever the policy.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Durability {
+    /// Sync every append before acknowledging it
+    #[default]
+    Always,
+    /// Acknowledge appends after a sync shared with the appends written at the
+    /// same time, waiting at most `group_commit_ms` for them
+    Group,
+    /// Leave flushing to the OS; a power cut can lose acknowledged updates
+    Buffered,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,6 +153,202 @@ pub struct RateLimitSettings {
     pub max_requests: u32,
 }
 
+/// Per-connection WebSocket behaviour
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WebSocketSettings {
+    /// How long relayed updates are collected before being sent as one message
+    pub relay_batch_window_ms: u64,
+    /// Maximum number of updates carried by a single `UpdateRelay` message
+    pub relay_max_batch_size: usize,
+    /// Seconds between heartbeat pings sent to each client
+    pub ping_interval_secs: u64,
+    /// Consecutive unanswered pings after which a connection is closed
+    pub max_missed_pongs: u32,
+    /// A resuming client missing more updates than this gets a snapshot instead
+    pub resume_snapshot_threshold: usize,
+    /// Maximum number of updates carried by a single `ServerPull` message
+    pub pull_page_size: usize,
+}
+
+/// Scheduled deletion of old meet data. Each limit is in days, and 0 keeps
+/// that kind of data forever.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// Run the retention job in the background
+    pub enabled: bool,
+    /// Only report what would be purged
+    pub dry_run: bool,
+    /// Minutes between runs
+    pub interval_mins: u64,
+    /// Time a meet past its limit is reported as pending before it is purged
+    pub grace_period_hours: u64,
+    /// Current meets without any change for this long
+    pub abandoned_meet_days: u64,
+    /// Finished meets, counted from when they were archived
+    pub finished_meet_days: u64,
+    /// Published CSVs and return emails, counted from publication
+    pub published_csv_days: u64,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dry_run: true,
+            interval_mins: 60,
+            grace_period_hours: 24,
+            abandoned_meet_days: 90,
+            finished_meet_days: 30,
+            published_csv_days: 30,
+        }
+    }
+}
+
+/// Environment variable that overrides `admin.token`
+pub const ADMIN_TOKEN_ENV: &str = "OPENLIFTER_ADMIN_TOKEN";
+
+/// Access to the `/admin` API
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AdminSettings {
+    /// Bearer token operators present to the admin API. When unset, the API
+    /// is disabled.
+    pub token: Option<String>,
+}
+
+impl fmt::Debug for AdminSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminSettings")
+            .field("token", &Redacted(&self.token))
+            .finish()
+    }
+}
+
+impl AdminSettings {
+    /// The operator token, preferring the environment over the config file
+    pub fn token(&self) -> Option<String> {
+        std::env::var(ADMIN_TOKEN_ENV)
+            .ok()
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+/// Levels accepted for `logging.level` and `logging.modules`
+const LOG_LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// One human-readable line per event
+    #[default]
+    Text,
+    /// One JSON object per event, including the fields of its spans
+    Json,
+}
+
+/// Log output of the server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LoggingSettings {
+    pub format: LogFormat,
+    /// Level of every module without its own entry in `modules`
+    pub level: String,
+    /// Levels by module path, e.g. `"backend_lib::meet_actor" = "debug"`
+    pub modules: BTreeMap<String, String>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::default(),
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl LoggingSettings {
+    /// The levels as filter directives in `RUST_LOG` syntax
+    pub fn directives(&self) -> String {
+        let mut directives = vec![self.level.clone()];
+        directives.extend(
+            self.modules
+                .iter()
+                .map(|(module, level)| format!("{module}={level}")),
+        );
+        directives.join(",")
+    }
+}
+
+/// Environment variable that overrides `sessions.key_secret`
+pub const SESSION_SECRET_ENV: &str = "OPENLIFTER_SESSION_SECRET";
+
+/// Where sessions are kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionBackend {
+    /// Encrypted `sessions.dat`, rewritten on every change
+    #[default]
+    File,
+    /// Memory only; sessions are lost on restart
+    Memory,
+    /// Embedded `sessions.db`, one row per session (feature `sqlite`)
+    Sqlite,
+}
+
+/// Storage and encryption of sessions
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SessionSettings {
+    /// Session store used by the server
+    pub backend: SessionBackend,
+    /// Secret the session encryption key is derived from. When unset, keys are
+    /// generated and kept in a keyring file in the sessions directory.
+    pub key_secret: Option<String>,
+    /// Earlier secrets, kept only to read sessions saved before a change
+    pub previous_key_secrets: Vec<String>,
+}
+
+impl fmt::Debug for SessionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionSettings")
+            .field("backend", &self.backend)
+            .field("key_secret", &Redacted(&self.key_secret))
+            .field(
+                "previous_key_secrets",
+                &format_args!("<{} redacted>", self.previous_key_secrets.len()),
+            )
+            .finish()
+    }
+}
+
+impl SessionSettings {
+    /// The key secret, preferring the environment over the config file
+    pub fn key_secret(&self) -> Option<String> {
+        std::env::var(SESSION_SECRET_ENV)
+            .ok()
+            .or_else(|| self.key_secret.clone())
+            .filter(|secret| !secret.is_empty())
+    }
+}
+
+impl Default for WebSocketSettings {
+    fn default() -> Self {
+        Self {
+            relay_batch_window_ms: default_relay_batch_window_ms(),
+            relay_max_batch_size: default_relay_max_batch_size(),
+            ping_interval_secs: default_ping_interval_secs(),
+            max_missed_pongs: default_max_missed_pongs(),
+            resume_snapshot_threshold: default_resume_snapshot_threshold(),
+            pull_page_size: default_pull_page_size(),
+        }
+    }
+}
+
 impl Settings {
     /// Load configuration from environment and file
     pub fn load() -> Result<Self, ConfigError> {
@@ -60,6 +373,65 @@ impl Settings {
     pub fn get_rate_limit_settings(&self) -> &RateLimitSettings {
         &self.rate_limit
     }
+
+    /// Find settings the server cannot run with
+    /// Nothing is opened or created; only the values themselves are checked.
+    /// # Returns
+    /// * One message per problem, empty if the settings are usable
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.server.host.is_empty() {
+            problems.push("server.host is empty".to_string());
+        }
+        if self.storage.path.as_os_str().is_empty() {
+            problems.push("storage.path is empty".to_string());
+        }
+        if !cfg!(feature = "sqlite") {
+            if self.storage.backend == StorageBackend::Sqlite {
+                problems
+                    .push("storage.backend = \"sqlite\" needs the `sqlite` feature".to_string());
+            }
+            if self.sessions.backend == SessionBackend::Sqlite {
+                problems
+                    .push("sessions.backend = \"sqlite\" needs the `sqlite` feature".to_string());
+            }
+        }
+        if self.storage.durability == Durability::Group && self.storage.group_commit_ms == 0 {
+            problems.push(
+                "storage.group_commit_ms must be above 0 with \"group\" durability".to_string(),
+            );
+        }
+        if self.rate_limit.window_secs == 0 || self.rate_limit.max_requests == 0 {
+            problems.push("rate_limit.window_secs and max_requests must be above 0".to_string());
+        }
+        let websocket = [
+            (
+                "relay_max_batch_size",
+                self.websocket.relay_max_batch_size == 0,
+            ),
+            ("ping_interval_secs", self.websocket.ping_interval_secs == 0),
+            ("max_missed_pongs", self.websocket.max_missed_pongs == 0),
+            ("pull_page_size", self.websocket.pull_page_size == 0),
+        ];
+        for (name, _) in websocket.iter().filter(|(_, zero)| *zero) {
+            problems.push(format!("websocket.{name} must be above 0"));
+        }
+        let levels = std::iter::once(("logging.level".to_string(), &self.logging.level)).chain(
+            self.logging
+                .modules
+                .iter()
+                .map(|(module, level)| (format!("logging.modules.\"{module}\""), level)),
+        );
+        for (name, level) in levels {
+            if !LOG_LEVELS.contains(&level.to_lowercase().as_str()) {
+                problems.push(format!(
+                    "{name} must be one of {}, not \"{level}\"",
+                    LOG_LEVELS.join(", ")
+                ));
+            }
+        }
+        problems
+    }
 }
 
 /// Settings manager for the application
@@ -130,6 +502,38 @@ fn default_metrics_port() -> u16 {
     9090
 }
 
+fn default_drain_secs() -> u64 {
+    5
+}
+
+fn default_group_commit_ms() -> u64 {
+    10
+}
+
+fn default_relay_batch_window_ms() -> u64 {
+    50
+}
+
+fn default_relay_max_batch_size() -> usize {
+    100
+}
+
+fn default_ping_interval_secs() -> u64 {
+    15
+}
+
+fn default_max_missed_pongs() -> u32 {
+    3
+}
+
+fn default_resume_snapshot_threshold() -> usize {
+    500
+}
+
+fn default_pull_page_size() -> usize {
+    500
+}
+
 #[allow(dead_code)]
 fn default_rate_limit() -> RateLimitSettings {
     RateLimitSettings {
@@ -144,11 +548,15 @@ impl Default for Settings {
             server: ServerSettings {
                 port: 8080,
                 host: "127.0.0.1".to_string(),
+                drain_secs: default_drain_secs(),
             },
-            storage: StorageSettings {
-                path: PathBuf::from("data"),
-            },
+            storage: StorageSettings::default(),
             rate_limit: default_rate_limit(),
+            websocket: WebSocketSettings::default(),
+            sessions: SessionSettings::default(),
+            retention: RetentionSettings::default(),
+            admin: AdminSettings::default(),
+            logging: LoggingSettings::default(),
         }
     }
 }
@@ -164,11 +572,18 @@ mod config_tests {
             server: ServerSettings {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                drain_secs: default_drain_secs(),
             },
             storage: StorageSettings {
                 path: default_data_dir(),
+                ..StorageSettings::default()
             },
             rate_limit: default_rate_limit(),
+            websocket: WebSocketSettings::default(),
+            sessions: SessionSettings::default(),
+            retention: RetentionSettings::default(),
+            admin: AdminSettings::default(),
+            logging: LoggingSettings::default(),
         }
     }
 
@@ -221,6 +636,58 @@ mod config_tests {
         );
     }
 
+    #[test]
+    fn test_problems() {
+        assert!(create_test_config().problems().is_empty());
+
+        let mut config = create_test_config();
+        config.storage.durability = Durability::Group;
+        config.storage.group_commit_ms = 0;
+        config.websocket.pull_page_size = 0;
+        config.rate_limit.max_requests = 0;
+        let problems = config.problems();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[2].contains("websocket.pull_page_size"));
+    }
+
+    #[test]
+    fn test_logging_directives() {
+        let mut config = create_test_config();
+        assert_eq!(config.logging.directives(), "info");
+
+        config.logging.level = "warn".to_string();
+        config
+            .logging
+            .modules
+            .insert("backend_lib::meet_actor".to_string(), "debug".to_string());
+        config
+            .logging
+            .modules
+            .insert("tower_http".to_string(), "loud".to_string());
+        assert_eq!(
+            config.logging.directives(),
+            "warn,backend_lib::meet_actor=debug,tower_http=loud"
+        );
+        let problems = config.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("logging.modules.\"tower_http\""));
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut config = create_test_config();
+        config.sessions.key_secret = Some("session-secret".to_string());
+        config.sessions.previous_key_secrets = vec!["old-session-secret".to_string()];
+        config.storage.encryption.key_secret = Some("storage-secret".to_string());
+        config.admin.token = Some("operator-token".to_string());
+
+        let printed = format!("{config:?}");
+        for secret in ["session-secret", "storage-secret", "operator-token"] {
+            assert!(!printed.contains(secret), "{secret} leaked: {printed}");
+        }
+        assert!(printed.contains("key_secret: Some(<redacted>)"));
+    }
+
     #[test]
     fn test_environment_override() {
         // We'll just test that our settings builder works as expected
@@ -0,0 +1,283 @@
+// crates/backend-lib/src/connections.rs
+
+/** Registry of live WebSocket connections.
+Every socket accepted by the router is recorded here for as long as it stays
+open, together with the meet it has joined and its most recent heartbeat
+round-trip time. This is what lets the server answer "who is connected to this
+meet right now" without relying on TCP to notice dead peers, and lets it close
+the sockets of a session that has been revoked. */
+use crate::messages::{ServerMessage, Session};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+/// Snapshot of a single connection
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConnectionInfo {
+    /// Server-assigned client identifier
+    pub client_id: String,
+    /// Remote address of the client
+    pub ip: IpAddr,
+    /// Meet the client is subscribed to, once it has joined one
+    pub meet_id: Option<String>,
+    /// Location name taken from the client's session
+    pub location_name: Option<String>,
+    /// When the socket was accepted
+    pub connected_at: DateTime<Utc>,
+    /// Round-trip time of the most recent heartbeat, in milliseconds
+    pub rtt_ms: Option<f64>,
+}
+
+/// Handles used to reach a connection's socket from elsewhere
+#[derive(Debug, Clone)]
+pub struct ConnectionControl {
+    /// Outgoing message channel of the connection
+    pub tx: mpsc::Sender<ServerMessage>,
+    /// Signalled to make the connection close itself
+    pub close: Arc<Notify>,
+}
+
+#[derive(Debug)]
+struct Connection {
+    info: ConnectionInfo,
+    session_token: Option<String>,
+    control: ConnectionControl,
+}
+
+/// Thread-safe map of open connections keyed by client ID
+#[derive(Debug, Default)]
+pub struct ConnectionRegistry {
+    connections: DashMap<String, Connection>,
+}
+
+impl ConnectionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a newly accepted connection
+    pub fn register(&self, client_id: &str, ip: IpAddr, control: ConnectionControl) {
+        self.connections.insert(
+            client_id.to_string(),
+            Connection {
+                info: ConnectionInfo {
+                    client_id: client_id.to_string(),
+                    ip,
+                    meet_id: None,
+                    location_name: None,
+                    connected_at: Utc::now(),
+                    rtt_ms: None,
+                },
+                session_token: None,
+                control,
+            },
+        );
+    }
+
+    /// Associate a connection with the session it authenticated with
+    pub fn bind_session(&self, client_id: &str, session: &Session) {
+        if let Some(mut conn) = self.connections.get_mut(client_id) {
+            conn.info.meet_id = Some(session.meet_id.clone());
+            conn.info.location_name = Some(session.location_name.clone());
+            conn.session_token = Some(session.token.clone());
+        }
+    }
+
+    /// Detach a connection from its session, e.g. after logout
+    pub fn unbind_session(&self, client_id: &str) {
+        if let Some(mut conn) = self.connections.get_mut(client_id) {
+            conn.info.meet_id = None;
+            conn.info.location_name = None;
+            conn.session_token = None;
+        }
+    }
+
+    /// Record the latest heartbeat round-trip time for a connection
+    pub fn record_rtt(&self, client_id: &str, rtt: Duration) {
+        if let Some(mut conn) = self.connections.get_mut(client_id) {
+            conn.info.rtt_ms = Some(rtt.as_secs_f64() * 1000.0);
+        }
+    }
+
+    /// Forget a connection once its socket has closed
+    pub fn remove(&self, client_id: &str) -> Option<ConnectionInfo> {
+        self.connections
+            .remove(client_id)
+            .map(|(_, conn)| conn.info)
+    }
+
+    /// Look up a single connection
+    pub fn get(&self, client_id: &str) -> Option<ConnectionInfo> {
+        self.connections
+            .get(client_id)
+            .map(|conn| conn.info.clone())
+    }
+
+    /// List the connections subscribed to a meet, oldest first
+    pub fn for_meet(&self, meet_id: &str) -> Vec<ConnectionInfo> {
+        let mut connections: Vec<ConnectionInfo> = self
+            .connections
+            .iter()
+            .filter(|conn| conn.info.meet_id.as_deref() == Some(meet_id))
+            .map(|conn| conn.info.clone())
+            .collect();
+        connections.sort_by_key(|info| info.connected_at);
+        connections
+    }
+
+    /// Number of connections using a session
+    pub fn count_for_session(&self, session_token: &str) -> usize {
+        self.connections
+            .iter()
+            .filter(|conn| conn.session_token.as_deref() == Some(session_token))
+            .count()
+    }
+
+    /// Notify and close every connection using a session.
+    /// Returns the number of connections closed.
+    pub fn disconnect_session(&self, session_token: &str, notice: &ServerMessage) -> usize {
+        self.disconnect_where(
+            |conn| conn.session_token.as_deref() == Some(session_token),
+            notice,
+        )
+    }
+
+    /// Notify and close every connection bound to a meet.
+    /// Returns the number of connections closed.
+    pub fn disconnect_meet(&self, meet_id: &str, notice: &ServerMessage) -> usize {
+        self.disconnect_where(|conn| conn.info.meet_id.as_deref() == Some(meet_id), notice)
+    }
+
+    fn disconnect_where(
+        &self,
+        matches: impl Fn(&Connection) -> bool,
+        notice: &ServerMessage,
+    ) -> usize {
+        let controls: Vec<ConnectionControl> = self
+            .connections
+            .iter()
+            .filter(|conn| matches(conn))
+            .map(|conn| conn.control.clone())
+            .collect();
+
+        for control in &controls {
+            // Best effort: a full channel must not keep the socket open
+            let _ = control.tx.try_send(notice.clone());
+            control.close.notify_one();
+        }
+        controls.len()
+    }
+
+    /// Number of open connections
+    pub fn len(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Whether no connections are open
+    pub fn is_empty(&self) -> bool {
+        self.connections.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn control() -> (ConnectionControl, mpsc::Receiver<ServerMessage>) {
+        let (tx, rx) = mpsc::channel(4);
+        let control = ConnectionControl {
+            tx,
+            close: Arc::new(Notify::new()),
+        };
+        (control, rx)
+    }
+
+    fn session(meet_id: &str, location_name: &str) -> Session {
+        Session::new(meet_id.to_string(), location_name.to_string(), 1)
+    }
+
+    #[test]
+    fn test_connection_lifecycle() {
+        let registry = ConnectionRegistry::new();
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        registry.register("a", ip, control().0);
+        registry.register("b", ip, control().0);
+        registry.register("c", ip, control().0);
+        registry.bind_session("a", &session("123-456-789", "Scoring Table"));
+        registry.bind_session("b", &session("123-456-789", "Marshal"));
+        registry.bind_session("c", &session("987-654-321", "Scoring Table"));
+        registry.record_rtt("a", Duration::from_millis(12));
+
+        let listed = registry.for_meet("123-456-789");
+        assert_eq!(listed.len(), 2);
+        let a = listed.iter().find(|c| c.client_id == "a").unwrap();
+        assert_eq!(a.location_name.as_deref(), Some("Scoring Table"));
+        assert_eq!(a.rtt_ms, Some(12.0));
+
+        assert!(registry.remove("a").is_some());
+        assert_eq!(registry.for_meet("123-456-789").len(), 1);
+        assert_eq!(registry.len(), 2);
+        assert!(registry.get("a").is_none());
+    }
+
+    #[test]
+    fn test_updates_to_unknown_connection_are_ignored() {
+        let registry = ConnectionRegistry::new();
+        registry.bind_session("missing", &session("meet", "Table"));
+        registry.record_rtt("missing", Duration::from_millis(1));
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_session() {
+        let registry = ConnectionRegistry::new();
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let revoked = session("meet", "Lost Laptop");
+        let kept = session("meet", "Scoring Table");
+
+        let (revoked_control, mut revoked_rx) = control();
+        let close = revoked_control.close.clone();
+        registry.register("a", ip, revoked_control);
+        registry.register("b", ip, control().0);
+        registry.bind_session("a", &revoked);
+        registry.bind_session("b", &kept);
+        assert_eq!(registry.count_for_session(&revoked.token), 1);
+
+        let notice = ServerMessage::SessionRevoked {
+            meet_id: "meet".to_string(),
+            reason: "revoked".to_string(),
+        };
+        assert_eq!(registry.disconnect_session(&revoked.token, &notice), 1);
+
+        // The close signal is stored until the connection waits on it
+        close.notified().await;
+        assert!(matches!(
+            revoked_rx.recv().await,
+            Some(ServerMessage::SessionRevoked { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_meet() {
+        let registry = ConnectionRegistry::new();
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        for (client_id, meet_id) in [("a", "meet"), ("b", "meet"), ("c", "other")] {
+            registry.register(client_id, ip, control().0);
+            registry.bind_session(client_id, &session(meet_id, client_id));
+        }
+
+        let notice = ServerMessage::SessionRevoked {
+            meet_id: "meet".to_string(),
+            reason: "archived".to_string(),
+        };
+        assert_eq!(registry.disconnect_meet("meet", &notice), 2);
+        assert_eq!(registry.disconnect_meet("missing", &notice), 0);
+    }
+}
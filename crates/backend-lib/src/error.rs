@@ -34,6 +34,9 @@ pub enum AppError {
     #[error("Invalid meet ID")]
     InvalidMeetId,
 
+    #[error("Meet {0} already exists")]
+    MeetExists(String),
+
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
@@ -48,6 +51,9 @@ pub enum AppError {
         meet_id: String,
         last_known_seq: u64,
     },
+
+    #[error("Update log of meet {meet_id} is corrupt at byte {offset}")]
+    CorruptLog { meet_id: String, offset: u64 },
 }
 
 impl AppError {
@@ -60,7 +66,7 @@ impl AppError {
             AppError::RateLimitExceeded | AppError::AuthRateLimited => {
                 StatusCode::TOO_MANY_REQUESTS
             },
-            AppError::NeedsRecovery { .. } => StatusCode::CONFLICT,
+            AppError::NeedsRecovery { .. } | AppError::MeetExists(_) => StatusCode::CONFLICT,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -76,10 +82,12 @@ impl AppError {
             AppError::InvalidPassword => "AUTH_002",
             AppError::MeetNotFound => "MEET_001",
             AppError::InvalidMeetId => "MEET_002",
+            AppError::MeetExists(_) => "MEET_003",
             AppError::RateLimitExceeded => "RATE_001",
             AppError::AuthRateLimited => "AUTH_003",
             AppError::InvalidInput(_) => "VAL_001",
             AppError::NeedsRecovery { .. } => "RECOVERY_001",
+            AppError::CorruptLog { .. } => "STORE_001",
         }
     }
 
@@ -97,11 +105,13 @@ impl AppError {
             AppError::NotFound(_) => "Resource not found".to_string(),
             AppError::MeetNotFound => "Resource not found".to_string(),
             AppError::InvalidMeetId => "Invalid resource identifier".to_string(),
+            AppError::MeetExists(_) => "Resource already exists".to_string(),
             AppError::RateLimitExceeded => {
                 "Rate limit exceeded, please try again later".to_string()
             },
             AppError::InvalidInput(_) => "Invalid input provided".to_string(),
             AppError::NeedsRecovery { .. } => "Data synchronization required".to_string(),
+            AppError::CorruptLog { .. } => "Internal server error".to_string(),
         }
     }
 }
@@ -148,6 +158,13 @@ impl From<&str> for AppError {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        AppError::Internal(format!("Database error: {err}"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
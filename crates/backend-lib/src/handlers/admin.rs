@@ -0,0 +1,660 @@
+// crates/backend-lib/src/handlers/admin.rs
+
+//! Administrative HTTP API.
+//!
+//! Every route is nested under `/admin` and needs the operator token from
+//! `admin.token` as `Authorization: Bearer <token>`.
+//!
+//! - `GET /admin/meets` lists every meet with its lifecycle state
+//! - `GET /admin/meets/{meet_id}` adds its connections and sessions
+//! - `POST /admin/meets/{meet_id}/archive` finishes a current meet now
+//! - `POST /admin/meets/{meet_id}/evict` stops a meet's actor; the next client
+//!   to join starts a fresh one from storage
+//! - `DELETE /admin/meets/{meet_id}/sessions` revokes the meet's sessions, or
+//!   only those of one location with `?location_name=`
+//! - `POST /admin/sessions/rotate-key` switches session encryption to a fresh
+//!   key and re-encrypts the stored sessions with it
+//! - `GET /admin/audit` lists security events, filtered by `?from=`, `?to=`
+//!   (days as `YYYY-MM-DD`), `?event=`, `?meet_id=`, `?ip=` and `?limit=`
+//! - `GET /admin/audit/verify` checks the audit log's hash chain
+use crate::{
+    audit::{self, AuditQuery, AuditRecord, ChainReport, AUDIT_DIR},
+    connections::ConnectionInfo,
+    error::AppError,
+    meet_actor::{ActorStats, MeetHandle},
+    meet_registry::MeetStatus,
+    messages::{ServerMessage, SessionSummary},
+    middleware::require_operator,
+    storage::Storage,
+    AppState, ACTOR_STOP_TIMEOUT,
+};
+use axum::{
+    extract::{Path, Query, State},
+    middleware,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long actors get to report their stats before they count as
+/// unresponsive. All actors of a request are asked at once and share it.
+const ACTOR_STATS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Where a meet is in its life, as far as the server can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lifecycle {
+    /// Current, without a running actor
+    Idle,
+    /// Current, with an actor answering requests
+    Running,
+    /// An actor exists but did not answer in time
+    Unresponsive,
+    /// Archived
+    Finished,
+}
+
+/// One meet in the admin listing
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetOverview {
+    pub meet_id: String,
+    /// Registry status; `None` for an actor whose meet is not registered
+    pub status: Option<MeetStatus>,
+    pub lifecycle: Lifecycle,
+    /// Open connections bound to the meet
+    pub connected_clients: usize,
+    /// Unexpired sessions of the meet
+    pub sessions: usize,
+    /// Figures reported by the actor, when it is running
+    pub actor: Option<ActorStats>,
+}
+
+/// A meet together with its connections and sessions
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetDetail {
+    #[serde(flatten)]
+    pub overview: MeetOverview,
+    pub connections: Vec<ConnectionInfo>,
+    pub session_list: Vec<SessionSummary>,
+}
+
+/// Outcome of an admin action on a meet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminActionResult {
+    pub meet_id: String,
+    pub sessions_revoked: usize,
+    pub connections_closed: usize,
+}
+
+/// Outcome of a session key rotation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    /// ID of the key now encrypting sessions
+    pub key_id: u32,
+}
+
+/// Query parameters of a session revocation
+#[derive(Debug, Default, Deserialize)]
+pub struct RevokeParams {
+    /// Only revoke sessions of this location
+    pub location_name: Option<String>,
+}
+
+/// Routes of the admin API, guarded by the operator token
+pub fn admin_router<S: Storage + Send + Sync + Clone + 'static>(
+    state: Arc<AppState<S>>,
+) -> Router<Arc<AppState<S>>> {
+    Router::new()
+        .route("/meets", get(list_meets))
+        .route("/meets/{meet_id}", get(meet_detail))
+        .route("/meets/{meet_id}/archive", post(archive_meet))
+        .route("/meets/{meet_id}/evict", post(evict_actor))
+        .route("/meets/{meet_id}/sessions", delete(revoke_sessions))
+        .route("/sessions/rotate-key", post(rotate_session_key))
+        .route("/audit", get(audit_events))
+        .route("/audit/verify", get(verify_audit))
+        .route_layer(middleware::from_fn_with_state(state, require_operator::<S>))
+}
+
+/// Stop a meet's actor once it has handled what it was sent. An actor that
+/// takes too long stops later on its own; it stays registered until then.
+async fn stop_actor(handle: &MeetHandle, meet_id: &str) -> Result<(), AppError> {
+    tokio::time::timeout(ACTOR_STOP_TIMEOUT, handle.shutdown(false))
+        .await
+        .map(|_| ())
+        .map_err(|_| {
+            AppError::Internal(format!(
+                "The actor of meet {meet_id} did not stop in time, try again later"
+            ))
+        })
+}
+
+/// Build the overview of one meet, giving its actor until `deadline` to
+/// report its stats
+async fn overview<S>(
+    state: &AppState<S>,
+    meet_id: &str,
+    status: Option<MeetStatus>,
+    deadline: Instant,
+) -> MeetOverview {
+    let handle = state.meet_handles.get(meet_id).map(|handle| handle.clone());
+    let has_actor = handle.is_some();
+    let actor = match handle {
+        Some(handle) => tokio::time::timeout_at(deadline, handle.stats())
+            .await
+            .ok()
+            .and_then(Result::ok),
+        None => None,
+    };
+    let lifecycle = match (status, has_actor, &actor) {
+        (Some(MeetStatus::Finished), false, _) => Lifecycle::Finished,
+        (_, true, Some(_)) => Lifecycle::Running,
+        (_, true, None) => Lifecycle::Unresponsive,
+        (_, false, _) => Lifecycle::Idle,
+    };
+
+    MeetOverview {
+        meet_id: meet_id.to_string(),
+        status,
+        lifecycle,
+        connected_clients: state.connections.for_meet(meet_id).len(),
+        sessions: state.auth.sessions_for_meet(meet_id).await.len(),
+        actor,
+    }
+}
+
+/// List every registered meet and every running actor
+async fn list_meets<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+) -> Json<Vec<MeetOverview>> {
+    let records = state.meets.list_meets().await;
+    let registered: BTreeSet<&str> = records.iter().map(|r| r.meet_id.as_str()).collect();
+    let mut unregistered: Vec<String> = state
+        .meet_handles
+        .iter()
+        .map(|entry| entry.key().clone())
+        .filter(|meet_id| !registered.contains(meet_id.as_str()))
+        .collect();
+    unregistered.sort();
+
+    // Ask every actor at once, so stuck ones cost one timeout in total
+    let deadline = Instant::now() + ACTOR_STATS_TIMEOUT;
+    let meets = records
+        .iter()
+        .map(|record| (record.meet_id.as_str(), Some(record.status)))
+        .chain(unregistered.iter().map(|meet_id| (meet_id.as_str(), None)))
+        .map(|(meet_id, status)| overview(&state, meet_id, status, deadline));
+    Json(join_all(meets).await)
+}
+
+/// Show one meet with its connections and sessions
+async fn meet_detail<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Path(meet_id): Path<String>,
+) -> Result<Json<MeetDetail>, AppError> {
+    let status = state.meets.get(&meet_id).await.map(|record| record.status);
+    if status.is_none() && !state.meet_handles.contains_key(&meet_id) {
+        return Err(AppError::MeetNotFound);
+    }
+
+    let mut session_list: Vec<SessionSummary> = state
+        .auth
+        .sessions_for_meet(&meet_id)
+        .await
+        .into_iter()
+        .map(|session| SessionSummary {
+            connections: state.connections.count_for_session(&session.token),
+            location_name: session.location_name,
+            priority: session.priority,
+            role: session.role,
+        })
+        .collect();
+    session_list.sort_by(|a, b| a.location_name.cmp(&b.location_name));
+
+    Ok(Json(MeetDetail {
+        overview: overview(
+            &state,
+            &meet_id,
+            status,
+            Instant::now() + ACTOR_STATS_TIMEOUT,
+        )
+        .await,
+        connections: state.connections.for_meet(&meet_id),
+        session_list,
+    }))
+}
+
+/// Archive a current meet, signing out everyone using it
+async fn archive_meet<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Path(meet_id): Path<String>,
+) -> Result<Json<AdminActionResult>, AppError> {
+    if state.meets.get(&meet_id).await.map(|record| record.status) != Some(MeetStatus::Current) {
+        return Err(AppError::MeetNotFound);
+    }
+
+    // Nothing is changed unless the actor stops; the meet is finished before
+    // anyone is signed out, so a failure leaves it as it was
+    let (sessions_revoked, connections_closed) = state
+        .with_actor_stopped(&meet_id, async {
+            state.meets.finish(&meet_id).await?;
+            let sessions_revoked = state
+                .sessions
+                .remove_sessions_for_meet(&meet_id, "")
+                .await
+                .len();
+            let notice = ServerMessage::SessionRevoked {
+                meet_id: meet_id.clone(),
+                reason: "The meet was archived by an operator".to_string(),
+            };
+            let connections_closed = state.connections.disconnect_meet(&meet_id, &notice);
+            Ok((sessions_revoked, connections_closed))
+        })
+        .await?;
+
+    tracing::info!(
+        "Operator archived meet {} ({} sessions revoked, {} connections closed)",
+        meet_id,
+        sessions_revoked,
+        connections_closed
+    );
+    Ok(Json(AdminActionResult {
+        meet_id,
+        sessions_revoked,
+        connections_closed,
+    }))
+}
+
+/// Stop a meet's actor and close the connections relying on it
+async fn evict_actor<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Path(meet_id): Path<String>,
+) -> Result<Json<AdminActionResult>, AppError> {
+    let Some(handle) = state.meet_handles.get(&meet_id).map(|h| h.clone()) else {
+        return Err(AppError::NotFound(format!(
+            "No actor is running for meet {meet_id}"
+        )));
+    };
+    // Wait until the actor has handled what it was sent, so the next one
+    // loads a log nobody else appends to
+    stop_actor(&handle, &meet_id).await?;
+    state.meet_handles.remove_if(&meet_id, |_, running| {
+        running.cmd_tx.same_channel(&handle.cmd_tx)
+    });
+
+    // Their relays came from the old actor, so clients have to join again
+    let notice = ServerMessage::Error {
+        code: "ACTOR_EVICTED".to_string(),
+        message: "The meet was restarted by an operator, please reconnect".to_string(),
+    };
+    let connections_closed = state.connections.disconnect_meet(&meet_id, &notice);
+
+    tracing::info!(
+        "Operator evicted the actor of meet {} ({} connections closed)",
+        meet_id,
+        connections_closed
+    );
+    Ok(Json(AdminActionResult {
+        meet_id,
+        sessions_revoked: 0,
+        connections_closed,
+    }))
+}
+
+/// Revoke sessions of a meet and close their connections
+async fn revoke_sessions<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Path(meet_id): Path<String>,
+    Query(params): Query<RevokeParams>,
+) -> Json<AdminActionResult> {
+    let targets: Vec<_> = state
+        .auth
+        .sessions_for_meet(&meet_id)
+        .await
+        .into_iter()
+        .filter(|session| {
+            params
+                .location_name
+                .as_deref()
+                .is_none_or(|location| location == session.location_name)
+        })
+        .collect();
+
+    let notice = ServerMessage::SessionRevoked {
+        meet_id: meet_id.clone(),
+        reason: "Session revoked by an operator".to_string(),
+    };
+    let mut connections_closed = 0;
+    for target in &targets {
+        state.auth.remove_session(&target.token).await;
+        connections_closed += state.connections.disconnect_session(&target.token, &notice);
+    }
+
+    tracing::info!(
+        "Operator revoked {} sessions of meet {} ({} connections closed)",
+        targets.len(),
+        meet_id,
+        connections_closed
+    );
+    Json(AdminActionResult {
+        meet_id,
+        sessions_revoked: targets.len(),
+        connections_closed,
+    })
+}
+
+/// Encrypt sessions with a fresh key from now on
+async fn rotate_session_key<S>(
+    State(state): State<Arc<AppState<S>>>,
+) -> Result<Json<KeyRotation>, AppError> {
+    if state.settings.sessions.key_secret().is_some() {
+        return Err(AppError::InvalidInput(
+            "Keys derived from sessions.key_secret are rotated by changing the secret".to_string(),
+        ));
+    }
+
+    let key_id = state
+        .sessions
+        .rotate_encryption_key()
+        .await
+        .map_err(|err| AppError::Internal(format!("Failed to rotate the session key: {err}")))?
+        .ok_or_else(|| {
+            AppError::InvalidInput("The session store keeps no encrypted sessions".to_string())
+        })?;
+
+    tracing::info!(
+        "Operator rotated the session encryption key to key {}",
+        key_id
+    );
+    Ok(Json(KeyRotation { key_id }))
+}
+
+/// Search the audit log, oldest match first
+async fn audit_events<S>(
+    State(state): State<Arc<AppState<S>>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditRecord>>, AppError> {
+    let dir = state.settings.storage.path.join(AUDIT_DIR);
+    let records = tokio::task::spawn_blocking(move || audit::query(&dir, &query))
+        .await
+        .map_err(|err| AppError::Internal(err.to_string()))??;
+    Ok(Json(records))
+}
+
+/// Check that no audit record was changed, removed or reordered
+async fn verify_audit<S>(
+    State(state): State<Arc<AppState<S>>>,
+) -> Result<Json<ChainReport>, AppError> {
+    let dir = state.settings.storage.path.join(AUDIT_DIR);
+    let report = tokio::task::spawn_blocking(move || audit::verify(&dir))
+        .await
+        .map_err(|err| AppError::Internal(err.to_string()))??;
+    if !report.ok {
+        tracing::warn!(
+            "Audit log chain is broken in {} places",
+            report.breaks.len()
+        );
+    }
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditEvent, AuditKind};
+    use crate::config::SessionBackend;
+    use crate::meet_actor::spawn_meet_actor;
+    use crate::storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, StorageOp};
+    use crate::test_support::app_state;
+    use crate::ws_router::create_router;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use std::io::ErrorKind;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "operator-token";
+
+    async fn setup(token: Option<&str>) -> (Arc<AppState<MemoryStorage>>, TempDir) {
+        let (state, temp_dir) = app_state(MemoryStorage::new(), |settings| {
+            settings.admin.token = token.map(str::to_string);
+        })
+        .await;
+        for meet_id in ["idle-meet", "live-meet"] {
+            state.meets.register(meet_id).await.unwrap();
+        }
+        let handle = spawn_meet_actor("live-meet", state.storage.clone()).await;
+        state.meet_handles.insert("live-meet".to_string(), handle);
+        for location_name in ["Platform A", "Platform B"] {
+            state
+                .auth
+                .new_session("live-meet".to_string(), location_name.to_string(), 1)
+                .await;
+        }
+        (Arc::new(state), temp_dir)
+    }
+
+    async fn request<S: Storage + Clone + Send + Sync + 'static>(
+        state: &Arc<AppState<S>>,
+        method: &str,
+        uri: &str,
+        token: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = create_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_admin_needs_operator_token() {
+        let (state, _temp_dir) = setup(None).await;
+        let (status, _) = request(&state, "GET", "/admin/meets", TOKEN).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (state, _temp_dir) = setup(Some(TOKEN)).await;
+        let (status, _) = request(&state, "GET", "/admin/meets", "operator-tokeN").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = request(&state, "POST", "/admin/meets/live-meet/archive", "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(state.meet_handles.contains_key("live-meet"));
+    }
+
+    #[tokio::test]
+    async fn test_list_meets() {
+        let (state, _temp_dir) = setup(Some(TOKEN)).await;
+        state.meets.register("done-meet").await.unwrap();
+        state.meets.finish("done-meet").await.unwrap();
+
+        let (status, body) = request(&state, "GET", "/admin/meets", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        let lifecycles: Vec<(&str, &str)> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|meet| {
+                (
+                    meet["meet_id"].as_str().unwrap(),
+                    meet["lifecycle"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            lifecycles,
+            vec![
+                ("done-meet", "finished"),
+                ("idle-meet", "idle"),
+                ("live-meet", "running")
+            ]
+        );
+        let live = &body[2];
+        assert_eq!(live["sessions"], 2);
+        assert_eq!(live["actor"]["server_seq"], 0);
+        assert_eq!(live["actor"]["mailbox_depth"], 0);
+
+        let (status, detail) = request(&state, "GET", "/admin/meets/live-meet", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(detail["session_list"][1]["location_name"], "Platform B");
+        let (status, _) = request(&state, "GET", "/admin/meets/no-meet", TOKEN).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_stuck_actors_share_one_timeout() {
+        let (state, _temp_dir) = setup(Some(TOKEN)).await;
+        // Actors that never read their mailbox
+        let mut mailboxes = Vec::new();
+        for meet_id in ["stuck-1", "stuck-2", "stuck-3"] {
+            let (cmd_tx, cmd_rx) = tokio::sync::mpsc::unbounded_channel();
+            let (relay_tx, _) = tokio::sync::broadcast::channel(1);
+            mailboxes.push(cmd_rx);
+            state
+                .meet_handles
+                .insert(meet_id.to_string(), MeetHandle { cmd_tx, relay_tx });
+        }
+
+        let started = Instant::now();
+        let (status, body) = request(&state, "GET", "/admin/meets", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(started.elapsed() < ACTOR_STATS_TIMEOUT * 2);
+        let stuck = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|meet| meet["lifecycle"] == "unresponsive")
+            .count();
+        assert_eq!(stuck, 3);
+    }
+
+    #[tokio::test]
+    async fn test_admin_actions() {
+        let (state, _temp_dir) = setup(Some(TOKEN)).await;
+
+        let uri = "/admin/meets/live-meet/sessions?location_name=Platform%20A";
+        let (status, body) = request(&state, "DELETE", uri, TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sessions_revoked"], 1);
+        assert_eq!(state.auth.sessions_for_meet("live-meet").await.len(), 1);
+
+        let running = state.meet_handles.get("live-meet").unwrap().clone();
+        let (status, _) = request(&state, "POST", "/admin/meets/live-meet/evict", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(running.cmd_tx.is_closed());
+        assert!(!state.meet_handles.contains_key("live-meet"));
+        let (status, _) = request(&state, "POST", "/admin/meets/live-meet/evict", TOKEN).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let running = state.meet_handle("live-meet").await;
+        let (status, body) = request(&state, "POST", "/admin/meets/live-meet/archive", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sessions_revoked"], 1);
+        assert!(running.cmd_tx.is_closed());
+        assert!(!state.meet_handles.contains_key("live-meet"));
+        assert_eq!(
+            state.meets.get("live-meet").await.unwrap().status,
+            MeetStatus::Finished
+        );
+        let (status, _) = request(&state, "POST", "/admin/meets/live-meet/archive", TOKEN).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_failed_archive_changes_nothing() {
+        let plan = FaultPlan::new().always(StorageOp::ArchiveMeet, Fault::Error(ErrorKind::Other));
+        let (state, _temp_dir) =
+            app_state(FaultyStorage::new(MemoryStorage::new(), plan), |settings| {
+                settings.admin.token = Some(TOKEN.to_string());
+            })
+            .await;
+        state.meets.register("live-meet").await.unwrap();
+        let session = state
+            .auth
+            .new_session("live-meet".to_string(), "Platform A".to_string(), 1)
+            .await;
+        let state = Arc::new(state);
+
+        let (status, _) = request(&state, "POST", "/admin/meets/live-meet/archive", TOKEN).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        // The meet stays current and usable
+        assert!(state.auth.validate_session(&session).await);
+        assert_eq!(
+            state.meets.get("live-meet").await.unwrap().status,
+            MeetStatus::Current
+        );
+        assert!(!state.meet_handles.contains_key("live-meet"));
+        assert!(!state.meet_handle("live-meet").await.cmd_tx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_session_key() {
+        // Memory sessions have nothing to re-encrypt
+        let (state, _temp_dir) = setup(Some(TOKEN)).await;
+        let (status, _) = request(&state, "POST", "/admin/sessions/rotate-key", TOKEN).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (state, _temp_dir) = app_state(MemoryStorage::new(), |settings| {
+            settings.sessions.backend = SessionBackend::File;
+            settings.admin.token = Some(TOKEN.to_string());
+        })
+        .await;
+        let state = Arc::new(state);
+        let session = state
+            .auth
+            .new_session("live-meet".to_string(), "Platform A".to_string(), 1)
+            .await;
+
+        let (status, body) = request(&state, "POST", "/admin/sessions/rotate-key", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["key_id"], 2);
+        assert!(state.auth.validate_session(&session).await);
+    }
+
+    #[tokio::test]
+    async fn test_audit() {
+        let (state, temp_dir) = setup(Some(TOKEN)).await;
+        let dir = temp_dir.path().join(AUDIT_DIR);
+        let log = audit::AuditLog::open(&dir).unwrap();
+        for (kind, meet_id) in [
+            (AuditKind::LoginSucceeded, "live-meet"),
+            (AuditKind::LoginFailed, "live-meet"),
+            (AuditKind::LoginFailed, "idle-meet"),
+        ] {
+            log.append(AuditEvent::new(kind).with_meet(meet_id))
+                .unwrap();
+        }
+
+        let uri = "/admin/audit?event=login_failed&meet_id=live-meet";
+        let (status, body) = request(&state, "GET", uri, TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        let records = body.as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["seq"], 2);
+
+        let (status, body) = request(&state, "GET", "/admin/audit/verify", TOKEN).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ok"], true);
+        assert_eq!(body["records"], 3);
+
+        let path = audit::day_path(&dir, chrono::Utc::now().date_naive());
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, text.replacen("idle-meet", "live-meet", 1)).unwrap();
+        let (_, body) = request(&state, "GET", "/admin/audit/verify", TOKEN).await;
+        assert_eq!(body["ok"], false);
+        assert_eq!(body["breaks"][0]["seq"], 3);
+    }
+}
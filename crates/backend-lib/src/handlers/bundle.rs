@@ -0,0 +1,392 @@
+// crates/backend-lib/src/handlers/bundle.rs
+
+//! HTTP endpoints for meet bundles.
+//!
+//! `GET /meets/{meet_id}/bundle` exports a current meet to its director, who
+//! sends their session token as `Authorization: Bearer <token>`.
+//!
+//! `POST /meets/import` takes a bundle as the request body and the meet
+//! password, which must match the bundle, in `X-Meet-Password`. Importing a
+//! new meet needs the operator token from `admin.token` as the bearer token.
+//! Replacing a current meet needs `?force=true` and either the operator token
+//! or a director session of the meet being replaced. Its actor is stopped
+//! before anything is written, and its clients are disconnected and have to
+//! join again.
+use crate::{
+    audit::{self, AuditEvent, AuditKind},
+    auth::{verify_password, ConnectionAuthz},
+    bundle::{BundleManifest, MeetBundle},
+    error::AppError,
+    meet_registry::MeetStatus,
+    messages::{ServerMessage, Session, SessionRole},
+    middleware::is_operator,
+    storage::Storage,
+    AppState,
+};
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Header carrying the meet password of an imported bundle
+pub const MEET_PASSWORD_HEADER: &str = "x-meet-password";
+
+/// Query parameters of an import
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportParams {
+    /// Replace a current meet with the same ID
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Resolve the bearer token of a request to a director session, of
+/// `meet_id` if given
+async fn require_director<S>(
+    state: &AppState<S>,
+    meet_id: Option<&str>,
+    headers: &HeaderMap,
+) -> Result<Session, AppError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Auth("Missing session token".to_string()))?;
+
+    let mut authz = ConnectionAuthz::new();
+    let session = match meet_id {
+        Some(meet_id) => authz.authorize(state.auth.as_ref(), meet_id, token).await,
+        None => authz.resolve(state.auth.as_ref(), token).await,
+    }
+    .map_err(|err| AppError::Auth(err.to_string()))?
+    .clone();
+    if session.role != SessionRole::Director {
+        audit::record(
+            AuditEvent::new(AuditKind::PermissionDenied)
+                .with_meet(&session.meet_id)
+                .with_location(&session.location_name)
+                .with_detail("Bundle export or import needs the director role"),
+        );
+        return Err(AppError::Auth(
+            "Only the meet director can export or replace a meet".to_string(),
+        ));
+    }
+    Ok(session)
+}
+
+/// Export a current meet as a bundle
+pub async fn export_handler<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Path(meet_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    require_director(&state, Some(&meet_id), &headers).await?;
+
+    // The actor answers between two updates, so the log and state agree
+    let export = state.meet_handle(&meet_id).await.export().await?;
+    let bundle = MeetBundle::from_export(&meet_id, export).to_bytes()?;
+    tracing::info!("Exported meet {} ({} bytes)", meet_id, bundle.len());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{meet_id}.tar\""),
+            ),
+        ],
+        bundle,
+    ))
+}
+
+/// Import a bundle as a current meet
+pub async fn import_handler<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(params): Query<ImportParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<BundleManifest>, AppError> {
+    let ip = addr.ip();
+    if !state.auth_rate_limiter.check_rate_limit(ip) {
+        return Err(AppError::AuthRateLimited);
+    }
+
+    // Only the operator brings in new meets; a director may replace their
+    // own. Both are checked before the body is parsed.
+    let director = if is_operator(&state.settings, &headers) {
+        None
+    } else {
+        if !params.force {
+            audit::record(
+                AuditEvent::new(AuditKind::PermissionDenied)
+                    .with_ip(Some(ip))
+                    .with_detail("Importing a new meet needs the operator token"),
+            );
+            return Err(AppError::Auth(
+                "Importing a meet needs the operator token".to_string(),
+            ));
+        }
+        Some(require_director(&state, None, &headers).await?)
+    };
+
+    let bundle = MeetBundle::from_bytes(&body)?;
+    let meet_id = bundle.manifest.meet_id.clone();
+    if let Some(director) = director {
+        if director.meet_id != meet_id {
+            audit::record(
+                AuditEvent::new(AuditKind::PermissionDenied)
+                    .with_ip(Some(ip))
+                    .with_meet(&meet_id)
+                    .with_location(&director.location_name)
+                    .with_detail(format!(
+                        "Director of meet {} tried to replace this meet",
+                        director.meet_id
+                    )),
+            );
+            return Err(AppError::Auth(
+                "A director can only replace their own meet".to_string(),
+            ));
+        }
+    }
+
+    let password = headers
+        .get(MEET_PASSWORD_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !verify_password(&bundle.info.password_hash, password) {
+        state.auth_rate_limiter.record_failed_attempt(ip);
+        return Err(AppError::InvalidPassword);
+    }
+    state.auth_rate_limiter.record_success(ip);
+
+    let registered = state.meets.register(&meet_id).await?;
+    if !registered {
+        let current = state
+            .meets
+            .get(&meet_id)
+            .await
+            .is_some_and(|record| record.status == MeetStatus::Current);
+        if !params.force || !current {
+            return Err(AppError::MeetExists(meet_id));
+        }
+
+        // Every session and connection belongs to the meet being replaced
+        state.sessions.remove_sessions_for_meet(&meet_id, "").await;
+        let notice = ServerMessage::SessionRevoked {
+            meet_id: meet_id.clone(),
+            reason: "The meet was replaced by an import".to_string(),
+        };
+        state.connections.disconnect_meet(&meet_id, &notice);
+    }
+
+    let written = state
+        .with_actor_stopped(&meet_id, bundle.write_to(&state.storage, &meet_id))
+        .await;
+    if let Err(err) = written {
+        if registered {
+            state.meets.release(&meet_id).await?;
+        }
+        return Err(err);
+    }
+    tracing::info!(
+        "Imported meet {} with {} updates",
+        meet_id,
+        bundle.manifest.server_seq
+    );
+    Ok(Json(bundle.manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::hash_password;
+    use crate::storage::MemoryStorage;
+    use crate::test_support::app_state;
+    use crate::ws_router::create_router;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use openlifter_common::EndpointPriority;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    const PASSWORD: &str = "Bundle-Pass1!";
+    const OPERATOR: &str = "operator-token";
+
+    async fn setup() -> (Arc<AppState<MemoryStorage>>, TempDir) {
+        let (state, temp_dir) = app_state(MemoryStorage::new(), |settings| {
+            settings.admin.token = Some(OPERATOR.to_string());
+        })
+        .await;
+        (Arc::new(state), temp_dir)
+    }
+
+    async fn create_meet(state: &AppState<MemoryStorage>, meet_id: &str) -> (String, String) {
+        assert!(state.meets.register(meet_id).await.unwrap());
+        let endpoints = [EndpointPriority {
+            location_name: "Platform A".to_string(),
+            priority: 1,
+        }];
+        state
+            .storage
+            .store_meet_info(meet_id, &hash_password(PASSWORD).unwrap(), &endpoints)
+            .await
+            .unwrap();
+        state
+            .storage
+            .append_update(meet_id, r#"{"update_key":"a","update_value":1,"local_seq_num":1,"after_server_seq_num":0,"serverSeqNum":1}"#)
+            .await
+            .unwrap();
+        let director = state
+            .auth
+            .new_session_with_role(
+                meet_id.to_string(),
+                "Platform A".to_string(),
+                1,
+                SessionRole::Director,
+            )
+            .await;
+        let station = state
+            .auth
+            .new_session(meet_id.to_string(), "Platform B".to_string(), 1)
+            .await;
+        (director, station)
+    }
+
+    async fn export(state: &Arc<AppState<MemoryStorage>>, token: &str) -> (StatusCode, Vec<u8>) {
+        let request = Request::builder()
+            .uri("/meets/meet-1/bundle")
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = create_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    async fn import(
+        state: &Arc<AppState<MemoryStorage>>,
+        uri: &str,
+        password: &str,
+        token: Option<&str>,
+        bundle: Vec<u8>,
+    ) -> StatusCode {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(MEET_PASSWORD_HEADER, password)
+            .extension(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let request = request.body(Body::from(bundle)).unwrap();
+        create_router(state.clone())
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_export_needs_director() {
+        let (state, _temp_dir) = setup().await;
+        let (director, station) = create_meet(&state, "meet-1").await;
+
+        assert_eq!(
+            export(&state, "no-such-token").await.0,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(export(&state, &station).await.0, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = export(&state, &director).await;
+        assert_eq!(status, StatusCode::OK);
+        let bundle = MeetBundle::from_bytes(&body).unwrap();
+        assert_eq!(bundle.manifest.meet_id, "meet-1");
+        assert_eq!(bundle.updates.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import() {
+        let (source, _source_dir) = setup().await;
+        let (director, _) = create_meet(&source, "meet-1").await;
+        let bundle = export(&source, &director).await.1;
+
+        let (target, _target_dir) = setup().await;
+        let uri = "/meets/import";
+        // Nothing is parsed for a client that may not import
+        assert_eq!(
+            import(&target, uri, PASSWORD, None, b"not a bundle".to_vec()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // New meets need the operator token; the bundle's own password is not enough
+        assert_eq!(
+            import(&target, uri, PASSWORD, None, bundle.clone()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert!(!target.meets.meet_exists("meet-1").await);
+        assert_eq!(
+            import(&target, uri, "wrong", Some(OPERATOR), bundle.clone()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            import(&target, uri, PASSWORD, Some(OPERATOR), bundle.clone()).await,
+            StatusCode::OK
+        );
+        assert!(target.meets.meet_exists("meet-1").await);
+        assert_eq!(
+            target.storage.read_updates("meet-1").await.unwrap().len(),
+            1
+        );
+
+        // The ID is taken now
+        assert_eq!(
+            import(&target, uri, PASSWORD, Some(OPERATOR), bundle.clone()).await,
+            StatusCode::CONFLICT
+        );
+        let forced = "/meets/import?force=true";
+        assert_eq!(
+            import(&target, forced, PASSWORD, None, bundle.clone()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // A director of another meet cannot replace this one
+        let (other_director, _) = create_meet(&target, "meet-2").await;
+        assert_eq!(
+            import(
+                &target,
+                forced,
+                PASSWORD,
+                Some(&other_director),
+                bundle.clone()
+            )
+            .await,
+            StatusCode::UNAUTHORIZED
+        );
+        let target_director = target
+            .auth
+            .new_session_with_role(
+                "meet-1".to_string(),
+                "Platform A".to_string(),
+                1,
+                SessionRole::Director,
+            )
+            .await;
+        let running = target.meet_handle("meet-1").await;
+        assert_eq!(
+            import(&target, forced, PASSWORD, Some(&target_director), bundle).await,
+            StatusCode::OK
+        );
+        // Sessions of the replaced meet are gone, and its actor was stopped
+        assert!(!target.auth.validate_session(&target_director).await);
+        assert!(running.cmd_tx.is_closed());
+        assert!(!target.meet_handles.contains_key("meet-1"));
+    }
+}
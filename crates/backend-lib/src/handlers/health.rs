@@ -0,0 +1,252 @@
+// crates/backend-lib/src/handlers/health.rs
+
+//! Liveness and readiness endpoints.
+//!
+//! - `GET /health/live` (also `GET /health`) answers 200 while the process
+//!   can serve requests at all, including while draining
+//! - `GET /health/ready` answers 200 only if the data directory is writable,
+//!   the session store answers, no meet actor has died, every background
+//!   task is beating and the server is not draining; otherwise 503
+//!
+//! Both return JSON describing what was checked.
+use crate::{health::TaskStatus, meet_registry::MeetStatus, storage::Storage, AppState};
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How long each readiness probe may take before it counts as failed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Prefix of the file written and removed under the data directory to check
+/// it is writable; each probe adds its own suffix so concurrent probes don't
+/// remove each other's file
+const PROBE_FILE: &str = ".ready-probe";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Liveness {
+    /// `ok`, or `draining` once shutdown has started
+    pub status: &'static str,
+    pub uptime_secs: u64,
+}
+
+/// Outcome of one readiness probe
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Check {
+    fn from_result(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self {
+                ok: true,
+                error: None,
+            },
+            Err(error) => Self {
+                ok: false,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionsCheck {
+    pub ok: bool,
+    /// Unexpired sessions, if the store answered in time
+    pub active: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActorCounts {
+    /// Actors whose command channel is open
+    pub live: usize,
+    /// Actors that stopped while their handle was still in use
+    pub dead: usize,
+    /// Current meets in the registry, with or without an actor
+    pub registered: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub draining: bool,
+    pub storage: Check,
+    pub sessions: SessionsCheck,
+    pub actors: ActorCounts,
+    pub tasks: Vec<TaskStatus>,
+}
+
+/// Report that the process is up
+pub async fn live_handler<S>(State(state): State<Arc<AppState<S>>>) -> Json<Liveness> {
+    Json(Liveness {
+        status: if state.health.is_draining() {
+            "draining"
+        } else {
+            "ok"
+        },
+        uptime_secs: state.health.uptime().as_secs(),
+    })
+}
+
+/// Report whether the server should be sent new connections
+pub async fn ready_handler<S: Storage + Send + Sync + Clone + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+) -> (StatusCode, Json<Readiness>) {
+    let readiness = readiness(&state).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+/// Run every readiness probe
+pub async fn readiness<S>(state: &AppState<S>) -> Readiness {
+    let storage = Check::from_result(probe_writable(&state.settings.storage.path).await);
+
+    let active = tokio::time::timeout(PROBE_TIMEOUT, state.sessions.active_session_count())
+        .await
+        .ok();
+    let sessions = SessionsCheck {
+        ok: active.is_some(),
+        active,
+    };
+
+    let dead = state
+        .meet_handles
+        .iter()
+        .filter(|entry| entry.cmd_tx.is_closed())
+        .count();
+    let actors = ActorCounts {
+        live: state.meet_handles.len() - dead,
+        dead,
+        registered: state
+            .meets
+            .list_meets()
+            .await
+            .iter()
+            .filter(|record| record.status == MeetStatus::Current)
+            .count(),
+    };
+
+    let tasks = state.health.tasks();
+    let draining = state.health.is_draining();
+    Readiness {
+        ready: !draining
+            && storage.ok
+            && sessions.ok
+            && actors.dead == 0
+            && tasks.iter().all(|task| task.ok),
+        draining,
+        storage,
+        sessions,
+        actors,
+        tasks,
+    }
+}
+
+/// Write and remove a file in `dir`
+async fn probe_writable(dir: &Path) -> Result<(), String> {
+    let path = dir.join(format!("{}.{}", PROBE_FILE, Uuid::new_v4()));
+    let probe = async {
+        tokio::fs::write(&path, b"ok").await?;
+        tokio::fs::remove_file(&path).await
+    };
+    match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(err)) => Err(format!("{}: {}", dir.display(), err)),
+        Err(_) => Err(format!("{}: write timed out", dir.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::TASK_SESSION_CLEANUP;
+    use crate::meet_actor::spawn_meet_actor;
+    use crate::storage::MemoryStorage;
+    use crate::test_support::app_state;
+    use crate::ws_router::create_router;
+    use axum::{body::Body, http::Request};
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    async fn setup() -> (Arc<AppState<MemoryStorage>>, TempDir) {
+        let (state, temp_dir) = app_state(MemoryStorage::new(), |_| {}).await;
+        state.meets.register("live-meet").await.unwrap();
+        let handle = spawn_meet_actor("live-meet", state.storage.clone()).await;
+        state.meet_handles.insert("live-meet".to_string(), handle);
+        (Arc::new(state), temp_dir)
+    }
+
+    async fn get(
+        state: &Arc<AppState<MemoryStorage>>,
+        uri: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = create_router(state.clone())
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_ready() {
+        let (state, _temp_dir) = setup().await;
+        state
+            .health
+            .register(TASK_SESSION_CLEANUP, Duration::from_secs(60));
+
+        let (status, body) = get(&state, "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["storage"]["ok"], true);
+        assert_eq!(body["sessions"]["active"], 0);
+        assert_eq!(body["actors"]["live"], 1);
+        assert_eq!(body["actors"]["registered"], 1);
+        assert_eq!(body["tasks"][0]["name"], TASK_SESSION_CLEANUP);
+    }
+
+    #[tokio::test]
+    async fn test_not_ready() {
+        let (state, temp_dir) = setup().await;
+        state
+            .health
+            .register(TASK_SESSION_CLEANUP, Duration::from_millis(1));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(temp_dir);
+
+        let (status, body) = get(&state, "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["storage"]["ok"], false);
+        assert!(body["storage"]["error"].is_string());
+        assert_eq!(body["tasks"][0]["ok"], false);
+    }
+
+    #[tokio::test]
+    async fn test_draining() {
+        let (state, _temp_dir) = setup().await;
+        state.health.start_draining();
+
+        let (status, body) = get(&state, "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["draining"], true);
+
+        // Draining servers are still alive
+        let (status, body) = get(&state, "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "draining");
+    }
+}
@@ -4,31 +4,23 @@
 use crate::auth::{
     hash_password, validate_password_strength, verify_password, PasswordRequirements,
 };
+use crate::metrics::{self as metric_names, LABEL_MEET_ID, LABEL_MESSAGE_TYPE};
 use crate::storage::Storage;
 use crate::{error::AppError, AppState};
 use axum::extract::ws::Message;
-use metrics::{counter, gauge, histogram};
+use metrics::{counter, histogram};
 use openlifter_common::{ClientToServer, ServerToClient};
-use rand::Rng;
 use std::time::Instant;
 use tokio::sync::mpsc;
 
-/// Helper function to update session metrics
-fn update_session_metrics(event_type: &str, updates_len: Option<usize>, csv_len: Option<usize>) {
-    let _ = counter!(format!("live.session.{}", event_type), &[("value", "1")]);
-    let _ = gauge!(
-        "live.session.active",
-        &[("value", if event_type == "ended" { "-1" } else { "1" })]
-    );
-
-    if let Some(len) = updates_len {
-        let _ = histogram!("update.batch_size", &[("value", len.to_string())]);
-        let _ = gauge!("handler.updates_length", &[("value", len.to_string())]);
-    }
-
-    if let Some(len) = csv_len {
-        let _ = histogram!("publish.csv_size", &[("value", len.to_string())]);
-        let _ = gauge!("handler.csv_length", &[("value", len.to_string())]);
+/// `msgType` of a client message, used as a metric label
+fn message_type(msg: &ClientToServer) -> &'static str {
+    match msg {
+        ClientToServer::CreateMeet { .. } => "CreateMeet",
+        ClientToServer::JoinMeet { .. } => "JoinMeet",
+        ClientToServer::UpdateInit { .. } => "UpdateInit",
+        ClientToServer::ClientPull { .. } => "ClientPull",
+        ClientToServer::PublishMeet { .. } => "PublishMeet",
     }
 }
 
@@ -54,6 +46,7 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
     tx: mpsc::Sender<Message>,
 ) -> Result<(), AppError> {
     let start = Instant::now();
+    let message_type = message_type(&msg);
 
     match msg {
         ClientToServer::CreateMeet {
@@ -74,16 +67,8 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 return Ok(());
             }
 
-            // Generate a meet ID
-            let meet_id = {
-                let mut rng = rand::thread_rng();
-                format!(
-                    "{}-{}-{}",
-                    rng.gen_range(100..1000),
-                    rng.gen_range(100..1000),
-                    rng.gen_range(100..1000)
-                )
-            };
+            // Allocate a meet ID
+            let meet_id = crate::meet_id::allocate_meet_id(state.meets.as_ref()).await?;
 
             // Hash the password
             let hashed_password =
@@ -116,8 +101,7 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 .await
                 .map_err(|_| AppError::Internal("Failed to send message".to_string()))?;
 
-            // Update metrics
-            update_session_metrics("created", None, None);
+            counter!(metric_names::MEET_CREATED).increment(1);
         },
 
         ClientToServer::JoinMeet {
@@ -160,8 +144,7 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 .await
                 .map_err(|_| AppError::Internal("Failed to send message".to_string()))?;
 
-            // Update metrics
-            update_session_metrics("joined", None, None);
+            counter!(metric_names::MEET_JOINED, LABEL_MEET_ID => meet_id).increment(1);
         },
 
         ClientToServer::UpdateInit {
@@ -181,9 +164,6 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 .get(&session.meet_id)
                 .ok_or(AppError::MeetNotFound)?;
 
-            // Store updates length before moving
-            let updates_len = updates.len();
-
             // Convert from openlifter_common::Update to backend's Update format
             let backend_updates = updates
                 .into_iter()
@@ -219,9 +199,6 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
             tx.send(Message::Text(json.into()))
                 .await
                 .map_err(|_| AppError::Internal("Failed to send message".to_string()))?;
-
-            // Update metrics
-            update_session_metrics("updated", Some(updates_len), None);
         },
 
         ClientToServer::ClientPull {
@@ -241,24 +218,21 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 .get(&session.meet_id)
                 .ok_or(AppError::MeetNotFound)?;
 
-            // Get updates since last seen
-            let updates = handle.get_updates_since(last_server_seq).await?;
-            let updates_len = updates.len();
+            // Get one page of updates since last seen
+            let page = handle
+                .get_update_page(last_server_seq, state.settings.websocket.pull_page_size)
+                .await?;
 
             // Send response
             let reply = ServerToClient::ServerPull {
                 last_server_seq,
-                updates_relayed: updates.clone(),
+                has_more: page.has_more(),
+                updates_relayed: page.updates,
             };
             let json = serde_json::to_string(&reply)?;
             tx.send(Message::Text(json.into()))
                 .await
                 .map_err(|_| AppError::Internal("Failed to send message".to_string()))?;
-
-            // Update metrics
-            let _ = counter!("meet.pull", &[("value", "1")]);
-            let _ = histogram!("pull.updates_count", &[("value", updates_len.to_string())]);
-            let _ = gauge!("live.session.active", &[("value", "1")]);
         },
 
         ClientToServer::PublishMeet {
@@ -280,7 +254,6 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
                 .ok_or(AppError::MeetNotFound)?;
 
             // Store CSV data
-            let csv_len = opl_csv.len();
             handle.store_csv_data(opl_csv, return_email).await?;
 
             // Send response
@@ -289,14 +262,12 @@ pub async fn handle_client_message<S: Storage + Send + Sync + Clone + 'static>(
             tx.send(Message::Text(json.into()))
                 .await
                 .map_err(|_| AppError::Internal("Failed to send message".to_string()))?;
-
-            // Update metrics
-            update_session_metrics("published", None, Some(csv_len));
         },
     }
 
-    // Record handler duration
-    let _duration = start.elapsed();
+    counter!(metric_names::WS_MESSAGES, LABEL_MESSAGE_TYPE => message_type).increment(1);
+    histogram!(metric_names::WS_MESSAGE_DURATION_MS, LABEL_MESSAGE_TYPE => message_type)
+        .record(start.elapsed().as_secs_f64() * 1000.0);
 
     Ok(())
 }
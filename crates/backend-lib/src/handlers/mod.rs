@@ -2,4 +2,7 @@
 
 //! Request handlers for the WebSocket server.
 
+pub mod admin;
+pub mod bundle;
+pub mod health;
 pub mod live;
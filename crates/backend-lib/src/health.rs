@@ -0,0 +1,165 @@
+// crates/backend-lib/src/health.rs
+
+/** Server health as reported by `/health/live` and `/health/ready`.
+Background tasks report in through a `Heartbeat`; a task that has not beaten
+for `STALE_AFTER_PERIODS` of its periods counts as stalled. `Health` also
+holds the draining flag, set when shutdown starts so that readiness fails
+while the listener is still open. */
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Task removing expired sessions
+pub const TASK_SESSION_CLEANUP: &str = "session_cleanup";
+/// Task writing persisted sessions to disk
+pub const TASK_AUTO_SAVE: &str = "auto_save";
+
+/// Periods a task may miss before it counts as stalled
+const STALE_AFTER_PERIODS: u32 = 2;
+
+/// Time of a background task's last run, shared with whoever watches it
+#[derive(Debug, Clone)]
+pub struct Heartbeat(Arc<Mutex<Instant>>);
+
+impl Heartbeat {
+    /// Create a heartbeat that last beat now
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    /// Record that the task ran
+    pub fn beat(&self) {
+        *self.0.lock().unwrap() = Instant::now();
+    }
+
+    /// Time since the last beat
+    pub fn elapsed(&self) -> Duration {
+        self.0.lock().unwrap().elapsed()
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A watched task and how often it should beat
+struct TrackedTask {
+    period: Duration,
+    heartbeat: Heartbeat,
+}
+
+/// State of one background task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub period_secs: u64,
+    /// Seconds since the task last ran
+    pub last_beat_secs: u64,
+    /// Whether the task has run within `STALE_AFTER_PERIODS` periods
+    pub ok: bool,
+}
+
+/// Process-wide health state shared through `AppState`
+pub struct Health {
+    started: Instant,
+    draining: AtomicBool,
+    tasks: DashMap<String, TrackedTask>,
+}
+
+impl Health {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            draining: AtomicBool::new(false),
+            tasks: DashMap::new(),
+        }
+    }
+
+    /// Watch a new task expected to beat every `period`
+    /// # Returns
+    /// * `Heartbeat` - Handle the task beats on each run
+    pub fn register(&self, name: &str, period: Duration) -> Heartbeat {
+        let heartbeat = Heartbeat::new();
+        self.track(name, period, heartbeat.clone());
+        heartbeat
+    }
+
+    /// Watch a task that already has a heartbeat, replacing any task of that name
+    pub fn track(&self, name: &str, period: Duration, heartbeat: Heartbeat) {
+        self.tasks
+            .insert(name.to_string(), TrackedTask { period, heartbeat });
+    }
+
+    /// Mark the server as shutting down; it stays alive but is no longer ready
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// State of every watched task, by name
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        let mut tasks: Vec<TaskStatus> = self
+            .tasks
+            .iter()
+            .map(|entry| {
+                let elapsed = entry.heartbeat.elapsed();
+                TaskStatus {
+                    name: entry.key().clone(),
+                    period_secs: entry.period.as_secs(),
+                    last_beat_secs: elapsed.as_secs(),
+                    ok: elapsed <= entry.period * STALE_AFTER_PERIODS,
+                }
+            })
+            .collect();
+        tasks.sort_by(|a, b| a.name.cmp(&b.name));
+        tasks
+    }
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stalled_task() {
+        let health = Health::new();
+        let cleanup = health.register(TASK_SESSION_CLEANUP, Duration::from_millis(10));
+        health.track(TASK_AUTO_SAVE, Duration::from_secs(60), Heartbeat::new());
+        assert!(health.tasks().iter().all(|task| task.ok));
+
+        std::thread::sleep(Duration::from_millis(30));
+        let tasks = health.tasks();
+        assert_eq!(tasks[0].name, TASK_AUTO_SAVE);
+        assert!(tasks[0].ok);
+        assert_eq!(tasks[1].name, TASK_SESSION_CLEANUP);
+        assert!(!tasks[1].ok);
+
+        cleanup.beat();
+        assert!(health.tasks().iter().all(|task| task.ok));
+    }
+
+    #[test]
+    fn test_draining() {
+        let health = Health::new();
+        assert!(!health.is_draining());
+        health.start_draining();
+        assert!(health.is_draining());
+    }
+}
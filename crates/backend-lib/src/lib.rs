@@ -2,36 +2,60 @@
 
 #![allow(clippy::all, clippy::nursery, clippy::pedantic)]
 
+pub mod audit;
 pub mod auth;
+pub mod bundle;
 pub mod config;
+pub mod connections;
 pub mod error;
 pub mod handlers;
+pub mod health;
+pub mod log_inspect;
 pub mod meet;
 pub mod meet_actor;
+pub mod meet_id;
+pub mod meet_registry;
 pub mod messages;
 pub mod metrics;
 pub mod middleware;
+pub mod relay;
+pub mod retention;
 pub mod storage;
+#[cfg(test)]
+mod test_support;
 pub mod validation;
 pub mod websocket;
 pub mod ws_router;
 
-use crate::auth::{AuthRateLimiter, AuthService, DefaultAuth, PersistentSessionManager};
+use crate::auth::{
+    AuthRateLimiter, AuthService, DefaultAuth, Keyring, PersistentSessionManager, SessionManager,
+    SessionStore,
+};
+use crate::config::SessionBackend;
 use crate::config::Settings;
+use crate::connections::ConnectionRegistry;
+use crate::error::AppError;
+use crate::health::{Health, TASK_AUTO_SAVE};
 use crate::meet_actor::MeetHandle;
+use crate::meet_registry::{MeetRegistry, StorageMeetRegistry};
 use crate::middleware::rate_limit::RateLimiter;
-use crate::storage::FlatFileStorage;
+use crate::storage::{FlatFileStorage, Storage};
 use std::error::Error;
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// How long a change to a meet waits for the meet's actor to stop
+pub const ACTOR_STOP_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState<S> {
     /// Authentication service
     pub auth: Arc<dyn AuthService>,
-    /// Session manager
-    pub sessions: Arc<PersistentSessionManager>,
+    /// Session store shared with `auth`
+    pub sessions: Arc<dyn SessionStore>,
     /// Storage backend
     pub storage: S,
     /// Configuration settings
@@ -45,14 +69,50 @@ pub struct AppState<S> {
         Arc<dashmap::DashMap<String, Vec<tokio::sync::mpsc::Sender<messages::ServerMessage>>>>,
     /// Active meet handles
     pub meet_handles: Arc<dashmap::DashMap<String, MeetHandle>>,
+    /// Open WebSocket connections
+    pub connections: Arc<ConnectionRegistry>,
+    /// Every current and finished meet
+    pub meets: Arc<dyn MeetRegistry>,
+    /// Background task heartbeats and the draining flag
+    pub health: Arc<Health>,
+}
+
+/// Open the session store selected in `config`
+pub async fn open_session_store(
+    config: &Settings,
+) -> Result<Arc<dyn SessionStore>, Box<dyn Error>> {
+    // Create sessions directory in the storage path
+    let sessions_path = PathBuf::from(&config.storage.path).join("sessions");
+    let keyring = || -> Result<Keyring, Box<dyn Error>> {
+        Ok(match config.sessions.key_secret() {
+            Some(secret) => Keyring::from_secrets(&secret, &config.sessions.previous_key_secrets)?,
+            None => {
+                std::fs::create_dir_all(&sessions_path)?;
+                Keyring::load_or_create(&sessions_path)?
+            },
+        })
+    };
+    Ok(match config.sessions.backend {
+        SessionBackend::File => {
+            Arc::new(PersistentSessionManager::new_with_keyring(&sessions_path, keyring()?).await?)
+        },
+        SessionBackend::Memory => Arc::new(SessionManager::new()),
+        #[cfg(feature = "sqlite")]
+        SessionBackend::Sqlite => Arc::new(
+            crate::auth::SqliteSessionStore::open(sessions_path.join("sessions.db"), keyring()?)
+                .await?,
+        ),
+        #[cfg(not(feature = "sqlite"))]
+        SessionBackend::Sqlite => {
+            return Err("The sqlite session backend needs the `sqlite` feature".into())
+        },
+    })
 }
 
-impl<S> AppState<S> {
+impl<S: Storage + Clone + Send + Sync + 'static> AppState<S> {
     /// Create a new application state
     pub async fn new(storage: S, config: &Settings) -> Result<Self, Box<dyn Error>> {
-        // Create sessions directory in the storage path
-        let sessions_path = PathBuf::from(&config.storage.path).join("sessions");
-        let sessions = PersistentSessionManager::new(&sessions_path).await?;
+        let sessions = open_session_store(config).await?;
 
         let auth_rate_limiter = Arc::new(AuthRateLimiter::default());
         let auth = Arc::new(DefaultAuth::new_with_rate_limiter(
@@ -63,19 +123,99 @@ impl<S> AppState<S> {
         let rate_limiter = Arc::new(RateLimiter::new(std::time::Duration::from_secs(60), 100));
         let clients = Arc::new(dashmap::DashMap::new());
         let meet_handles = Arc::new(dashmap::DashMap::new());
+        let connections = Arc::new(ConnectionRegistry::new());
+        let meets = Arc::new(StorageMeetRegistry::load(storage.clone()).await?);
+        let health = Arc::new(Health::new());
+        if let Some((heartbeat, period)) = sessions.auto_save_heartbeat() {
+            health.track(TASK_AUTO_SAVE, period, heartbeat);
+        }
 
         Ok(Self {
             auth,
-            sessions: Arc::new(sessions),
+            sessions,
             storage,
             settings,
             rate_limiter,
             auth_rate_limiter,
             clients,
             meet_handles,
+            connections,
+            meets,
+            health,
         })
     }
 
+    /// Get the handle for a meet's actor, spawning the actor if needed
+    pub async fn meet_handle(&self, meet_id: &str) -> MeetHandle {
+        if let Some(handle) = self.meet_handles.get(meet_id) {
+            return handle.clone();
+        }
+
+        let handle = meet_actor::spawn_meet_actor(meet_id, self.storage.clone()).await;
+        self.meet_handles
+            .entry(meet_id.to_string())
+            .or_insert(handle)
+            .clone()
+    }
+
+    /** Run `change` on a meet while no actor can append to it.
+    The meet's actor, started first if there is none, is stopped once it has
+    handled what it was sent. Its stopped handle stays registered while
+    `change` runs, so a client asking for the meet in between gets the
+    stopped actor instead of starting a new one that would load the meet
+    half-changed; the handle is dropped once `change` is done. Nothing is
+    changed if the actor does not stop within `ACTOR_STOP_TIMEOUT`.
+    # Returns
+    * `Ok(T)` - What `change` returned
+    * `Err(AppError)` if the actor did not stop in time or `change` failed */
+    pub async fn with_actor_stopped<T>(
+        &self,
+        meet_id: &str,
+        change: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError> {
+        self.stop_actor_for(meet_id, false, change)
+            .await?
+            .ok_or_else(|| AppError::Internal(format!("The actor of meet {meet_id} kept running")))
+    }
+
+    /// Like `with_actor_stopped`, but leaves the meet alone while clients are
+    /// subscribed to its actor, which makes that decision itself so none can
+    /// join in between
+    /// # Returns
+    /// * `Ok(Some(T))` - What `change` returned
+    /// * `Ok(None)` if the meet is in use
+    /// * `Err(AppError)` if the actor did not stop in time or `change` failed
+    pub async fn with_unused_actor_stopped<T>(
+        &self,
+        meet_id: &str,
+        change: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<Option<T>, AppError> {
+        self.stop_actor_for(meet_id, true, change).await
+    }
+
+    async fn stop_actor_for<T>(
+        &self,
+        meet_id: &str,
+        if_unused: bool,
+        change: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<Option<T>, AppError> {
+        let handle = self.meet_handle(meet_id).await;
+        let stopped = tokio::time::timeout(ACTOR_STOP_TIMEOUT, handle.shutdown(if_unused))
+            .await
+            .map_err(|_| {
+                AppError::Internal(format!(
+                    "The actor of meet {meet_id} did not stop in time, try again later"
+                ))
+            })?;
+        if !stopped {
+            return Ok(None);
+        }
+        let changed = change.await;
+        self.meet_handles
+            .remove_if(meet_id, |_, handle| handle.cmd_tx.is_closed());
+        changed.map(Some)
+    }
+
     /// Create a new application state with default settings
     pub async fn new_default() -> Result<Self, anyhow::Error>
     where
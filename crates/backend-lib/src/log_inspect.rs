@@ -0,0 +1,507 @@
+// crates/backend-lib/src/log_inspect.rs
+
+/** Inspection of meet update logs.
+When stations disagree after a meet, these helpers show what the server
+recorded and compare it with another account of the meet:
+- `decode_log` and `read_log_file` turn stored records into decoded updates,
+  keeping track of records that could not be read
+- `UpdateFilter` selects updates by key prefix, client or sequence range, and
+  `format_update` prints one per line
+- `diff_logs` compares two logs update by update and by final value
+- `diff_client_export` compares a log with the updates a client exported
+- `replay` rebuilds the meet state in a fresh `MeetActor` and reports where
+  the log disagrees with itself
+
+Logs are read either through `Storage`, which works for every backend and
+decrypts encrypted meets, or straight from a plaintext `updates.log`. */
+use crate::{
+    error::AppError,
+    meet_actor::MeetActor,
+    storage::{update_log, FlatFileStorage, Storage},
+};
+use openlifter_common::{Update, UpdateWithServerSeq};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::{Range, RangeInclusive};
+use std::path::Path;
+use tokio::sync::broadcast;
+
+/// Meet ID the log is replayed under; the scratch storage holds nothing else
+const REPLAY_MEET_ID: &str = "replay";
+
+/// An update log decoded for inspection
+#[derive(Debug, Clone, Default)]
+pub struct InspectedLog {
+    /// Stored records, in log order
+    pub records: Vec<String>,
+    /// Records that decoded as updates, in log order
+    pub updates: Vec<UpdateWithServerSeq>,
+    /// Position (from 1) and error of every record that did not decode
+    pub undecodable: Vec<(u64, String)>,
+    /// Position and sequence number of every update not numbered above the
+    /// update before it. Gaps are allowed, since a repaired log may have lost
+    /// records.
+    pub misnumbered: Vec<(u64, u64)>,
+    /// Byte ranges of a log file skipped as damaged
+    pub damaged: Vec<Range<u64>>,
+}
+
+/// Decode stored update records
+pub fn decode_log(records: Vec<String>) -> InspectedLog {
+    let mut log = InspectedLog::default();
+    let mut last_seq = 0;
+    for (position, record) in (1u64..).zip(&records) {
+        match serde_json::from_str::<UpdateWithServerSeq>(record) {
+            Ok(update) => {
+                if update.server_seq_num <= last_seq {
+                    log.misnumbered.push((position, update.server_seq_num));
+                }
+                last_seq = last_seq.max(update.server_seq_num);
+                log.updates.push(update);
+            },
+            Err(err) => log.undecodable.push((position, err.to_string())),
+        }
+    }
+    log.records = records;
+    log
+}
+
+/// Read a meet's update log through storage
+pub async fn read_meet_log(
+    storage: &impl Storage,
+    meet_id: &str,
+) -> Result<InspectedLog, AppError> {
+    Ok(decode_log(storage.read_updates(meet_id).await?))
+}
+
+/// Read a plaintext `updates.log` file
+/// Damaged records are skipped and listed in `damaged` rather than failing
+/// the read, so a damaged log can still be inspected. Logs of encrypted meets
+/// must be read through storage instead.
+pub async fn read_log_file(path: &Path) -> Result<InspectedLog, AppError> {
+    let salvage = update_log::salvage(&tokio::fs::read(path).await?);
+    let mut log = decode_log(salvage.records);
+    log.damaged = salvage.dropped;
+    Ok(log)
+}
+
+/// Client exports are either a bare list of updates or an object holding one
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ClientExport {
+    List(Vec<Update>),
+    Wrapped { updates: Vec<Update> },
+}
+
+/// Read the updates a client exported, as a JSON list or `{"updates": [...]}`
+/// Entries may carry server fields too; only the client's fields are kept.
+pub fn parse_client_export(json: &str) -> Result<Vec<Update>, AppError> {
+    let export: ClientExport = serde_json::from_str(json)
+        .map_err(|err| AppError::InvalidInput(format!("Not a client update export: {err}")))?;
+    Ok(match export {
+        ClientExport::List(updates) | ClientExport::Wrapped { updates } => updates,
+    })
+}
+
+/// Which updates to show
+#[derive(Debug, Clone)]
+pub struct UpdateFilter {
+    /// Only updates whose key starts with this
+    pub key_prefix: Option<String>,
+    /// Only updates sent by this client
+    pub client_id: Option<String>,
+    /// Only updates with these server sequence numbers
+    pub seqs: RangeInclusive<u64>,
+}
+
+impl Default for UpdateFilter {
+    fn default() -> Self {
+        Self {
+            key_prefix: None,
+            client_id: None,
+            seqs: 0..=u64::MAX,
+        }
+    }
+}
+
+impl UpdateFilter {
+    /// Whether an update passes every condition of the filter
+    pub fn matches(&self, update: &UpdateWithServerSeq) -> bool {
+        self.seqs.contains(&update.server_seq_num)
+            && self
+                .key_prefix
+                .as_deref()
+                .is_none_or(|prefix| update.update.update_key.starts_with(prefix))
+            && self
+                .client_id
+                .as_deref()
+                .is_none_or(|client| update.source_client_id == client)
+    }
+}
+
+/// One line describing an update, for operators
+pub fn format_update(update: &UpdateWithServerSeq) -> String {
+    let client = if update.source_client_id.is_empty() {
+        "-"
+    } else {
+        &update.source_client_id
+    };
+    format!(
+        "#{:<6} {} (priority {}, local {}, after {})  {} = {}",
+        update.server_seq_num,
+        client,
+        update.source_client_priority,
+        update.update.local_seq_num,
+        update.update.after_server_seq_num,
+        update.update.update_key,
+        update.update.update_value
+    )
+}
+
+/// Last value written to each key, applying updates in order
+pub fn final_values<'a>(updates: impl IntoIterator<Item = &'a Update>) -> BTreeMap<String, Value> {
+    updates
+        .into_iter()
+        .map(|update| (update.update_key.clone(), update.update_value.clone()))
+        .collect()
+}
+
+/// A key whose final value differs between two accounts of a meet
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDiff {
+    pub key: String,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+/// Keys whose values differ between two states, in key order
+pub fn diff_values(
+    left: &BTreeMap<String, Value>,
+    right: &BTreeMap<String, Value>,
+) -> Vec<KeyDiff> {
+    let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    keys.into_iter()
+        .filter(|key| left.get(*key) != right.get(*key))
+        .map(|key| KeyDiff {
+            key: key.clone(),
+            left: left.get(key).cloned(),
+            right: right.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// Differences between two update logs of the same meet
+#[derive(Debug, Clone, Default)]
+pub struct LogDiff {
+    /// Updates whose sequence number only appears in the left log
+    pub only_left: Vec<UpdateWithServerSeq>,
+    /// Updates whose sequence number only appears in the right log
+    pub only_right: Vec<UpdateWithServerSeq>,
+    /// Updates with the same sequence number but different contents
+    pub changed: Vec<(UpdateWithServerSeq, UpdateWithServerSeq)>,
+    /// Keys that end with different values
+    pub values: Vec<KeyDiff>,
+}
+
+impl LogDiff {
+    /// Whether the logs agree completely
+    pub fn is_empty(&self) -> bool {
+        self.only_left.is_empty()
+            && self.only_right.is_empty()
+            && self.changed.is_empty()
+            && self.values.is_empty()
+    }
+}
+
+/// Whether two updates carry the same change from the same client
+fn same_update(a: &UpdateWithServerSeq, b: &UpdateWithServerSeq) -> bool {
+    a.update.update_key == b.update.update_key
+        && a.update.update_value == b.update.update_value
+        && a.update.local_seq_num == b.update.local_seq_num
+        && a.source_client_id == b.source_client_id
+}
+
+/// Compare two logs update by update, matched by server sequence number
+pub fn diff_logs(left: &[UpdateWithServerSeq], right: &[UpdateWithServerSeq]) -> LogDiff {
+    let by_seq: HashMap<u64, &UpdateWithServerSeq> =
+        right.iter().map(|u| (u.server_seq_num, u)).collect();
+    let left_seqs: BTreeSet<u64> = left.iter().map(|u| u.server_seq_num).collect();
+
+    let mut diff = LogDiff::default();
+    for update in left {
+        match by_seq.get(&update.server_seq_num) {
+            None => diff.only_left.push(update.clone()),
+            Some(other) if !same_update(update, other) => {
+                diff.changed.push((update.clone(), (*other).clone()));
+            },
+            Some(_) => {},
+        }
+    }
+    diff.only_right = right
+        .iter()
+        .filter(|u| !left_seqs.contains(&u.server_seq_num))
+        .cloned()
+        .collect();
+    diff.values = diff_values(
+        &final_values(left.iter().map(|u| &u.update)),
+        &final_values(right.iter().map(|u| &u.update)),
+    );
+    diff
+}
+
+/// Differences between a log and the updates one client exported
+#[derive(Debug, Clone, Default)]
+pub struct ExportDiff {
+    /// Client updates the server never recorded
+    pub missing: Vec<Update>,
+    /// Keys the client ends with a different value for; `left` is the server's
+    pub values: Vec<KeyDiff>,
+}
+
+/// Compare a log with a client's exported updates
+/// Client updates carry no server sequence number, so they are matched to
+/// the log by key, value and local sequence number. Final values are
+/// compared only for keys the client wrote.
+pub fn diff_client_export(log: &[UpdateWithServerSeq], export: &[Update]) -> ExportDiff {
+    let recorded: BTreeSet<(&str, u64, String)> = log
+        .iter()
+        .map(|u| {
+            (
+                u.update.update_key.as_str(),
+                u.update.local_seq_num,
+                u.update.update_value.to_string(),
+            )
+        })
+        .collect();
+    let missing = export
+        .iter()
+        .filter(|u| {
+            !recorded.contains(&(
+                u.update_key.as_str(),
+                u.local_seq_num,
+                u.update_value.to_string(),
+            ))
+        })
+        .cloned()
+        .collect();
+
+    let client = final_values(export);
+    let server: BTreeMap<String, Value> = final_values(log.iter().map(|u| &u.update))
+        .into_iter()
+        .filter(|(key, _)| client.contains_key(key))
+        .collect();
+    ExportDiff {
+        missing,
+        values: diff_values(&server, &client),
+    }
+}
+
+/// Result of replaying a log into a fresh meet actor
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Server sequence number the actor reached
+    pub server_seq: u64,
+    /// Meet state after the last update
+    pub state: Value,
+    /// Places where the log disagrees with itself or with the replayed state
+    pub divergence: Vec<String>,
+}
+
+/// Replay a log into a fresh `MeetActor` backed by a scratch directory
+/// The actor takes each update's number from the record, so updates numbered
+/// out of order, records that don't decode and damaged stretches of the file
+/// are reported, as is any key whose replayed value differs from the last
+/// update to it.
+pub async fn replay(log: &InspectedLog) -> Result<Replay, AppError> {
+    let scratch = tempfile::tempdir()?;
+    let storage = FlatFileStorage::new(scratch.path())
+        .map_err(|err| AppError::Internal(format!("Failed to open replay storage: {err}")))?;
+    storage
+        .replace_updates(REPLAY_MEET_ID, &log.records)
+        .await?;
+    let mut actor =
+        MeetActor::new(REPLAY_MEET_ID.to_string(), storage, broadcast::channel(1).0).with_window(0);
+    let server_seq = actor.load().await?;
+    let state = actor.get_state();
+
+    let mut divergence = Vec::new();
+    for range in &log.damaged {
+        divergence.push(format!("bytes {}..{} are damaged", range.start, range.end));
+    }
+    for (position, err) in &log.undecodable {
+        divergence.push(format!("record {position} does not decode: {err}"));
+    }
+    for (position, seq) in &log.misnumbered {
+        divergence.push(format!("record {position} is numbered {seq}, out of order"));
+    }
+
+    let replayed: BTreeMap<String, Value> = state
+        .as_object()
+        .map(|obj| obj.clone().into_iter().collect())
+        .unwrap_or_default();
+    let expected = final_values(log.updates.iter().map(|u| &u.update));
+    for diff in diff_values(&expected, &replayed) {
+        divergence.push(format!(
+            "{} replays as {} but was last set to {}",
+            diff.key,
+            show(diff.right.as_ref()),
+            show(diff.left.as_ref())
+        ));
+    }
+
+    Ok(Replay {
+        server_seq,
+        state,
+        divergence,
+    })
+}
+
+/// A value that may be missing, for operators
+pub fn show(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(unset)".to_string(), Value::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::update_log::encode_record;
+    use tempfile::TempDir;
+
+    fn update(seq: u64, client: &str, key: &str, value: i64) -> String {
+        serde_json::json!({
+            "update_key": key,
+            "update_value": value,
+            "local_seq_num": seq,
+            "after_server_seq_num": seq - 1,
+            "serverSeqNum": seq,
+            "sourceClientId": client,
+            "sourceClientPriority": 5,
+        })
+        .to_string()
+    }
+
+    fn sample() -> InspectedLog {
+        decode_log(vec![
+            update(1, "table", "lifter.1.name", 1),
+            update(2, "platform", "lifter.1.squat", 100),
+            update(3, "platform", "lifter.2.squat", 120),
+            update(4, "table", "lifter.1.squat", 105),
+        ])
+    }
+
+    #[test]
+    fn test_filter() {
+        let log = sample();
+        let filter = UpdateFilter {
+            key_prefix: Some("lifter.1.".to_string()),
+            client_id: Some("platform".to_string()),
+            ..UpdateFilter::default()
+        };
+        let seqs: Vec<u64> = log
+            .updates
+            .iter()
+            .filter(|u| filter.matches(u))
+            .map(|u| u.server_seq_num)
+            .collect();
+        assert_eq!(seqs, vec![2]);
+
+        let filter = UpdateFilter {
+            seqs: 2..=3,
+            ..UpdateFilter::default()
+        };
+        assert_eq!(log.updates.iter().filter(|u| filter.matches(u)).count(), 2);
+        assert!(format_update(&log.updates[1]).contains("lifter.1.squat = 100"));
+    }
+
+    #[test]
+    fn test_diff_logs() {
+        let left = sample();
+        let right = decode_log(vec![
+            update(1, "table", "lifter.1.name", 1),
+            update(2, "platform", "lifter.1.squat", 102),
+            update(3, "platform", "lifter.2.squat", 120),
+        ]);
+        let diff = diff_logs(&left.updates, &right.updates);
+        assert_eq!(diff.only_left.len(), 1);
+        assert_eq!(diff.only_left[0].server_seq_num, 4);
+        assert!(diff.only_right.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.server_seq_num, 2);
+        assert_eq!(
+            diff.values,
+            vec![KeyDiff {
+                key: "lifter.1.squat".to_string(),
+                left: Some(105.into()),
+                right: Some(102.into()),
+            }]
+        );
+        assert!(diff_logs(&left.updates, &left.updates).is_empty());
+    }
+
+    #[test]
+    fn test_diff_client_export() {
+        let log = sample();
+        let export = parse_client_export(
+            r#"{"updates": [
+                {"update_key": "lifter.1.squat", "update_value": 100, "local_seq_num": 2, "after_server_seq_num": 1},
+                {"update_key": "lifter.1.squat", "update_value": 110, "local_seq_num": 5, "after_server_seq_num": 4}
+            ]}"#,
+        )
+        .unwrap();
+        let diff = diff_client_export(&log.updates, &export);
+        assert_eq!(diff.missing.len(), 1);
+        assert_eq!(diff.missing[0].local_seq_num, 5);
+        // Keys the client never wrote are not compared
+        assert_eq!(diff.values.len(), 1);
+        assert_eq!(diff.values[0].left, Some(105.into()));
+        assert_eq!(diff.values[0].right, Some(110.into()));
+
+        assert!(parse_client_export("[]").unwrap().is_empty());
+        assert!(parse_client_export(r#"{"other": 1}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let replay = replay(&sample()).await.unwrap();
+        assert_eq!(replay.server_seq, 4);
+        assert_eq!(replay.state["lifter.1.squat"], 105);
+        assert!(replay.divergence.is_empty());
+    }
+
+    #[test]
+    fn test_decode_log_reports_numbers_out_of_order() {
+        let log = decode_log(vec![
+            update(1, "table", "a", 1),
+            update(3, "table", "a", 2),
+            update(3, "table", "b", 3),
+            update(2, "table", "c", 4),
+        ]);
+        assert_eq!(log.misnumbered, vec![(3, 3), (4, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_damaged_log_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("updates.log");
+        let mut bytes = String::new();
+        bytes.push_str(&encode_record(&update(1, "table", "a", 1)));
+        bytes.push_str(&encode_record("not json"));
+        bytes.push_str(&encode_record(&update(5, "table", "b", 2)));
+        bytes.push_str("garbage\n");
+        tokio::fs::write(&path, bytes).await.unwrap();
+
+        let log = read_log_file(&path).await.unwrap();
+        assert_eq!(log.updates.len(), 2);
+        assert_eq!(log.undecodable.len(), 1);
+        // Numbers may skip records that were lost
+        assert!(log.misnumbered.is_empty());
+        assert_eq!(log.damaged.len(), 1);
+
+        // The update numbered 5 replays as 5
+        let replay = replay(&log).await.unwrap();
+        assert_eq!(replay.server_seq, 5);
+        assert_eq!(replay.state["b"], 2);
+        assert_eq!(replay.divergence.len(), 2);
+    }
+}
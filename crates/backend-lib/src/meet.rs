@@ -6,7 +6,6 @@ use crate::{
     storage::Storage,
 };
 use dashmap::DashMap;
-use metrics::{counter, gauge};
 use std::sync::Arc;
 
 /// Manager for live meets
@@ -36,12 +35,7 @@ impl MeetManager {
         storage: impl Storage + 'static,
     ) -> MeetHandle {
         let handle = spawn_meet_actor(&meet_id, storage).await;
-        self.meets.insert(meet_id.clone(), handle.clone());
-
-        // Update metrics
-        let _ = counter!("meet.created", &[("value", "1")]);
-        let _ = gauge!("meet.active", &[("value", "1")]);
-
+        self.meets.insert(meet_id, handle.clone());
         handle
     }
 
@@ -52,14 +46,7 @@ impl MeetManager {
 
     /// Delete a meet
     pub fn delete_meet(&self, meet_id: &str) -> bool {
-        if self.meets.remove(meet_id).is_some() {
-            // Update metrics
-            let _ = counter!("meet.deleted", &[("value", "1")]);
-            let _ = gauge!("meet.active", &[("value", "-1")]);
-            true
-        } else {
-            false
-        }
+        self.meets.remove(meet_id).is_some()
     }
 
     /// Get all active meet IDs
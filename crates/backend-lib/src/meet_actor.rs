@@ -1,16 +1,76 @@
 // crates/backend-lib/src/meet_actor.rs
 
 //! Meet actor module
-use crate::{error::AppError, storage::Storage};
-use metrics::{counter, histogram};
-use openlifter_common::{Update, UpdateWithServerSeq};
+//!
+//! Each meet is served by one actor that owns its state. The actor keeps only
+//! the most recent updates in memory; older ones are paged from storage when a
+//! client asks for them. On start it rebuilds its state from the stored log.
+use crate::{
+    error::AppError,
+    metrics::{self as metric_names, LABEL_MEET_ID},
+    storage::{update_seq, Storage},
+};
+use metrics::{counter, gauge, histogram};
+use openlifter_common::{MeetInfo, Update, UpdateWithServerSeq};
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 use uuid::Uuid;
 
 pub type ClientId = Uuid;
 
+/// Number of recent updates an actor keeps in memory
+pub const DEFAULT_UPDATE_WINDOW: usize = 1024;
+
+/// Number of updates read per page when an actor rebuilds its state
+const LOAD_PAGE_SIZE: usize = 1024;
+
+/// Updates following a server sequence number, up to a page size
+#[derive(Debug, Clone)]
+pub struct UpdatePage {
+    pub updates: Vec<UpdateWithServerSeq>,
+    /// Latest server sequence number of the meet
+    pub server_seq: u64,
+}
+
+impl UpdatePage {
+    /// Whether updates after this page remain to be pulled
+    pub fn has_more(&self) -> bool {
+        self.updates
+            .last()
+            .is_some_and(|u| u.server_seq_num < self.server_seq)
+    }
+}
+
+/// Runtime figures of an actor, as shown to operators
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActorStats {
+    /// Latest server sequence number of the meet
+    pub server_seq: u64,
+    /// Messages queued behind the stats request
+    pub mailbox_depth: usize,
+    /// Recent updates held in memory
+    pub window_len: usize,
+    /// Seconds since the actor last applied an update
+    pub idle_secs: u64,
+}
+
+/// Everything stored for a meet and the state it replays to, all taken
+/// between two messages so no update is appended in the meantime
+#[derive(Debug, Clone)]
+pub struct MeetExport {
+    /// Server sequence number of the last update
+    pub server_seq: u64,
+    pub state: Value,
+    pub info: MeetInfo,
+    /// Update log, one JSON update per entry
+    pub updates: Vec<String>,
+    /// Published CSV and return email
+    pub csv: Option<(String, String)>,
+}
+
 /// Message sent *into* the actor
 #[derive(Debug)]
 pub enum ActorMsg {
@@ -22,7 +82,8 @@ pub enum ActorMsg {
     },
     Pull {
         since: u64,
-        resp_tx: mpsc::UnboundedSender<Result<Vec<UpdateWithServerSeq>, AppError>>,
+        limit: usize,
+        resp_tx: mpsc::UnboundedSender<Result<UpdatePage, AppError>>,
     },
     StoreCsv {
         opl_csv: String,
@@ -36,6 +97,66 @@ pub enum ActorMsg {
         priority: u8,
         resp_tx: mpsc::UnboundedSender<Result<(u64, usize), AppError>>,
     },
+    /// Current server sequence number together with the full meet state
+    Snapshot {
+        resp_tx: mpsc::UnboundedSender<(u64, Value)>,
+    },
+    Stats {
+        resp_tx: mpsc::UnboundedSender<ActorStats>,
+    },
+    Export {
+        resp_tx: mpsc::UnboundedSender<Result<MeetExport, AppError>>,
+    },
+    /// Stop once the messages queued before this one are handled. With
+    /// `if_unused` the actor keeps running while clients are subscribed to
+    /// its relay. Answers whether the actor stopped.
+    Shutdown {
+        if_unused: bool,
+        resp_tx: mpsc::UnboundedSender<bool>,
+    },
+}
+
+impl ActorMsg {
+    /// Name of the message as recorded in its tracing span
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ActorMsg::Update { .. } => "update",
+            ActorMsg::Pull { .. } => "pull",
+            ActorMsg::StoreCsv { .. } => "store_csv",
+            ActorMsg::RecoverState { .. } => "recover_state",
+            ActorMsg::Snapshot { .. } => "snapshot",
+            ActorMsg::Stats { .. } => "stats",
+            ActorMsg::Export { .. } => "export",
+            ActorMsg::Shutdown { .. } => "shutdown",
+        }
+    }
+
+    /// Answer the message with an error. Messages whose answer cannot carry
+    /// one are dropped, which their sender sees as a failed request.
+    fn fail(self, err: &str) {
+        let err = || AppError::Internal(err.to_string());
+        match self {
+            ActorMsg::Update { resp_tx, .. } => {
+                let _ = resp_tx.send(Err(err()));
+            },
+            ActorMsg::Pull { resp_tx, .. } => {
+                let _ = resp_tx.send(Err(err()));
+            },
+            ActorMsg::StoreCsv { resp_tx, .. } => {
+                let _ = resp_tx.send(Err(err()));
+            },
+            ActorMsg::RecoverState { resp_tx, .. } => {
+                let _ = resp_tx.send(Err(err()));
+            },
+            ActorMsg::Export { resp_tx } => {
+                let _ = resp_tx.send(Err(err()));
+            },
+            ActorMsg::Shutdown { resp_tx, .. } => {
+                let _ = resp_tx.send(true);
+            },
+            ActorMsg::Snapshot { .. } | ActorMsg::Stats { .. } => {},
+        }
+    }
 }
 
 /// Handle that other components keep: command channel + broadcast sender
@@ -80,13 +201,23 @@ impl MeetHandle {
             .ok_or_else(|| AppError::Internal("Failed to receive response".to_string()))?
     }
 
+    /// All updates after `since`. Prefer `get_update_page` when the gap may be large.
     pub async fn get_updates_since(
         &self,
         since: u64,
     ) -> Result<Vec<UpdateWithServerSeq>, AppError> {
+        Ok(self.get_update_page(since, usize::MAX).await?.updates)
+    }
+
+    /// At most `limit` updates after `since`
+    pub async fn get_update_page(&self, since: u64, limit: usize) -> Result<UpdatePage, AppError> {
         let (resp_tx, mut resp_rx) = mpsc::unbounded_channel();
 
-        self.cmd_tx.send(ActorMsg::Pull { since, resp_tx })?;
+        self.cmd_tx.send(ActorMsg::Pull {
+            since,
+            limit,
+            resp_tx,
+        })?;
 
         resp_rx
             .recv()
@@ -133,13 +264,66 @@ impl MeetHandle {
             .await
             .ok_or_else(|| AppError::Internal("Failed to receive response".to_string()))?
     }
+
+    pub async fn snapshot(&self) -> Result<(u64, Value), AppError> {
+        let (resp_tx, mut resp_rx) = mpsc::unbounded_channel();
+
+        self.cmd_tx.send(ActorMsg::Snapshot { resp_tx })?;
+
+        resp_rx
+            .recv()
+            .await
+            .ok_or_else(|| AppError::Internal("Failed to receive response".to_string()))
+    }
+
+    pub async fn stats(&self) -> Result<ActorStats, AppError> {
+        let (resp_tx, mut resp_rx) = mpsc::unbounded_channel();
+
+        self.cmd_tx.send(ActorMsg::Stats { resp_tx })?;
+
+        resp_rx
+            .recv()
+            .await
+            .ok_or_else(|| AppError::Internal("Failed to receive response".to_string()))
+    }
+
+    pub async fn export(&self) -> Result<MeetExport, AppError> {
+        let (resp_tx, mut resp_rx) = mpsc::unbounded_channel();
+
+        self.cmd_tx.send(ActorMsg::Export { resp_tx })?;
+
+        resp_rx
+            .recv()
+            .await
+            .ok_or_else(|| AppError::Internal("Failed to receive response".to_string()))?
+    }
+
+    /// Stop the actor and wait until it no longer touches storage. With
+    /// `if_unused` the actor decides whether clients are still subscribed, so
+    /// none can join between the check and the stop. Returns whether the
+    /// actor stopped; one that had already stopped counts as stopped.
+    pub async fn shutdown(&self, if_unused: bool) -> bool {
+        let (resp_tx, mut resp_rx) = mpsc::unbounded_channel();
+
+        if self
+            .cmd_tx
+            .send(ActorMsg::Shutdown { if_unused, resp_tx })
+            .is_err()
+        {
+            return true;
+        }
+
+        resp_rx.recv().await.unwrap_or(true)
+    }
 }
 
 pub struct MeetActor<S: Storage> {
     meet_id: String,
     storage: S,
     state: Value,
-    updates: Vec<UpdateWithServerSeq>,
+    /// The most recent updates, oldest first
+    updates: VecDeque<UpdateWithServerSeq>,
+    window: usize,
     server_seq: u64,
     updates_by_key: HashMap<String, UpdateWithServerSeq>,
     tx_relay: broadcast::Sender<UpdateWithServerSeq>,
@@ -158,7 +342,8 @@ impl<S: Storage> MeetActor<S> {
             meet_id,
             storage,
             state: serde_json::json!({}),
-            updates: Vec::new(),
+            updates: VecDeque::new(),
+            window: DEFAULT_UPDATE_WINDOW,
             server_seq: 0,
             updates_by_key: HashMap::new(),
             tx_relay,
@@ -168,6 +353,55 @@ impl<S: Storage> MeetActor<S> {
         }
     }
 
+    /// Keep at most `window` recent updates in memory
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Rebuild state from the stored update log, a page at a time
+    /// Returns the server sequence number of the latest stored update.
+    pub async fn load(&mut self) -> Result<u64, AppError> {
+        loop {
+            let lines = self
+                .storage
+                .read_updates_from(&self.meet_id, self.server_seq + 1, LOAD_PAGE_SIZE)
+                .await?;
+            for line in &lines {
+                // Every update carries its number, so one that no longer
+                // decodes, or is missing from the log, still keeps its number
+                // from being given out again
+                let Some(seq) = update_seq(line) else {
+                    continue;
+                };
+                self.server_seq = self.server_seq.max(seq);
+                match serde_json::from_str::<UpdateWithServerSeq>(line) {
+                    Ok(update) => {
+                        self.apply_update(&update);
+                        self.updates_by_key
+                            .insert(update.update.update_key.clone(), update.clone());
+                        self.remember(update);
+                    },
+                    Err(err) => error!(seq, "Error replaying update: {}", err),
+                }
+            }
+            if lines.len() < LOAD_PAGE_SIZE {
+                return Ok(self.server_seq);
+            }
+        }
+    }
+
+    /// Add an update to the in-memory window, dropping the oldest if it is full
+    fn remember(&mut self, update: UpdateWithServerSeq) {
+        if self.window == 0 {
+            return;
+        }
+        if self.updates.len() == self.window {
+            self.updates.pop_front();
+        }
+        self.updates.push_back(update);
+    }
+
     /// Detect sequence gaps in client updates
     ///
     /// This method checks if there are any gaps in the sequence numbers
@@ -192,15 +426,16 @@ impl<S: Storage> MeetActor<S> {
         // Check for gaps in the update sequence
         if expected_seq > 0 && first_update_seq > expected_seq {
             // Gap detected!
-            println!(
-                "Sequence gap detected for client {client_id}: expected {expected_seq}, got {first_update_seq}"
+            warn!(
+                client_id,
+                "Sequence gap detected: expected {expected_seq}, got {first_update_seq}"
             );
 
             // Mark that we need a consistency check
             self.need_consistency_check = true;
 
-            // Update metrics
-            let _ = counter!("meet.sequence_gaps", &[("value", "1")]);
+            counter!(metric_names::MEET_SEQUENCE_GAPS, LABEL_MEET_ID => self.meet_id.clone())
+                .increment(1);
 
             return true;
         }
@@ -210,16 +445,18 @@ impl<S: Storage> MeetActor<S> {
         for update in &updates[1..] {
             if update.local_seq_num > prev_seq + 1 {
                 // Gap detected within batch
-                println!(
-                    "Sequence gap detected within batch for client {}: gap between {} and {}",
-                    client_id, prev_seq, update.local_seq_num
+                warn!(
+                    client_id,
+                    "Sequence gap detected within batch: gap between {} and {}",
+                    prev_seq,
+                    update.local_seq_num
                 );
 
                 // Mark that we need a consistency check
                 self.need_consistency_check = true;
 
-                // Update metrics
-                let _ = counter!("meet.sequence_gaps", &[("value", "1")]);
+                counter!(metric_names::MEET_SEQUENCE_GAPS, LABEL_MEET_ID => self.meet_id.clone())
+                    .increment(1);
 
                 return true;
             }
@@ -253,10 +490,7 @@ impl<S: Storage> MeetActor<S> {
         let now = std::time::Instant::now();
         let inactivity_duration = now.duration_since(self.last_update_time);
         if inactivity_duration > std::time::Duration::from_secs(300) {
-            println!(
-                "Long inactivity period detected for meet {}: {:?}",
-                self.meet_id, inactivity_duration
-            );
+            info!("Long inactivity period detected: {:?}", inactivity_duration);
 
             // Update the last update time
             self.last_update_time = now;
@@ -268,6 +502,10 @@ impl<S: Storage> MeetActor<S> {
         false
     }
 
+    /// Store, apply and relay `updates` in order.
+    /// If storage fails partway, the updates already stored stay applied and
+    /// only they are returned, so the client resends just the rest; the error
+    /// is returned only when nothing was stored.
     pub async fn handle_update(
         &mut self,
         client_id: String,
@@ -295,8 +533,7 @@ impl<S: Storage> MeetActor<S> {
 
         let updates_len = updates.len();
         for update in updates {
-            self.server_seq += 1;
-            let seq = self.server_seq;
+            let seq = self.server_seq + 1;
 
             let update_with_seq = UpdateWithServerSeq {
                 update: update.clone(),
@@ -305,6 +542,24 @@ impl<S: Storage> MeetActor<S> {
                 source_client_priority: priority,
             };
 
+            // Store in persistent storage first, so a failed write leaves the
+            // in-memory state matching what is on disk
+            let json = serde_json::to_string(&update_with_seq)?;
+            if let Err(err) = self.storage.append_update(&self.meet_id, &json).await {
+                if results.is_empty() {
+                    return Err(err);
+                }
+                warn!(
+                    "Meet {}: stored {} of {} updates before failing: {}",
+                    self.meet_id,
+                    results.len(),
+                    updates_len,
+                    err
+                );
+                break;
+            }
+            self.server_seq = seq;
+
             // Apply the update to our state
             self.apply_update(&update_with_seq);
 
@@ -312,12 +567,8 @@ impl<S: Storage> MeetActor<S> {
             self.updates_by_key
                 .insert(update.update_key.clone(), update_with_seq.clone());
 
-            // Add to our list of updates
-            self.updates.push(update_with_seq.clone());
-
-            // Store in persistent storage
-            let json = serde_json::to_string(&update_with_seq)?;
-            self.storage.append_update(&self.meet_id, &json).await?;
+            // Add to the recent window
+            self.remember(update_with_seq.clone());
 
             // Broadcast to all connected clients
             let _ = self.tx_relay.send(update_with_seq);
@@ -325,22 +576,48 @@ impl<S: Storage> MeetActor<S> {
             results.push((seq, seq));
         }
 
-        // Update metrics
-        let _ = counter!("meet.updates", &[("value", "1")]);
-        let _ = histogram!(
-            "meet.update.batch_size",
-            &[("value", updates_len.to_string())]
-        );
+        counter!(metric_names::UPDATE_ACCEPTED, LABEL_MEET_ID => self.meet_id.clone())
+            .increment(results.len() as u64);
+        histogram!(metric_names::UPDATE_BATCH_SIZE, LABEL_MEET_ID => self.meet_id.clone())
+            .record(updates_len as f64);
 
         Ok(results)
     }
 
-    pub fn get_updates_since(&self, since: u64) -> Vec<UpdateWithServerSeq> {
-        self.updates
-            .iter()
-            .filter(|u| u.server_seq_num > since)
-            .cloned()
-            .collect()
+    /// At most `limit` updates after `since`, read from disk when they are
+    /// older than the in-memory window
+    pub async fn get_updates_since(
+        &self,
+        since: u64,
+        limit: usize,
+    ) -> Result<UpdatePage, AppError> {
+        let oldest = self
+            .updates
+            .front()
+            .map_or(self.server_seq + 1, |u| u.server_seq_num);
+        let updates: Vec<UpdateWithServerSeq> = if since + 1 >= oldest {
+            self.updates
+                .iter()
+                .skip_while(|u| u.server_seq_num <= since)
+                .take(limit)
+                .cloned()
+                .collect()
+        } else {
+            counter!(metric_names::PULL_DISK_READS, LABEL_MEET_ID => self.meet_id.clone())
+                .increment(1);
+            self.storage
+                .read_updates_from(&self.meet_id, since + 1, limit)
+                .await?
+                .iter()
+                .map(|line| serde_json::from_str(line))
+                .collect::<Result<_, _>>()?
+        };
+        histogram!(metric_names::PULL_UPDATES, LABEL_MEET_ID => self.meet_id.clone())
+            .record(updates.len() as f64);
+        Ok(UpdatePage {
+            updates,
+            server_seq: self.server_seq,
+        })
     }
 
     fn apply_update(&mut self, update: &UpdateWithServerSeq) {
@@ -413,8 +690,7 @@ impl<S: Storage> MeetActor<S> {
             }
 
             // Apply the update
-            self.server_seq += 1;
-            let seq = self.server_seq;
+            let seq = self.server_seq + 1;
 
             let update_with_seq = UpdateWithServerSeq {
                 update: common_update,
@@ -423,6 +699,12 @@ impl<S: Storage> MeetActor<S> {
                 source_client_priority: priority,
             };
 
+            // Store in persistent storage first, so the sequence number only
+            // advances for updates that are on disk
+            let json = serde_json::to_string(&update_with_seq)?;
+            self.storage.append_update(&self.meet_id, &json).await?;
+            self.server_seq = seq;
+
             // Apply to state
             self.apply_update(&update_with_seq);
 
@@ -431,11 +713,7 @@ impl<S: Storage> MeetActor<S> {
                 update_with_seq.update.update_key.clone(),
                 update_with_seq.clone(),
             );
-            self.updates.push(update_with_seq.clone());
-
-            // Store in persistent storage
-            let json = serde_json::to_string(&update_with_seq)?;
-            self.storage.append_update(&self.meet_id, &json).await?;
+            self.remember(update_with_seq);
 
             // Update counter
             applied_updates += 1;
@@ -445,9 +723,12 @@ impl<S: Storage> MeetActor<S> {
 
         if applied_updates > 0 {
             // Log recovery stats
-            println!(
-                "Recovered {} updates for meet {} from client {}, seq {} -> {}",
-                applied_updates, self.meet_id, client_id, original_seq, self.server_seq
+            info!(
+                client_id,
+                "Recovered {} updates, seq {} -> {}",
+                applied_updates,
+                original_seq,
+                self.server_seq
             );
         }
 
@@ -455,44 +736,125 @@ impl<S: Storage> MeetActor<S> {
     }
 
     pub async fn run(mut self, mut rx: mpsc::UnboundedReceiver<ActorMsg>) {
+        gauge!(metric_names::MEET_ACTIVE).increment(1.0);
+        let load_span = info_span!("actor_load", meet_id = %self.meet_id);
+        if let Err(err) = self.load().instrument(load_span.clone()).await {
+            load_span.in_scope(|| error!("Error loading updates, refusing every message: {}", err));
+            // Appending on top of a log that could not be read would reuse
+            // sequence numbers, so answer what is queued with errors and stop
+            rx.close();
+            let reason = format!("Meet {} could not be loaded: {}", self.meet_id, err);
+            while let Some(msg) = rx.recv().await {
+                msg.fail(&reason);
+            }
+            gauge!(metric_names::MEET_ACTIVE).decrement(1.0);
+            return;
+        }
+
+        let mut stopped = None;
         while let Some(msg) = rx.recv().await {
-            match msg {
-                ActorMsg::Update {
-                    client_id,
-                    priority,
-                    updates,
-                    resp_tx,
-                } => {
-                    let result = self.handle_update(client_id, priority, updates).await;
-                    let _ = resp_tx.send(result);
-                },
-                ActorMsg::Pull { since, resp_tx } => {
-                    let updates = self.get_updates_since(since);
-                    let _ = resp_tx.send(Ok(updates));
-                },
-                ActorMsg::StoreCsv {
-                    opl_csv,
-                    return_email,
-                    resp_tx,
-                } => {
-                    let result = self.store_csv_data(opl_csv, return_email).await;
-                    let _ = resp_tx.send(result);
-                },
-                ActorMsg::RecoverState {
-                    client_id,
-                    priority,
-                    updates,
-                    resp_tx,
-                } => {
-                    let result = self
-                        .handle_state_recovery(client_id, priority, updates)
-                        .await;
-                    let _ = resp_tx.send(result);
-                },
+            if let ActorMsg::Shutdown { if_unused, resp_tx } = msg {
+                if if_unused && self.tx_relay.receiver_count() > 0 {
+                    let _ = resp_tx.send(false);
+                    continue;
+                }
+                info!(meet_id = %self.meet_id, "Shutting down meet actor");
+                rx.close();
+                let reason = format!("Meet {} was shut down", self.meet_id);
+                while let Some(msg) = rx.recv().await {
+                    msg.fail(&reason);
+                }
+                stopped = Some(resp_tx);
+                break;
             }
+            // `seq` is the meet's server sequence number when the message is taken
+            let span = info_span!(
+                "actor_msg",
+                meet_id = %self.meet_id,
+                msg = msg.kind(),
+                seq = self.server_seq
+            );
+            let mailbox_depth = rx.len();
+            self.handle_msg(msg, mailbox_depth).instrument(span).await;
+        }
+        gauge!(metric_names::MEET_ACTIVE).decrement(1.0);
+        if let Some(resp_tx) = stopped {
+            let _ = resp_tx.send(true);
         }
     }
 
+    /// Handle one message, answering on its response channel
+    async fn handle_msg(&mut self, msg: ActorMsg, mailbox_depth: usize) {
+        match msg {
+            ActorMsg::Update {
+                client_id,
+                priority,
+                updates,
+                resp_tx,
+            } => {
+                let result = self.handle_update(client_id, priority, updates).await;
+                let _ = resp_tx.send(result);
+            },
+            ActorMsg::Pull {
+                since,
+                limit,
+                resp_tx,
+            } => {
+                let result = self.get_updates_since(since, limit).await;
+                let _ = resp_tx.send(result);
+            },
+            ActorMsg::StoreCsv {
+                opl_csv,
+                return_email,
+                resp_tx,
+            } => {
+                let result = self.store_csv_data(opl_csv, return_email).await;
+                let _ = resp_tx.send(result);
+            },
+            ActorMsg::RecoverState {
+                client_id,
+                priority,
+                updates,
+                resp_tx,
+            } => {
+                let result = self
+                    .handle_state_recovery(client_id, priority, updates)
+                    .await;
+                let _ = resp_tx.send(result);
+            },
+            ActorMsg::Snapshot { resp_tx } => {
+                let _ = resp_tx.send((self.server_seq, self.get_state()));
+            },
+            ActorMsg::Stats { resp_tx } => {
+                let _ = resp_tx.send(ActorStats {
+                    server_seq: self.server_seq,
+                    mailbox_depth,
+                    window_len: self.updates.len(),
+                    idle_secs: self.last_update_time.elapsed().as_secs(),
+                });
+            },
+            ActorMsg::Export { resp_tx } => {
+                let _ = resp_tx.send(self.export().await);
+            },
+            // Taken by `run`, which stops the actor
+            ActorMsg::Shutdown { resp_tx, .. } => {
+                let _ = resp_tx.send(false);
+            },
+        }
+        debug!(seq = self.server_seq, "Handled message");
+    }
+
+    /// Read everything stored for the meet together with its current state
+    pub async fn export(&self) -> Result<MeetExport, AppError> {
+        Ok(MeetExport {
+            server_seq: self.server_seq,
+            state: self.get_state(),
+            info: self.storage.get_meet_info(&self.meet_id).await?,
+            updates: self.storage.read_updates(&self.meet_id).await?,
+            csv: self.storage.get_meet_csv(&self.meet_id).await?,
+        })
+    }
+
     pub async fn store_csv_data(
         &self,
         opl_csv: String,
@@ -503,9 +865,9 @@ impl<S: Storage> MeetActor<S> {
             .store_meet_csv(&self.meet_id, &opl_csv, &return_email)
             .await?;
 
-        // Update metrics
-        let _ = counter!("meet.published", &[("value", "1")]);
-        let _ = histogram!("meet.csv_size", &[("value", opl_csv.len().to_string())]);
+        counter!(metric_names::MEET_PUBLISHED, LABEL_MEET_ID => self.meet_id.clone()).increment(1);
+        histogram!(metric_names::MEET_CSV_BYTES, LABEL_MEET_ID => self.meet_id.clone())
+            .record(opl_csv.len() as f64);
 
         Ok(())
     }
@@ -628,6 +990,213 @@ mod tests {
         assert_eq!(updates[0].update.update_value, serde_json::json!(25));
     }
 
+    fn numbered(range: std::ops::RangeInclusive<u64>) -> Vec<Update> {
+        range
+            .map(|n| openlifter_common::Update {
+                update_key: format!("test.key{n}"),
+                update_value: serde_json::json!(n),
+                local_seq_num: n,
+                after_server_seq_num: 0,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_old_updates_are_paged_from_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (relay_tx, _) = broadcast::channel(32);
+        let actor =
+            MeetActor::new("test-meet".to_string(), storage, relay_tx.clone()).with_window(2);
+        tokio::spawn(actor.run(cmd_rx));
+        let handle = MeetHandle { cmd_tx, relay_tx };
+
+        handle
+            .apply_updates("client1".to_string(), 1, numbered(1..=5))
+            .await
+            .unwrap();
+
+        // Only 4 and 5 are in memory; the rest comes from the log
+        let page = handle.get_update_page(0, 3).await.unwrap();
+        let seqs: Vec<u64> = page.updates.iter().map(|u| u.server_seq_num).collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+        assert_eq!(page.updates[0].update.update_key, "test.key1");
+        assert!(page.has_more());
+
+        let page = handle.get_update_page(3, 3).await.unwrap();
+        let seqs: Vec<u64> = page.updates.iter().map(|u| u.server_seq_num).collect();
+        assert_eq!(seqs, vec![4, 5]);
+        assert!(!page.has_more());
+        assert_eq!(handle.get_updates_since(0).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_actor_reloads_stored_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+        let first = spawn_meet_actor("test-meet", storage.clone()).await;
+        first
+            .apply_updates("client1".to_string(), 1, numbered(1..=3))
+            .await
+            .unwrap();
+
+        // A new actor for the same meet picks up where the log ends
+        let second = spawn_meet_actor("test-meet", storage).await;
+        let (server_seq, state) = second.snapshot().await.unwrap();
+        assert_eq!(server_seq, 3);
+        assert_eq!(state["test.key2"], serde_json::json!(2));
+
+        let result = second
+            .apply_updates("client2".to_string(), 1, numbered(1..=1))
+            .await
+            .unwrap();
+        assert_eq!(result, vec![(4, 4)]);
+        assert_eq!(second.get_updates_since(2).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_keeps_numbers_of_lost_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+        // Update 3 was lost from the log, say by a repair
+        let lines: Vec<String> = numbered(1..=5)
+            .into_iter()
+            .filter(|update| update.local_seq_num != 3)
+            .map(|update| {
+                serde_json::to_string(&UpdateWithServerSeq {
+                    server_seq_num: update.local_seq_num,
+                    update,
+                    source_client_id: "client1".to_string(),
+                    source_client_priority: 1,
+                })
+                .unwrap()
+            })
+            .collect();
+        storage.replace_updates("test-meet", &lines).await.unwrap();
+
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (relay_tx, _) = broadcast::channel(32);
+        let actor =
+            MeetActor::new("test-meet".to_string(), storage, relay_tx.clone()).with_window(1);
+        tokio::spawn(actor.run(cmd_rx));
+        let handle = MeetHandle { cmd_tx, relay_tx };
+
+        let (server_seq, state) = handle.snapshot().await.unwrap();
+        assert_eq!(server_seq, 5);
+        assert_eq!(state["test.key5"], serde_json::json!(5));
+
+        // Pages read from disk keep the stored numbers
+        let page = handle.get_update_page(2, 2).await.unwrap();
+        let seqs: Vec<u64> = page.updates.iter().map(|u| u.server_seq_num).collect();
+        assert_eq!(seqs, vec![4, 5]);
+
+        // Numbers are not given out twice
+        let result = handle
+            .apply_updates("client2".to_string(), 1, numbered(1..=1))
+            .await
+            .unwrap();
+        assert_eq!(result, vec![(6, 6)]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_if_unused() {
+        let (handle, _temp_dir) = setup().await;
+
+        let client = handle.relay_tx.subscribe();
+        assert!(!handle.shutdown(true).await);
+        assert!(handle.snapshot().await.is_ok());
+
+        drop(client);
+        assert!(handle.shutdown(true).await);
+        assert!(handle.cmd_tx.is_closed());
+        let result = handle
+            .apply_updates("client1".to_string(), 1, numbered(1..=1))
+            .await;
+        assert!(result.is_err());
+        assert!(handle.shutdown(false).await);
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_refuses_messages() {
+        use crate::storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, StorageOp};
+
+        let plan = FaultPlan::new().always(
+            StorageOp::ReadUpdatesFrom,
+            Fault::Error(std::io::ErrorKind::PermissionDenied),
+        );
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+        let actor = spawn_meet_actor("test-meet", storage.clone()).await;
+
+        let result = actor
+            .apply_updates("client1".to_string(), 1, numbered(1..=1))
+            .await;
+        assert!(result.is_err());
+        assert!(actor.get_updates_since(0).await.is_err());
+        assert!(actor.snapshot().await.is_err());
+        assert_eq!(storage.calls(StorageOp::AppendUpdate), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_append_mid_batch() {
+        use crate::storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, StorageOp};
+
+        let plan = FaultPlan::new().on_call(
+            StorageOp::AppendUpdate,
+            2,
+            Fault::Error(std::io::ErrorKind::StorageFull),
+        );
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+        let actor = spawn_meet_actor("test-meet", storage.clone()).await;
+
+        let updates: Vec<_> = (1..=3)
+            .map(|n| openlifter_common::Update {
+                update_key: format!("test.key{n}"),
+                update_value: serde_json::json!(n),
+                local_seq_num: n,
+                after_server_seq_num: 0,
+            })
+            .collect();
+        let acks = actor
+            .apply_updates("client1".to_string(), 1, updates.clone())
+            .await
+            .unwrap();
+
+        // Only the update that reached storage is acknowledged and served
+        assert_eq!(acks, vec![(1, 1)]);
+        let served = actor.get_updates_since(0).await.unwrap();
+        assert_eq!(served.len(), 1);
+        assert_eq!(served[0].server_seq_num, 1);
+        assert_eq!(storage.read_updates("test-meet").await.unwrap().len(), 1);
+
+        // The client resends what was not acknowledged, without duplicates
+        let acks = actor
+            .apply_updates("client1".to_string(), 1, updates[acks.len()..].to_vec())
+            .await
+            .unwrap();
+        assert_eq!(acks, vec![(2, 2), (3, 3)]);
+        let served = actor.get_updates_since(0).await.unwrap();
+        let keys: Vec<&str> = served
+            .iter()
+            .map(|u| u.update.update_key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["test.key1", "test.key2", "test.key3"]);
+        assert_eq!(storage.read_updates("test-meet").await.unwrap().len(), 3);
+
+        // A batch that stores nothing fails as a whole
+        let storage = FaultyStorage::new(
+            MemoryStorage::new(),
+            FaultPlan::new().on_call(
+                StorageOp::AppendUpdate,
+                1,
+                Fault::Error(std::io::ErrorKind::StorageFull),
+            ),
+        );
+        let actor = spawn_meet_actor("test-meet", storage).await;
+        let result = actor.apply_updates("client1".to_string(), 1, updates).await;
+        assert!(matches!(result, Err(crate::error::AppError::Io(_))));
+    }
+
     #[tokio::test]
     async fn test_sequence_gap_detection() {
         let (actor, _temp_dir) = setup().await;
@@ -0,0 +1,97 @@
+// crates/backend-lib/src/meet_id.rs
+
+//! Meet ID allocation.
+//!
+//! Meet IDs are nine cryptographically random digits presented in groups of
+//! three, e.g. `123-456-789`. They are only ever created here, and an ID is
+//! never handed out twice: it is registered in the meet registry, and thereby
+//! reserved in storage, before it is returned and stays taken after the meet
+//! is finished.
+use crate::{error::AppError, meet_registry::MeetRegistry};
+use rand::{rngs::OsRng, Rng, TryRngCore};
+
+/// Number of possible meet IDs
+const MEET_ID_SPACE: u32 = 1_000_000_000;
+
+/// Collisions tolerated before giving up on allocation
+const MAX_ALLOCATION_ATTEMPTS: usize = 16;
+
+/// Generate a random meet ID without reserving it
+pub fn generate_meet_id() -> String {
+    format_meet_id(OsRng.unwrap_err().random_range(0..MEET_ID_SPACE))
+}
+
+/// Format a number below `MEET_ID_SPACE` as `ddd-ddd-ddd`
+fn format_meet_id(n: u32) -> String {
+    format!(
+        "{:03}-{:03}-{:03}",
+        n / 1_000_000,
+        n / 1_000 % 1_000,
+        n % 1_000
+    )
+}
+
+/// Allocate and register a meet ID that no current or finished meet uses
+pub async fn allocate_meet_id(meets: &dyn MeetRegistry) -> Result<String, AppError> {
+    allocate_with(meets, generate_meet_id).await
+}
+
+async fn allocate_with(
+    meets: &dyn MeetRegistry,
+    mut generate: impl FnMut() -> String,
+) -> Result<String, AppError> {
+    for _ in 0..MAX_ALLOCATION_ATTEMPTS {
+        let meet_id = generate();
+        if meets.register(&meet_id).await? {
+            return Ok(meet_id);
+        }
+        tracing::warn!("Meet ID {} is already taken, generating another", meet_id);
+    }
+
+    Err(AppError::Internal(
+        "Could not allocate a unique meet ID".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meet_registry::InMemoryMeetRegistry;
+
+    #[test]
+    fn test_meet_id_format() {
+        assert_eq!(format_meet_id(0), "000-000-000");
+        assert_eq!(format_meet_id(12_345_678), "012-345-678");
+        assert_eq!(format_meet_id(MEET_ID_SPACE - 1), "999-999-999");
+
+        let id = generate_meet_id();
+        assert_eq!(id.len(), 11);
+        assert!(id
+            .split('-')
+            .all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit())));
+    }
+
+    #[tokio::test]
+    async fn test_allocation_skips_used_ids() {
+        let meets = InMemoryMeetRegistry::new();
+        meets.register("100-000-001").await.unwrap();
+        meets.register("100-000-002").await.unwrap();
+        meets.finish("100-000-002").await.unwrap();
+
+        let mut candidates = ["100-000-001", "100-000-002", "100-000-003"].into_iter();
+        let id = allocate_with(&meets, || candidates.next().unwrap().to_string())
+            .await
+            .unwrap();
+        assert_eq!(id, "100-000-003");
+        assert!(meets.meet_exists("100-000-003").await);
+    }
+
+    #[tokio::test]
+    async fn test_allocation_gives_up() {
+        let meets = InMemoryMeetRegistry::new();
+        meets.register("100-000-001").await.unwrap();
+
+        let result = allocate_with(&meets, || "100-000-001".to_string()).await;
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+}
@@ -0,0 +1,247 @@
+// crates/backend-lib/src/meet_registry.rs
+
+//! Registry of every meet the server knows about.
+//!
+//! The registry answers "does this meet exist" and "which meets are there"
+//! without touching the disk on every request. The storage-backed registry is
+//! rebuilt at startup by scanning storage, and new meets are reserved in
+//! storage before they are recorded, so IDs stay unique across restarts.
+//! Tests can swap in `InMemoryMeetRegistry` instead.
+use crate::{error::AppError, storage::Storage};
+use async_trait::async_trait;
+use dashmap::{mapref::entry::Entry, DashMap};
+use serde::Serialize;
+
+/// Lifecycle stage of a meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeetStatus {
+    /// Live and accepting updates
+    Current,
+    /// Archived; its ID is never reused
+    Finished,
+}
+
+/// A meet known to the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MeetRecord {
+    pub meet_id: String,
+    pub status: MeetStatus,
+}
+
+#[async_trait]
+pub trait MeetRegistry: Send + Sync {
+    /// Claim `meet_id` for a new meet, returning false if it is already used
+    async fn register(&self, meet_id: &str) -> Result<bool, AppError>;
+    /// Look up a single meet
+    async fn get(&self, meet_id: &str) -> Option<MeetRecord>;
+    /// All known meets, sorted by ID
+    async fn list_meets(&self) -> Vec<MeetRecord>;
+    /// Archive a current meet
+    async fn finish(&self, meet_id: &str) -> Result<(), AppError>;
+    /// Forget a current meet that was registered but never set up
+    async fn release(&self, meet_id: &str) -> Result<(), AppError>;
+
+    /// Whether a current or finished meet uses `meet_id`
+    async fn meet_exists(&self, meet_id: &str) -> bool {
+        self.get(meet_id).await.is_some()
+    }
+}
+
+/// Sort an index into the records returned by `list_meets`
+fn sorted_records(meets: &DashMap<String, MeetStatus>) -> Vec<MeetRecord> {
+    let mut records: Vec<MeetRecord> = meets
+        .iter()
+        .map(|entry| MeetRecord {
+            meet_id: entry.key().clone(),
+            status: *entry.value(),
+        })
+        .collect();
+    records.sort_by(|a, b| a.meet_id.cmp(&b.meet_id));
+    records
+}
+
+/// Registry kept in memory and backed by `Storage`
+pub struct StorageMeetRegistry<S> {
+    storage: S,
+    meets: DashMap<String, MeetStatus>,
+}
+
+impl<S: Storage> StorageMeetRegistry<S> {
+    /// Build the registry from the meets already in storage
+    pub async fn load(storage: S) -> Result<Self, AppError> {
+        let meets = DashMap::new();
+        for record in storage.list_meets().await? {
+            meets.insert(record.meet_id, record.status);
+        }
+        tracing::info!("Meet registry loaded {} meets", meets.len());
+
+        Ok(Self { storage, meets })
+    }
+}
+
+#[async_trait]
+impl<S: Storage + Send + Sync> MeetRegistry for StorageMeetRegistry<S> {
+    async fn register(&self, meet_id: &str) -> Result<bool, AppError> {
+        if self.meets.contains_key(meet_id) {
+            return Ok(false);
+        }
+
+        // Storage has the final say, which also covers meets added behind our back
+        if !self.storage.reserve_meet_id(meet_id).await? {
+            return Ok(false);
+        }
+        self.meets.insert(meet_id.to_string(), MeetStatus::Current);
+        Ok(true)
+    }
+
+    async fn get(&self, meet_id: &str) -> Option<MeetRecord> {
+        self.meets.get(meet_id).map(|status| MeetRecord {
+            meet_id: meet_id.to_string(),
+            status: *status,
+        })
+    }
+
+    async fn list_meets(&self) -> Vec<MeetRecord> {
+        sorted_records(&self.meets)
+    }
+
+    async fn finish(&self, meet_id: &str) -> Result<(), AppError> {
+        if self.meets.get(meet_id).as_deref() != Some(&MeetStatus::Current) {
+            return Err(AppError::MeetNotFound);
+        }
+
+        self.storage.archive_meet(meet_id).await?;
+        self.meets.insert(meet_id.to_string(), MeetStatus::Finished);
+        Ok(())
+    }
+
+    async fn release(&self, meet_id: &str) -> Result<(), AppError> {
+        if self.meets.get(meet_id).as_deref() != Some(&MeetStatus::Current) {
+            return Err(AppError::MeetNotFound);
+        }
+
+        self.storage.release_meet_id(meet_id).await?;
+        self.meets.remove(meet_id);
+        Ok(())
+    }
+}
+
+/// Registry that only lives in memory, for tests
+#[derive(Debug, Default)]
+pub struct InMemoryMeetRegistry {
+    meets: DashMap<String, MeetStatus>,
+}
+
+impl InMemoryMeetRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MeetRegistry for InMemoryMeetRegistry {
+    async fn register(&self, meet_id: &str) -> Result<bool, AppError> {
+        match self.meets.entry(meet_id.to_string()) {
+            Entry::Occupied(_) => Ok(false),
+            Entry::Vacant(entry) => {
+                entry.insert(MeetStatus::Current);
+                Ok(true)
+            },
+        }
+    }
+
+    async fn get(&self, meet_id: &str) -> Option<MeetRecord> {
+        self.meets.get(meet_id).map(|status| MeetRecord {
+            meet_id: meet_id.to_string(),
+            status: *status,
+        })
+    }
+
+    async fn list_meets(&self) -> Vec<MeetRecord> {
+        sorted_records(&self.meets)
+    }
+
+    async fn finish(&self, meet_id: &str) -> Result<(), AppError> {
+        match self.meets.get_mut(meet_id) {
+            Some(mut status) if *status == MeetStatus::Current => {
+                *status = MeetStatus::Finished;
+                Ok(())
+            },
+            _ => Err(AppError::MeetNotFound),
+        }
+    }
+
+    async fn release(&self, meet_id: &str) -> Result<(), AppError> {
+        self.meets
+            .remove_if(meet_id, |_, status| *status == MeetStatus::Current)
+            .map(|_| ())
+            .ok_or(AppError::MeetNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FlatFileStorage;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_registry_rebuilt_from_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+
+        let registry = StorageMeetRegistry::load(storage.clone()).await.unwrap();
+        assert!(registry.register("111-111-111").await.unwrap());
+        assert!(registry.register("222-222-222").await.unwrap());
+        assert!(!registry.register("111-111-111").await.unwrap());
+        registry.finish("222-222-222").await.unwrap();
+
+        // A restart sees the same meets
+        let reloaded = StorageMeetRegistry::load(storage).await.unwrap();
+        assert_eq!(reloaded.list_meets().await, registry.list_meets().await);
+        assert_eq!(
+            reloaded.get("222-222-222").await.map(|m| m.status),
+            Some(MeetStatus::Finished)
+        );
+        assert!(!reloaded.register("222-222-222").await.unwrap());
+        assert!(!reloaded.meet_exists("333-333-333").await);
+    }
+
+    #[tokio::test]
+    async fn test_release_returns_id_to_pool() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+        let registry = StorageMeetRegistry::load(storage.clone()).await.unwrap();
+
+        assert!(registry.register("111-111-111").await.unwrap());
+        registry.release("111-111-111").await.unwrap();
+        assert!(!registry.meet_exists("111-111-111").await);
+        assert!(storage.list_meets().await.unwrap().is_empty());
+        assert!(registry.register("111-111-111").await.unwrap());
+
+        // Finished meets keep their IDs
+        registry.finish("111-111-111").await.unwrap();
+        assert!(matches!(
+            registry.release("111-111-111").await,
+            Err(AppError::MeetNotFound)
+        ));
+        assert!(!registry.register("111-111-111").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_registry() {
+        let registry = InMemoryMeetRegistry::new();
+        assert!(registry.register("meet").await.unwrap());
+        assert!(!registry.register("meet").await.unwrap());
+        assert!(registry.meet_exists("meet").await);
+
+        registry.finish("meet").await.unwrap();
+        assert!(matches!(
+            registry.finish("meet").await,
+            Err(AppError::MeetNotFound)
+        ));
+        assert_eq!(registry.list_meets().await.len(), 1);
+    }
+}
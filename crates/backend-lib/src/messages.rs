@@ -1,13 +1,14 @@
 // crates/backend-lib/src/messages.rs
 
+use openlifter_common::UpdateWithServerSeq;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "msgType")]
 pub enum ClientMessage {
+    /// Create a meet; the server allocates its ID
     CreateMeet {
-        meet_id: String,
         password: String,
         location_name: String,
         priority: u8,
@@ -41,6 +42,99 @@ pub enum ClientMessage {
         updates: Vec<Update>,
         priority: u8,
     },
+    /// Rebind a new socket to an existing session after a reconnect
+    Resume {
+        session_token: String,
+        last_server_seq: u64,
+    },
+    /// End this session; the socket stays open but is no longer subscribed
+    Logout { session_token: String },
+    /// Replace this session's token with a fresh one
+    RotateSession { session_token: String },
+    /// Director only: list the sessions of a meet
+    ListSessions {
+        meet_id: String,
+        session_token: String,
+    },
+    /// Director only: revoke sessions by location name and/or token
+    RevokeSession {
+        meet_id: String,
+        session_token: String,
+        location_name: Option<String>,
+        target_token: Option<String>,
+    },
+    /// Director only: replace the meet password and sign everyone else out
+    ChangeMeetPassword {
+        meet_id: String,
+        session_token: String,
+        old_password: String,
+        new_password: String,
+    },
+}
+
+impl ClientMessage {
+    /// The `msgType` this message is tagged with
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            ClientMessage::CreateMeet { .. } => "CreateMeet",
+            ClientMessage::JoinMeet { .. } => "JoinMeet",
+            ClientMessage::UpdateInit { .. } => "UpdateInit",
+            ClientMessage::ClientPull { .. } => "ClientPull",
+            ClientMessage::PublishMeet { .. } => "PublishMeet",
+            ClientMessage::StateRecoveryResponse { .. } => "StateRecoveryResponse",
+            ClientMessage::Resume { .. } => "Resume",
+            ClientMessage::Logout { .. } => "Logout",
+            ClientMessage::RotateSession { .. } => "RotateSession",
+            ClientMessage::ListSessions { .. } => "ListSessions",
+            ClientMessage::RevokeSession { .. } => "RevokeSession",
+            ClientMessage::ChangeMeetPassword { .. } => "ChangeMeetPassword",
+        }
+    }
+
+    /// Meet and session token for messages that act on an existing meet
+    pub fn session_scope(&self) -> Option<(&str, &str)> {
+        match self {
+            ClientMessage::UpdateInit {
+                meet_id,
+                session_token,
+                ..
+            }
+            | ClientMessage::ClientPull {
+                meet_id,
+                session_token,
+                ..
+            }
+            | ClientMessage::PublishMeet {
+                meet_id,
+                session_token,
+                ..
+            }
+            | ClientMessage::StateRecoveryResponse {
+                meet_id,
+                session_token,
+                ..
+            }
+            | ClientMessage::ListSessions {
+                meet_id,
+                session_token,
+            }
+            | ClientMessage::RevokeSession {
+                meet_id,
+                session_token,
+                ..
+            }
+            | ClientMessage::ChangeMeetPassword {
+                meet_id,
+                session_token,
+                ..
+            } => Some((meet_id, session_token)),
+            ClientMessage::CreateMeet { .. }
+            | ClientMessage::JoinMeet { .. }
+            | ClientMessage::Resume { .. }
+            | ClientMessage::Logout { .. }
+            | ClientMessage::RotateSession { .. } => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +148,9 @@ pub enum ServerMessage {
         meet_id: String,
         session_token: String,
     },
+    /// Server sequence numbers of the stored updates, in the order sent. If
+    /// storage fails partway through a batch only the stored prefix is
+    /// acknowledged; the client resends the rest.
     UpdateAck {
         meet_id: String,
         update_ids: Vec<String>,
@@ -69,10 +166,19 @@ pub enum ServerMessage {
         meet_id: String,
         updates_rejected: Vec<(String, String)>,
     },
+    /// The live relay fell behind and dropped updates; the client should
+    /// `ClientPull` from `last_server_seq`, the last one it was relayed
+    PullRequired {
+        meet_id: String,
+        last_server_seq: u64,
+    },
     ServerPull {
         meet_id: String,
         last_server_seq: u64,
         updates_relayed: Vec<UpdateWithMetadata>,
+        /// More updates follow; pull again from the last one relayed
+        #[serde(default)]
+        has_more: bool,
     },
     PublishAck {
         meet_id: String,
@@ -86,6 +192,11 @@ pub enum ServerMessage {
     InvalidSession {
         session_token: String,
     },
+    /// The session is valid but not for the meet named in the request
+    Forbidden {
+        meet_id: String,
+        reason: String,
+    },
     Error {
         code: String,
         message: String,
@@ -99,6 +210,44 @@ pub enum ServerMessage {
         new_seq_num: u64,
         updates_recovered: usize,
     },
+    /// Full meet state, sent instead of individual updates when a resuming
+    /// client is too far behind
+    MeetSnapshot {
+        meet_id: String,
+        server_seq: u64,
+        state: serde_json::Value,
+    },
+    /// The socket is bound to the session and live relays have resumed
+    Resumed {
+        meet_id: String,
+        location_name: String,
+        priority: u8,
+        server_seq: u64,
+    },
+    LoggedOut {
+        meet_id: String,
+    },
+    SessionRotated {
+        meet_id: String,
+        session_token: String,
+    },
+    SessionList {
+        meet_id: String,
+        sessions: Vec<SessionSummary>,
+    },
+    SessionsRevoked {
+        meet_id: String,
+        revoked: usize,
+    },
+    /// Pushed to a socket whose session was revoked, just before it is closed
+    SessionRevoked {
+        meet_id: String,
+        reason: String,
+    },
+    MeetPasswordChanged {
+        meet_id: String,
+        sessions_invalidated: usize,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,12 +265,30 @@ pub struct UpdateWithMetadata {
     pub priority: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl From<&UpdateWithServerSeq> for UpdateWithMetadata {
+    fn from(u: &UpdateWithServerSeq) -> Self {
+        Self {
+            update: Update {
+                location: u.update.update_key.clone(),
+                value: u.update.update_value.to_string(),
+                #[allow(clippy::cast_possible_wrap)]
+                timestamp: u.update.local_seq_num as i64,
+            },
+            source_client: u.source_client_id.clone(),
+            server_seq: u.server_seq_num,
+            priority: u.source_client_priority,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Session {
     pub token: String,
     pub meet_id: String,
     pub location_name: String,
     pub priority: u8,
+    #[serde(default)]
+    pub role: SessionRole,
 }
 
 impl Session {
@@ -131,10 +298,31 @@ impl Session {
             meet_id,
             location_name,
             priority,
+            role: SessionRole::default(),
         }
     }
 }
 
+/// What a session is allowed to do within its meet
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionRole {
+    /// The client that created the meet; may manage other sessions
+    Director,
+    /// Any client that joined the meet
+    #[default]
+    Station,
+}
+
+/// Session as shown to a meet director. Tokens are never listed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub location_name: String,
+    pub priority: u8,
+    pub role: SessionRole,
+    /// Number of sockets currently using the session
+    pub connections: usize,
+}
+
 // Store client information with priority
 #[derive(Debug, Clone)]
 pub struct ClientInfo {
@@ -161,7 +349,6 @@ mod tests {
     fn test_client_message_serialization() {
         // Test CreateMeet message
         let create_meet = ClientMessage::CreateMeet {
-            meet_id: "test-meet".to_string(),
             password: "TestPassword123!".to_string(),
             location_name: "Test Location".to_string(),
             priority: 10,
@@ -173,7 +360,7 @@ mod tests {
         // Verify the structure
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed["msgType"], "CreateMeet");
-        assert_eq!(parsed["meet_id"], "test-meet");
+        assert!(parsed.get("meet_id").is_none());
         assert_eq!(parsed["password"], "TestPassword123!");
         assert_eq!(parsed["location_name"], "Test Location");
         assert_eq!(parsed["priority"], 10);
@@ -182,12 +369,10 @@ mod tests {
         let parsed_msg: ClientMessage = serde_json::from_str(&json).unwrap();
         match parsed_msg {
             ClientMessage::CreateMeet {
-                meet_id,
                 password,
                 location_name,
                 priority,
             } => {
-                assert_eq!(meet_id, "test-meet");
                 assert_eq!(password, "TestPassword123!");
                 assert_eq!(location_name, "Test Location");
                 assert_eq!(priority, 10);
@@ -1,10 +1,170 @@
-// ==============
 // crates/backend-lib/src/metrics.rs
 
-//! Central place for Prometheus metric keys
+/** Metric names, labels and the Prometheus recorder.
+Every metric the server records is named here. Names use dots, which the
+exporter turns into underscores, so `ws.active` is scraped as `ws_active`.
+Per-meet metrics carry a `meet_id` label, per-message metrics a
+`message_type` label and the heartbeat round-trip time a `connection_id`
+label; nothing else is put in labels.
+
+Metrics are only kept once `install_recorder` has been called, which
+`backend-bin` does at startup. Until then every call is a no-op, and
+`/metrics` answers 404. Like the admin API, `/metrics` needs the operator
+token and is hidden when none is configured. */
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+/// Label holding the meet a metric belongs to
+pub const LABEL_MEET_ID: &str = "meet_id";
+/// Label holding the `msgType` of a client message
+pub const LABEL_MESSAGE_TYPE: &str = "message_type";
+/// Label holding the server-assigned ID of a WebSocket connection
+pub const LABEL_CONNECTION_ID: &str = "connection_id";
+
 pub const WS_CONNECTION: &str = "ws.connection";
 pub const WS_ACTIVE: &str = "ws.active";
+pub const WS_MESSAGES: &str = "ws.messages";
+pub const WS_MESSAGE_DURATION_MS: &str = "ws.message_duration_ms";
+pub const WS_RTT_MS: &str = "ws.rtt_ms";
+pub const WS_HEARTBEAT_TIMEOUT: &str = "ws.heartbeat_timeout";
 pub const MEET_CREATED: &str = "meet.created";
 pub const MEET_JOINED: &str = "meet.joined";
+pub const MEET_ACTIVE: &str = "meet.active";
+pub const MEET_SEQUENCE_GAPS: &str = "meet.sequence_gaps";
+pub const MEET_PUBLISHED: &str = "meet.published";
+pub const MEET_CSV_BYTES: &str = "meet.csv_bytes";
 pub const UPDATE_ACCEPTED: &str = "update.accepted";
 pub const UPDATE_BATCH_SIZE: &str = "update.batch_size";
+pub const PULL_UPDATES: &str = "pull.updates";
+pub const PULL_DISK_READS: &str = "pull.disk_reads";
+pub const AUTH_MEET_MISMATCH: &str = "auth.meet_mismatch";
+pub const RELAY_UPDATES_RECEIVED: &str = "relay.updates_received";
+pub const RELAY_UPDATES_COALESCED: &str = "relay.updates_coalesced";
+pub const RELAY_BATCHES_SENT: &str = "relay.batches_sent";
+pub const RELAY_BATCH_SIZE: &str = "relay.batch_size";
+pub const RELAY_LAGGED: &str = "relay.lagged";
+pub const RETENTION_PURGED: &str = "retention.purged";
+
+/// Handle of the installed recorder, used to render `/metrics`
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Histograms of counts per message or batch
+const COUNT_BUCKETS: &[f64] = &[1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0];
+
+/// Histograms of milliseconds
+const MS_BUCKETS: &[f64] = &[
+    1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0,
+];
+
+/// Install the Prometheus recorder as the global metrics recorder
+/// Histograms are drained by `PrometheusHandle::run_upkeep`, which the
+/// caller must run every few seconds.
+/// # Returns
+/// * `Ok(PrometheusHandle)` - Handle of the recorder
+/// * `Err(BuildError)` if a recorder is already installed
+pub fn install_recorder() -> Result<PrometheusHandle, BuildError> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets(COUNT_BUCKETS)?
+        .set_buckets_for_metric(Matcher::Suffix("_ms".to_string()), MS_BUCKETS)?
+        .set_buckets_for_metric(
+            Matcher::Full(MEET_CSV_BYTES.to_string()),
+            &[1e3, 1e4, 1e5, 1e6, 1e7],
+        )?
+        .install_recorder()?;
+    describe();
+    // Only one recorder can be installed, so the handle is only ever set once
+    let _ = PROMETHEUS.set(handle.clone());
+    Ok(handle)
+}
+
+/// Register the description and unit of every metric
+fn describe() {
+    describe_counter!(WS_CONNECTION, "WebSocket connections opened");
+    describe_gauge!(WS_ACTIVE, "Open WebSocket connections");
+    describe_counter!(WS_MESSAGES, "Client messages handled, by message type");
+    describe_histogram!(
+        WS_MESSAGE_DURATION_MS,
+        Unit::Milliseconds,
+        "Time taken to handle a client message, by message type"
+    );
+    describe_histogram!(
+        WS_RTT_MS,
+        Unit::Milliseconds,
+        "Heartbeat round-trip time, by connection"
+    );
+    describe_counter!(WS_HEARTBEAT_TIMEOUT, "Connections closed for missing pongs");
+    describe_counter!(MEET_CREATED, "Meets created");
+    describe_counter!(MEET_JOINED, "Sessions that joined a meet");
+    describe_gauge!(MEET_ACTIVE, "Running meet actors");
+    describe_counter!(MEET_SEQUENCE_GAPS, "Client sequence gaps detected");
+    describe_counter!(MEET_PUBLISHED, "Results published");
+    describe_histogram!(MEET_CSV_BYTES, Unit::Bytes, "Size of published results");
+    describe_counter!(UPDATE_ACCEPTED, "Updates stored and relayed");
+    describe_histogram!(UPDATE_BATCH_SIZE, Unit::Count, "Updates per client batch");
+    describe_histogram!(PULL_UPDATES, Unit::Count, "Updates returned per pull");
+    describe_counter!(PULL_DISK_READS, "Pulls older than the in-memory window");
+    describe_counter!(
+        AUTH_MEET_MISMATCH,
+        "Messages refused for naming another meet"
+    );
+    describe_counter!(RELAY_UPDATES_RECEIVED, "Updates queued for relay");
+    describe_counter!(
+        RELAY_UPDATES_COALESCED,
+        "Relayed updates replaced by a newer one"
+    );
+    describe_counter!(RELAY_BATCHES_SENT, "UpdateRelay messages sent");
+    describe_histogram!(
+        RELAY_BATCH_SIZE,
+        Unit::Count,
+        "Updates per UpdateRelay message"
+    );
+    describe_counter!(
+        RELAY_LAGGED,
+        "Updates skipped by connections that fell behind"
+    );
+    describe_counter!(RETENTION_PURGED, "Meets purged by the retention policy");
+}
+
+/// Serve the installed recorder's metrics in the Prometheus text format
+pub async fn metrics_handler() -> Response {
+    match PROMETHEUS.get() {
+        Some(handle) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            handle.render(),
+        )
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "Metrics are not enabled").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{counter, gauge, histogram};
+
+    #[test]
+    fn test_labels_and_values() {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets(COUNT_BUCKETS)
+            .unwrap()
+            .build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            counter!(UPDATE_ACCEPTED, LABEL_MEET_ID => "123456789").increment(3);
+            gauge!(WS_ACTIVE).increment(2.0);
+            gauge!(WS_ACTIVE).decrement(1.0);
+            histogram!(WS_MESSAGE_DURATION_MS, LABEL_MESSAGE_TYPE => "ClientPull").record(4.0);
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains("update_accepted{meet_id=\"123456789\"} 3"));
+        assert!(rendered.contains("ws_active 1"));
+        assert!(rendered
+            .contains("ws_message_duration_ms_bucket{message_type=\"ClientPull\",le=\"5\"} 1"));
+    }
+}
@@ -2,8 +2,10 @@
 
 //! Middleware for the `OpenLifter` WebSocket server.
 
+pub mod operator;
 pub mod rate_limit;
 
+pub use operator::{is_operator, require_operator};
 pub use rate_limit::rate_limit;
 
 #[cfg(test)]
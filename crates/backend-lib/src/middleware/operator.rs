@@ -0,0 +1,72 @@
+// crates/backend-lib/src/middleware/operator.rs
+
+//! Operator authentication for the admin API and `/metrics`
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::config::Settings;
+use crate::storage::Storage;
+use crate::{error::AppError, AppState};
+use axum::{
+    extract::{ConnectInfo, OriginalUri, State},
+    http::{header, HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Compare two tokens in time that depends only on their length
+fn tokens_match(presented: &[u8], expected: &[u8]) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether request headers carry the operator token. Without a configured
+/// token nobody is an operator.
+pub fn is_operator(settings: &Settings, headers: &HeaderMap) -> bool {
+    settings.admin.token().is_some_and(|expected| {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| tokens_match(token.as_bytes(), expected.as_bytes()))
+    })
+}
+
+/// Only let requests carrying the operator token through.
+/// Without a configured token the routes it guards answer as if they didn't
+/// exist.
+pub async fn require_operator<S: Storage + Send + Sync + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    request: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    if state.settings.admin.token().is_none() {
+        return Err(AppError::NotFound(
+            "Operator routes are disabled".to_string(),
+        ));
+    }
+
+    if !is_operator(&state.settings, request.headers()) {
+        let ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        // Nested routers see the path without `/admin`
+        let path = request
+            .extensions()
+            .get::<OriginalUri>()
+            .map_or_else(|| request.uri().path(), |OriginalUri(uri)| uri.path());
+        audit::record(
+            AuditEvent::new(AuditKind::PermissionDenied)
+                .with_ip(ip)
+                .with_detail(format!("Invalid operator token for {}", path)),
+        );
+        return Err(AppError::Auth("Invalid operator token".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,428 @@
+// crates/backend-lib/src/relay.rs
+
+/** Per-connection relay coalescing.
+The meet actor broadcasts every accepted update on its own. Forwarding those
+one at a time means a busy flight fills each connection's outgoing channel
+long before the socket can drain it. Instead, each connection runs a relay
+forwarder that collects updates for a short window, collapses updates to the
+same key (only the newest value is relevant to a client that has not seen
+either), and sends the result as a single `UpdateRelay` message. */
+use crate::{
+    config::WebSocketSettings,
+    messages::{ServerMessage, UpdateWithMetadata},
+    metrics as metric_names,
+};
+use metrics::{counter, histogram};
+use openlifter_common::UpdateWithServerSeq;
+use std::collections::HashMap;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Duration, Instant};
+use tracing::Instrument;
+
+/// Buffer of pending relay updates keyed by update key
+#[derive(Debug)]
+pub struct RelayBatch {
+    pending: HashMap<String, UpdateWithServerSeq>,
+    received: usize,
+}
+
+impl Default for RelayBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RelayBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            received: 0,
+        }
+    }
+
+    /// Add an update, replacing any older update to the same key.
+    /// Returns true if an earlier update was superseded.
+    pub fn push(&mut self, update: UpdateWithServerSeq) -> bool {
+        self.received += 1;
+        match self.pending.get(&update.update.update_key) {
+            Some(existing) if existing.server_seq_num > update.server_seq_num => true,
+            Some(_) => {
+                self.pending
+                    .insert(update.update.update_key.clone(), update);
+                true
+            },
+            None => {
+                self.pending
+                    .insert(update.update.update_key.clone(), update);
+                false
+            },
+        }
+    }
+
+    /// Number of distinct keys waiting to be sent
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether nothing is waiting to be sent
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Number of updates pushed since the last drain, including superseded ones
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Take the pending updates in server sequence order
+    pub fn drain(&mut self) -> Vec<UpdateWithServerSeq> {
+        let mut updates: Vec<_> = self.pending.drain().map(|(_, u)| u).collect();
+        updates.sort_by_key(|u| u.server_seq_num);
+        self.received = 0;
+        updates
+    }
+}
+
+/// Spawn a task that forwards a meet's relay broadcast to one client.
+///
+/// Updates originating from `own_client_id` are skipped, since that client
+/// already received an `UpdateAck` for them. Updates with a server sequence
+/// number at or below `skip_through` are also skipped; this lets a caller that
+/// has just sent a catch-up snapshot avoid relaying the same updates twice.
+/// If the broadcast drops updates because this client fell behind, the batch
+/// collected so far is sent, followed by `PullRequired` naming the last update
+/// received before the gap, so the client fetches the rest with `ClientPull`.
+/// The task ends when either the broadcast or the client channel closes.
+pub fn spawn_relay_forwarder(
+    meet_id: String,
+    mut relay_rx: broadcast::Receiver<UpdateWithServerSeq>,
+    client_tx: mpsc::Sender<ServerMessage>,
+    own_client_id: String,
+    skip_through: u64,
+    settings: &WebSocketSettings,
+) -> JoinHandle<()> {
+    let window = Duration::from_millis(settings.relay_batch_window_ms);
+    let max_batch = settings.relay_max_batch_size.max(1);
+
+    let forward = async move {
+        let mut batch = RelayBatch::new();
+        // Last update received, relayed or not; a pull after a gap starts here
+        let mut last_seen = skip_through;
+
+        loop {
+            // Wait for the first update of the next batch
+            match relay_rx.recv().await {
+                Ok(update) => {
+                    last_seen = last_seen.max(update.server_seq_num);
+                    if !accept(&update, &own_client_id, skip_through) {
+                        continue;
+                    }
+                    push_counted(&mut batch, update);
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    if !report_lag(&meet_id, skipped, last_seen, &client_tx).await {
+                        break;
+                    }
+                    continue;
+                },
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+
+            // Keep collecting until the window expires or the batch is full
+            let deadline = Instant::now() + window;
+            let mut closed = false;
+            let mut lagged = None;
+            while batch.len() < max_batch {
+                match time::timeout_at(deadline, relay_rx.recv()).await {
+                    Err(_) => break,
+                    Ok(Ok(update)) => {
+                        last_seen = last_seen.max(update.server_seq_num);
+                        if accept(&update, &own_client_id, skip_through) {
+                            push_counted(&mut batch, update);
+                        }
+                    },
+                    Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                        lagged = Some(skipped);
+                        break;
+                    },
+                    Ok(Err(broadcast::error::RecvError::Closed)) => {
+                        closed = true;
+                        break;
+                    },
+                }
+            }
+
+            if !flush(&meet_id, &mut batch, &client_tx).await || closed {
+                break;
+            }
+            if let Some(skipped) = lagged {
+                if !report_lag(&meet_id, skipped, last_seen, &client_tx).await {
+                    break;
+                }
+            }
+        }
+    };
+    tokio::spawn(forward.in_current_span())
+}
+
+fn accept(update: &UpdateWithServerSeq, own_client_id: &str, skip_through: u64) -> bool {
+    update.source_client_id != own_client_id && update.server_seq_num > skip_through
+}
+
+fn push_counted(batch: &mut RelayBatch, update: UpdateWithServerSeq) {
+    counter!(metric_names::RELAY_UPDATES_RECEIVED).increment(1);
+    if batch.push(update) {
+        counter!(metric_names::RELAY_UPDATES_COALESCED).increment(1);
+    }
+}
+
+/// Tell the client it missed updates after `last_seen` and must pull them;
+/// returns false once the client has gone away
+async fn report_lag(
+    meet_id: &str,
+    skipped: u64,
+    last_seen: u64,
+    client_tx: &mpsc::Sender<ServerMessage>,
+) -> bool {
+    counter!(metric_names::RELAY_LAGGED).increment(skipped);
+    tracing::warn!(
+        "Relay for meet {} lagged, {} updates skipped after {}",
+        meet_id,
+        skipped,
+        last_seen
+    );
+    client_tx
+        .send(ServerMessage::PullRequired {
+            meet_id: meet_id.to_string(),
+            last_server_seq: last_seen,
+        })
+        .await
+        .is_ok()
+}
+
+/// Send the pending batch; returns false once the client has gone away
+async fn flush(
+    meet_id: &str,
+    batch: &mut RelayBatch,
+    client_tx: &mpsc::Sender<ServerMessage>,
+) -> bool {
+    if batch.is_empty() {
+        return true;
+    }
+
+    let updates: Vec<UpdateWithMetadata> =
+        batch.drain().iter().map(UpdateWithMetadata::from).collect();
+
+    counter!(metric_names::RELAY_BATCHES_SENT).increment(1);
+    #[allow(clippy::cast_precision_loss)]
+    histogram!(metric_names::RELAY_BATCH_SIZE).record(updates.len() as f64);
+
+    client_tx
+        .send(ServerMessage::UpdateRelay {
+            meet_id: meet_id.to_string(),
+            updates,
+        })
+        .await
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openlifter_common::Update;
+
+    fn update(key: &str, seq: u64, client: &str) -> UpdateWithServerSeq {
+        UpdateWithServerSeq {
+            update: Update {
+                update_key: key.to_string(),
+                update_value: serde_json::json!(seq),
+                local_seq_num: seq,
+                after_server_seq_num: 0,
+            },
+            server_seq_num: seq,
+            source_client_id: client.to_string(),
+            source_client_priority: 1,
+        }
+    }
+
+    fn settings(window_ms: u64, max_batch: usize) -> WebSocketSettings {
+        WebSocketSettings {
+            relay_batch_window_ms: window_ms,
+            relay_max_batch_size: max_batch,
+            ..WebSocketSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_batch_collapses_superseded_keys() {
+        let mut batch = RelayBatch::new();
+        assert!(!batch.push(update("lifter.1.squat", 1, "a")));
+        assert!(!batch.push(update("lifter.2.squat", 2, "a")));
+        assert!(batch.push(update("lifter.1.squat", 3, "b")));
+
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.received(), 3);
+
+        let drained = batch.drain();
+        assert_eq!(
+            drained.iter().map(|u| u.server_seq_num).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(batch.is_empty());
+        assert_eq!(batch.received(), 0);
+    }
+
+    #[test]
+    fn test_batch_keeps_newest_when_out_of_order() {
+        let mut batch = RelayBatch::new();
+        batch.push(update("k", 5, "a"));
+        assert!(batch.push(update("k", 4, "a")));
+
+        let drained = batch.drain();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(drained[0].server_seq_num, 5);
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_coalesces_within_window() {
+        let (relay_tx, relay_rx) = broadcast::channel(32);
+        let (client_tx, mut client_rx) = mpsc::channel(8);
+        let task = spawn_relay_forwarder(
+            "meet".to_string(),
+            relay_rx,
+            client_tx,
+            "me".to_string(),
+            0,
+            &settings(50, 100),
+        );
+
+        relay_tx.send(update("a", 1, "other")).unwrap();
+        relay_tx.send(update("b", 2, "other")).unwrap();
+        relay_tx.send(update("a", 3, "other")).unwrap();
+        // Our own update is never relayed back to us
+        relay_tx.send(update("c", 4, "me")).unwrap();
+
+        let msg = time::timeout(Duration::from_secs(2), client_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match msg {
+            ServerMessage::UpdateRelay { meet_id, updates } => {
+                assert_eq!(meet_id, "meet");
+                let seqs: Vec<u64> = updates.iter().map(|u| u.server_seq).collect();
+                assert_eq!(seqs, vec![2, 3]);
+            },
+            other => panic!("Expected UpdateRelay, got {other:?}"),
+        }
+
+        drop(relay_tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_splits_at_max_batch_size() {
+        let (relay_tx, relay_rx) = broadcast::channel(32);
+        let (client_tx, mut client_rx) = mpsc::channel(8);
+        let _task = spawn_relay_forwarder(
+            "meet".to_string(),
+            relay_rx,
+            client_tx,
+            "me".to_string(),
+            0,
+            &settings(10_000, 2),
+        );
+
+        for seq in 1..=4 {
+            relay_tx
+                .send(update(&format!("k{seq}"), seq, "other"))
+                .unwrap();
+        }
+
+        for expected in [vec![1, 2], vec![3, 4]] {
+            let msg = time::timeout(Duration::from_secs(2), client_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            let ServerMessage::UpdateRelay { updates, .. } = msg else {
+                panic!("Expected UpdateRelay, got {msg:?}");
+            };
+            let seqs: Vec<u64> = updates.iter().map(|u| u.server_seq).collect();
+            assert_eq!(seqs, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_skips_already_seen_updates() {
+        let (relay_tx, relay_rx) = broadcast::channel(32);
+        let (client_tx, mut client_rx) = mpsc::channel(8);
+        let _task = spawn_relay_forwarder(
+            "meet".to_string(),
+            relay_rx,
+            client_tx,
+            "me".to_string(),
+            2,
+            &settings(10, 100),
+        );
+
+        relay_tx.send(update("a", 2, "other")).unwrap();
+        relay_tx.send(update("b", 3, "other")).unwrap();
+
+        let msg = time::timeout(Duration::from_secs(2), client_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let ServerMessage::UpdateRelay { updates, .. } = msg else {
+            panic!("Expected UpdateRelay, got {msg:?}");
+        };
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].server_seq, 3);
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_asks_lagging_client_to_pull() {
+        let (relay_tx, relay_rx) = broadcast::channel(2);
+        let (client_tx, mut client_rx) = mpsc::channel(8);
+
+        // The forwarder isn't running yet, so the oldest updates are dropped
+        for seq in 1..=5 {
+            relay_tx
+                .send(update(&format!("k{seq}"), seq, "other"))
+                .unwrap();
+        }
+        let _task = spawn_relay_forwarder(
+            "meet".to_string(),
+            relay_rx,
+            client_tx,
+            "me".to_string(),
+            0,
+            &settings(10, 100),
+        );
+
+        let msg = time::timeout(Duration::from_secs(2), client_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let ServerMessage::PullRequired {
+            meet_id,
+            last_server_seq,
+        } = msg
+        else {
+            panic!("Expected PullRequired, got {msg:?}");
+        };
+        assert_eq!(meet_id, "meet");
+        assert_eq!(last_server_seq, 0);
+
+        // What is still buffered is relayed as usual
+        let msg = time::timeout(Duration::from_secs(2), client_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let ServerMessage::UpdateRelay { updates, .. } = msg else {
+            panic!("Expected UpdateRelay, got {msg:?}");
+        };
+        let seqs: Vec<u64> = updates.iter().map(|u| u.server_seq).collect();
+        assert_eq!(seqs, vec![4, 5]);
+    }
+}
@@ -0,0 +1,522 @@
+// crates/backend-lib/src/retention.rs
+
+/** Retention policy for meet data.
+Meet data includes personal data of lifters, so it is not kept forever. Three
+kinds of data age out, each after its own number of days (see
+`RetentionSettings`):
+
+- abandoned meets: current meets without any change, which are archived and
+  then purged
+- finished meets, counted from when they were archived
+- published CSVs and their return email, counted from publication
+
+A meet past its limit is first reported as pending for the grace period, so
+operators can see what is about to go, and only purged on a later run. A purged
+meet stays listed as finished so its ID is never reused. Every purge is
+recorded as one JSON line in `purge.log` under the storage path, synced before
+any data is removed, so a purge that fails partway is still on record and is
+retried on the next run. With `dry_run`, runs only report what they would
+purge. */
+use crate::{
+    config::RetentionSettings, error::AppError, meet_registry::MeetStatus, metrics as metric_names,
+    storage::MeetActivity, storage::Storage, AppState,
+};
+use chrono::{DateTime, Duration, Utc};
+use metrics::counter;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::{fs as tokio_fs, io::AsyncWriteExt};
+
+/// Name of the purge log under the storage path
+pub const PURGE_LOG_FILE: &str = "purge.log";
+
+/// Why data is purged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeReason {
+    /// A current meet without changes for too long; the whole meet goes
+    AbandonedMeet,
+    /// A finished meet past its limit; the whole meet goes
+    FinishedMeet,
+    /// Published results past their limit; only the CSV and return email go
+    PublishedCsv,
+}
+
+/// Data of one meet that is past its limit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PurgeCandidate {
+    pub meet_id: String,
+    pub reason: PurgeReason,
+    /// When the grace period ends and the data is purged
+    pub purge_at: DateTime<Utc>,
+}
+
+/// Limits of a retention policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    abandoned_meet: Option<Duration>,
+    finished_meet: Option<Duration>,
+    published_csv: Option<Duration>,
+    grace_period: Duration,
+}
+
+fn days(days: u64) -> Option<Duration> {
+    (days > 0).then(|| Duration::days(i64::try_from(days).unwrap_or(i64::MAX / 86_400_000)))
+}
+
+impl RetentionPolicy {
+    pub fn from_settings(settings: &RetentionSettings) -> Self {
+        Self {
+            abandoned_meet: days(settings.abandoned_meet_days),
+            finished_meet: days(settings.finished_meet_days),
+            published_csv: days(settings.published_csv_days),
+            grace_period: Duration::hours(
+                i64::try_from(settings.grace_period_hours).unwrap_or(i64::MAX / 3_600_000),
+            ),
+        }
+    }
+
+    /// Data of a meet that is past its limit at `now`. Purging the whole meet
+    /// supersedes purging its CSV.
+    pub fn candidate(
+        &self,
+        meet_id: &str,
+        status: MeetStatus,
+        activity: &MeetActivity,
+        now: DateTime<Utc>,
+    ) -> Option<PurgeCandidate> {
+        if activity.purged_at.is_some() {
+            return None;
+        }
+
+        let (reason, since, limit) = match status {
+            MeetStatus::Current => (
+                PurgeReason::AbandonedMeet,
+                activity.last_change,
+                self.abandoned_meet,
+            ),
+            MeetStatus::Finished => (
+                PurgeReason::FinishedMeet,
+                activity.finished_at.or(activity.last_change),
+                self.finished_meet,
+            ),
+        };
+        let expiry = |since: Option<DateTime<Utc>>, limit: Option<Duration>| {
+            Some(since? + limit?).filter(|expires| *expires <= now)
+        };
+
+        let (reason, expired_at) = match expiry(since, limit) {
+            Some(expired_at) => (reason, expired_at),
+            None => (
+                PurgeReason::PublishedCsv,
+                expiry(activity.published_at, self.published_csv)?,
+            ),
+        };
+        Some(PurgeCandidate {
+            meet_id: meet_id.to_string(),
+            reason,
+            purge_at: expired_at + self.grace_period,
+        })
+    }
+}
+
+/// Outcome of one retention run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    /// Data purged, or that would have been purged in a dry run
+    pub purged: Vec<PurgeCandidate>,
+    /// Data past its limit but still in its grace period
+    pub pending: Vec<PurgeCandidate>,
+    /// Abandoned meets left alone because clients are connected to them
+    pub in_use: Vec<String>,
+    /// Purges that failed and will be retried on the next run
+    pub failed: usize,
+}
+
+/// One line of the purge log
+#[derive(Serialize)]
+struct PurgeLogEntry<'a> {
+    time: DateTime<Utc>,
+    meet_id: &'a str,
+    reason: PurgeReason,
+}
+
+/// Append a line to the purge log and sync it
+async fn record_purge(path: &Path, candidate: &PurgeCandidate) -> Result<(), AppError> {
+    let entry = PurgeLogEntry {
+        time: Utc::now(),
+        meet_id: &candidate.meet_id,
+        reason: candidate.reason,
+    };
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
+
+    let mut file = tokio_fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    file.sync_data().await?;
+    Ok(())
+}
+
+/// Purge the data of one candidate
+async fn purge<S: Storage>(
+    state: &AppState<S>,
+    candidate: &PurgeCandidate,
+) -> Result<(), AppError> {
+    let meet_id = candidate.meet_id.as_str();
+    match candidate.reason {
+        PurgeReason::AbandonedMeet => {
+            state.meets.finish(meet_id).await?;
+            state.storage.purge_meet(meet_id).await?;
+            state.sessions.remove_sessions_for_meet(meet_id, "").await;
+        },
+        PurgeReason::FinishedMeet => {
+            state.storage.purge_meet(meet_id).await?;
+            state.sessions.remove_sessions_for_meet(meet_id, "").await;
+        },
+        PurgeReason::PublishedCsv => state.storage.purge_meet_csv(meet_id).await?,
+    }
+    Ok(())
+}
+
+/// Record the purge of a candidate, then purge it. An abandoned meet is
+/// left alone while clients are connected to it; its actor is stopped
+/// otherwise.
+/// # Returns
+/// * `Ok(true)` if the candidate was purged
+/// * `Ok(false)` if the meet is in use
+/// * `Err(AppError)` if the purge failed
+async fn record_and_purge<S: Storage + Clone + Send + Sync + 'static>(
+    state: &AppState<S>,
+    log_path: &Path,
+    candidate: &PurgeCandidate,
+) -> Result<bool, AppError> {
+    let purged = async {
+        record_purge(log_path, candidate).await?;
+        purge(state, candidate).await
+    };
+    if candidate.reason == PurgeReason::AbandonedMeet {
+        Ok(state
+            .with_unused_actor_stopped(&candidate.meet_id, purged)
+            .await?
+            .is_some())
+    } else {
+        purged.await.map(|()| true)
+    }
+}
+
+/// Whether clients are connected to a meet's actor, for a dry run
+fn in_use<S: Storage>(state: &AppState<S>, meet_id: &str) -> bool {
+    state
+        .meet_handles
+        .get(meet_id)
+        .is_some_and(|handle| handle.relay_tx.receiver_count() > 0)
+}
+
+/// Apply the configured retention policy to every meet
+/// # Arguments
+/// * `state` - Application state holding the storage and meet registry
+/// * `now` - Time the limits are measured against
+/// # Returns
+/// * `Ok(RetentionReport)` - What was purged and what is pending
+/// * `Err(AppError)` if meet times could not be read
+pub async fn run_retention<S: Storage + Clone + Send + Sync + 'static>(
+    state: &AppState<S>,
+    now: DateTime<Utc>,
+) -> Result<RetentionReport, AppError> {
+    let settings = &state.settings.retention;
+    let policy = RetentionPolicy::from_settings(settings);
+    let log_path: PathBuf = state.settings.storage.path.join(PURGE_LOG_FILE);
+    let mut report = RetentionReport::default();
+
+    for record in state.meets.list_meets().await {
+        let Some(activity) = state.storage.meet_activity(&record.meet_id).await? else {
+            continue;
+        };
+        let Some(candidate) = policy.candidate(&record.meet_id, record.status, &activity, now)
+        else {
+            continue;
+        };
+
+        if candidate.purge_at > now {
+            tracing::info!(
+                "Retention: {} of meet {} will be purged after {}",
+                reason_label(candidate.reason),
+                candidate.meet_id,
+                candidate.purge_at
+            );
+            report.pending.push(candidate);
+            continue;
+        }
+
+        let purged =
+            if settings.dry_run {
+                Ok(candidate.reason != PurgeReason::AbandonedMeet
+                    || !in_use(state, &candidate.meet_id))
+            } else {
+                record_and_purge(state, &log_path, &candidate).await
+            };
+        if let Ok(false) = purged {
+            tracing::info!(
+                "Retention: meet {} is abandoned but has connected clients",
+                candidate.meet_id
+            );
+            report.in_use.push(candidate.meet_id);
+            continue;
+        }
+
+        if settings.dry_run {
+            tracing::info!(
+                "Retention (dry run): would purge {} of meet {}",
+                reason_label(candidate.reason),
+                candidate.meet_id
+            );
+            report.purged.push(candidate);
+            continue;
+        }
+
+        match purged {
+            Ok(_) => {
+                tracing::info!(
+                    "Retention: purged {} of meet {}",
+                    reason_label(candidate.reason),
+                    candidate.meet_id
+                );
+                counter!(metric_names::RETENTION_PURGED).increment(1);
+                report.purged.push(candidate);
+            },
+            Err(err) => {
+                tracing::error!(
+                    "Error purging {} of meet {}: {}",
+                    reason_label(candidate.reason),
+                    candidate.meet_id,
+                    err
+                );
+                report.failed += 1;
+            },
+        }
+    }
+
+    Ok(report)
+}
+
+fn reason_label(reason: PurgeReason) -> &'static str {
+    match reason {
+        PurgeReason::AbandonedMeet => "abandoned meet data",
+        PurgeReason::FinishedMeet => "finished meet data",
+        PurgeReason::PublishedCsv => "published results",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use crate::test_support::app_state;
+    use tempfile::TempDir;
+
+    fn at(day: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(day * 86_400, 0)
+    }
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy::from_settings(&RetentionSettings {
+            abandoned_meet_days: 90,
+            finished_meet_days: 30,
+            published_csv_days: 10,
+            grace_period_hours: 24,
+            ..RetentionSettings::default()
+        })
+    }
+
+    #[test]
+    fn test_abandoned_meet_ages_from_last_change() {
+        let activity = MeetActivity {
+            last_change: at(0),
+            ..MeetActivity::default()
+        };
+        let candidate =
+            |day| policy().candidate("m", MeetStatus::Current, &activity, at(day).unwrap());
+
+        assert_eq!(candidate(89), None);
+        let pending = candidate(90).unwrap();
+        assert_eq!(pending.reason, PurgeReason::AbandonedMeet);
+        assert_eq!(pending.purge_at, at(91).unwrap());
+    }
+
+    #[test]
+    fn test_published_csv_expires_before_meet() {
+        let activity = MeetActivity {
+            last_change: at(0),
+            finished_at: at(5),
+            published_at: at(5),
+            purged_at: None,
+        };
+        let candidate =
+            |day| policy().candidate("m", MeetStatus::Finished, &activity, at(day).unwrap());
+
+        assert_eq!(candidate(14), None);
+        assert_eq!(candidate(15).unwrap().reason, PurgeReason::PublishedCsv);
+        assert_eq!(candidate(35).unwrap().reason, PurgeReason::FinishedMeet);
+
+        // Purged meets and disabled limits are left alone
+        let purged = MeetActivity {
+            purged_at: at(40),
+            ..activity.clone()
+        };
+        assert_eq!(
+            policy().candidate("m", MeetStatus::Finished, &purged, at(400).unwrap()),
+            None
+        );
+        let keep_forever = RetentionPolicy::from_settings(&RetentionSettings {
+            finished_meet_days: 0,
+            published_csv_days: 0,
+            ..RetentionSettings::default()
+        });
+        assert_eq!(
+            keep_forever.candidate("m", MeetStatus::Finished, &activity, at(400).unwrap()),
+            None
+        );
+    }
+
+    async fn setup(dry_run: bool) -> (AppState<MemoryStorage>, TempDir) {
+        let storage = MemoryStorage::new();
+        for meet_id in ["abandoned", "finished", "published"] {
+            storage.reserve_meet_id(meet_id).await.unwrap();
+        }
+        storage.archive_meet("finished").await.unwrap();
+        storage
+            .store_meet_csv("published", "csv", "lifter@example.com")
+            .await
+            .unwrap();
+
+        app_state(storage, |settings| {
+            settings.retention = RetentionSettings {
+                dry_run,
+                abandoned_meet_days: 90,
+                finished_meet_days: 30,
+                published_csv_days: 10,
+                grace_period_hours: 24,
+                ..RetentionSettings::default()
+            };
+        })
+        .await
+    }
+
+    fn purged_reasons(report: &RetentionReport) -> Vec<(&str, PurgeReason)> {
+        // Meets are visited in ID order
+        report
+            .purged
+            .iter()
+            .map(|c| (c.meet_id.as_str(), c.reason))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_run_retention() {
+        let (state, temp_dir) = setup(false).await;
+
+        // Within the grace period nothing is touched
+        let days = |days: i64| Utc::now() + Duration::days(days) + Duration::hours(12);
+        let report = run_retention(&state, days(10)).await.unwrap();
+        assert!(report.purged.is_empty());
+        assert_eq!(report.pending.len(), 1);
+        assert!(state
+            .storage
+            .get_meet_csv("published")
+            .await
+            .unwrap()
+            .is_some());
+
+        let report = run_retention(&state, days(31)).await.unwrap();
+        assert_eq!(report.failed, 0);
+        assert_eq!(
+            purged_reasons(&report),
+            vec![
+                ("finished", PurgeReason::FinishedMeet),
+                ("published", PurgeReason::PublishedCsv),
+            ]
+        );
+        assert_eq!(state.storage.get_meet_csv("published").await.unwrap(), None);
+
+        let later = days(91);
+        let report = run_retention(&state, later).await.unwrap();
+        assert_eq!(
+            purged_reasons(&report),
+            vec![
+                ("abandoned", PurgeReason::AbandonedMeet),
+                ("published", PurgeReason::AbandonedMeet),
+            ]
+        );
+        assert_eq!(
+            state.meets.get("abandoned").await.unwrap().status,
+            MeetStatus::Finished
+        );
+
+        let log = std::fs::read_to_string(temp_dir.path().join(PURGE_LOG_FILE)).unwrap();
+        assert_eq!(log.lines().count(), 4);
+        assert!(log.contains(r#""meet_id":"finished","reason":"finished_meet""#));
+
+        // Nothing is left to purge
+        assert_eq!(
+            run_retention(&state, later).await.unwrap(),
+            RetentionReport::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_changes_nothing() {
+        let (state, temp_dir) = setup(true).await;
+
+        let report = run_retention(&state, Utc::now() + Duration::days(12))
+            .await
+            .unwrap();
+        assert_eq!(
+            purged_reasons(&report),
+            vec![("published", PurgeReason::PublishedCsv)]
+        );
+        assert!(state
+            .storage
+            .get_meet_csv("published")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(!temp_dir.path().join(PURGE_LOG_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_connected_meets_are_not_purged() {
+        let (state, _temp_dir) = setup(false).await;
+        let handle = crate::meet_actor::spawn_meet_actor("abandoned", state.storage.clone()).await;
+        let client = handle.relay_tx.subscribe();
+        state
+            .meet_handles
+            .insert("abandoned".to_string(), handle.clone());
+
+        let report = run_retention(&state, Utc::now() + Duration::days(92))
+            .await
+            .unwrap();
+        assert_eq!(report.in_use, vec!["abandoned".to_string()]);
+        assert_eq!(
+            state.meets.get("abandoned").await.unwrap().status,
+            MeetStatus::Current
+        );
+        assert!(!handle.cmd_tx.is_closed());
+
+        // Once the client leaves, the actor is stopped before the meet goes
+        drop(client);
+        let report = run_retention(&state, Utc::now() + Duration::days(92))
+            .await
+            .unwrap();
+        assert!(report.in_use.is_empty());
+        assert!(handle.cmd_tx.is_closed());
+        assert!(!state.meet_handles.contains_key("abandoned"));
+        assert_eq!(
+            state.meets.get("abandoned").await.unwrap().status,
+            MeetStatus::Finished
+        );
+    }
+}
@@ -7,27 +7,120 @@ with a flat-file implementation that stores data in a simple directory structure
 data/
 |-- current-meets/
 |   |-- {meet_id}/
-|       |-- updates.log      # Append-only log of updates
+|       |-- updates.log      # Append-only log of CRC-framed updates
+|       |-- updates.idx      # Sparse offset index into updates.log
 |       |-- meet-info.json   # Meet metadata (password hash, endpoints)
 |       |-- meet.csv         # Final meet results
 |       |-- return-email.txt # Email for results
 |-- finished-meets/
-     |-- {meet_id}/           # Archived meets
+|   |-- {meet_id}/           # Archived meets
+|       |-- finished-at.txt  # When the meet was archived
+|       |-- purged-at.txt    # When its data was purged; nothing else remains
+|-- staging/                 # Meets being replaced, before they are swapped in
 
-The storage is designed to be simple and reliable, with atomic operations
-where possible. The flat-file implementation is suitable for most use cases
-and provides good performance for the expected load. */
+The storage is designed to be simple and reliable. Whole files are replaced
+atomically and synced to disk, and appended updates are synced according to
+the `durability` policy in `StorageSettings`. Every update log record carries
+a length and checksum, so torn or damaged records are caught when the log is
+read (see `update_log`), and a sparse offset index lets a page of updates be
+read without scanning the whole log (see `log_index`). The flat-file
+implementation is suitable for most use cases and provides good performance
+for the expected load. With the `sqlite` feature, `SqliteStorage` keeps the
+same data in a single `meets.db` instead.
+`EncryptedStorage` wraps any backend to encrypt meet data at rest.
+For tests, `MemoryStorage` keeps everything in memory and `FaultyStorage`
+wraps another backend to inject errors, latency and partial writes. Other
+crates get them with the `test-util` feature. */
+mod durable;
+mod encrypted;
+#[cfg(any(test, feature = "test-util"))]
+mod faulty;
+mod log_index;
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+pub mod update_log;
 
+pub use encrypted::{EncryptedStorage, MASTER_KEYRING_FILE};
+#[cfg(any(test, feature = "test-util"))]
+pub use faulty::{Fault, FaultPlan, FaultyStorage, StorageOp};
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+use crate::auth::Keyring;
+use crate::config::{Durability, StorageBackend, StorageSettings};
 use crate::error::AppError;
+use crate::meet_registry::{MeetRecord, MeetStatus};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+pub(crate) use durable::write_atomic;
+use durable::{sync_dir, AppendSync};
 use openlifter_common::{EndpointPriority, MeetInfo};
-use serde_json;
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    fs as tokio_fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::{Mutex as AsyncMutex, OwnedMutexGuard},
 };
-use tokio::{fs as tokio_fs, io::AsyncWriteExt};
+use update_log::{repair_update_log, LogScan, Salvage};
+use uuid::Uuid;
+
+/// Flat-file marker holding the time a meet was archived
+const FINISHED_AT_FILE: &str = "finished-at.txt";
+
+/// Flat-file marker holding the time a meet's data was purged
+const PURGED_AT_FILE: &str = "purged-at.txt";
+
+/// Flat-file directory where a replaced meet is written before it is swapped in
+const STAGING_DIR: &str = "staging";
+
+/// Times that decide when a meet's data may be purged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeetActivity {
+    /// Last time the meet's updates or meet info changed
+    pub last_change: Option<DateTime<Utc>>,
+    /// When the meet was archived
+    pub finished_at: Option<DateTime<Utc>>,
+    /// When results were published, while they are still stored
+    pub published_at: Option<DateTime<Utc>>,
+    /// When the meet's data was purged
+    pub purged_at: Option<DateTime<Utc>>,
+}
+
+/// Everything stored for a meet, as the backend holds it
+#[derive(Debug, Clone, Default)]
+pub struct MeetData {
+    /// Update log, in append order
+    pub updates: Vec<String>,
+    /// Meet info, unless it was never stored
+    pub info: Option<MeetInfo>,
+    /// Published CSV and return email, unless nothing was published
+    pub csv: Option<(String, String)>,
+}
+
+/// Server sequence number stored in an update line, if it has one
+/// Backends number updates by it rather than by their place in the log, so a
+/// record lost from the middle of a log leaves a gap instead of shifting the
+/// numbers of every record after it.
+pub fn update_seq(line: &str) -> Option<u64> {
+    #[derive(serde::Deserialize)]
+    struct Numbered {
+        #[serde(rename = "serverSeqNum")]
+        server_seq_num: u64,
+    }
+    serde_json::from_str::<Numbered>(line)
+        .ok()
+        .map(|numbered| numbered.server_seq_num)
+}
 
 /// Trait for storage backends
 /// This trait defines the interface for storing and retrieving meet data.
@@ -52,6 +145,33 @@ pub trait Storage: Send + Sync {
     /// * `Err(AppError)` if the operation failed
     async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError>;
 
+    /// Read a page of updates for a meet, starting at a server sequence number
+    /// Updates are numbered by the server sequence number stored in them (see
+    /// `update_seq`), which grows in the order they were appended. Lines
+    /// without one are left out.
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// * `first_seq` - Sequence number of the first update to return
+    /// * `limit` - Maximum number of updates to return
+    /// # Returns
+    /// * `Ok(Vec<String>)` - JSON-encoded updates, fewer than `limit` at the end of the log
+    /// * `Err(AppError)` if the operation failed
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError>;
+
+    /// Replace the whole update log of a current meet in one atomic step
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// * `json_lines` - JSON-encoded updates, in order
+    /// # Returns
+    /// * `Ok(())` if the log was replaced
+    /// * `Err(AppError)` if the operation failed; the old log is then unchanged
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError>;
+
     /// Archive a meet (move from current to finished)
     /// # Arguments
     /// * `meet_id` - ID of the meet to archive
@@ -60,6 +180,30 @@ pub trait Storage: Send + Sync {
     /// * `Err(AppError)` if the operation failed
     async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError>;
 
+    /// List every current and finished meet
+    /// # Returns
+    /// * `Ok(Vec<MeetRecord>)` - Meets found in storage
+    /// * `Err(AppError)` if the operation failed
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError>;
+
+    /// Claim a meet ID for a new meet
+    /// # Arguments
+    /// * `meet_id` - ID to claim
+    /// # Returns
+    /// * `Ok(true)` if the ID was unused and is now reserved
+    /// * `Ok(false)` if a current or finished meet already uses it
+    /// * `Err(AppError)` if the operation failed
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError>;
+
+    /// Give back a reserved meet ID whose meet was never set up
+    /// Everything stored under the ID is deleted. Finished meets are left alone.
+    /// # Arguments
+    /// * `meet_id` - ID to release
+    /// # Returns
+    /// * `Ok(())` if no current meet uses the ID any more
+    /// * `Err(AppError)` if the operation failed
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError>;
+
     /// Store meet information
     /// # Arguments
     /// * `meet_id` - ID of the meet
@@ -97,6 +241,144 @@ pub trait Storage: Send + Sync {
         opl_csv: &str,
         return_email: &str,
     ) -> Result<(), AppError>;
+
+    /// Get the published CSV data of a current meet
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(Some((opl_csv, return_email)))` if results were published
+    /// * `Ok(None)` if nothing was published yet
+    /// * `Err(AppError)` if the operation failed
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError>;
+
+    /// Get the times used by the retention policy for a current or finished meet
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(Some(MeetActivity))` - Times known for the meet
+    /// * `Ok(None)` if the meet doesn't exist
+    /// * `Err(AppError)` if the operation failed
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError>;
+
+    /// Delete every piece of data of a finished meet
+    /// The meet stays listed as finished, so its ID is never reused.
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(())` if the data was deleted
+    /// * `Err(AppError::MeetNotFound)` if there is no finished meet with this ID
+    /// * `Err(AppError)` if the operation failed
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError>;
+
+    /// Delete the published CSV and return email of a current or finished meet
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(())` if nothing published remains
+    /// * `Err(AppError)` if the operation failed
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError>;
+
+    /// Read everything still stored for a finished meet
+    /// The other methods only serve current meets; this lets maintenance such
+    /// as the encryption migration reach archived data.
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// # Returns
+    /// * `Ok(Some(MeetData))` - The meet's data
+    /// * `Ok(None)` if there is no finished meet with this ID
+    /// * `Err(AppError)` if the operation failed
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError>;
+
+    /// Overwrite the data of a finished meet in place
+    /// The meet stays finished and its retention times are kept. The update
+    /// log is replaced; meet info and the published CSV are only replaced when
+    /// given.
+    /// # Arguments
+    /// * `meet_id` - ID of the meet
+    /// * `data` - New data for the meet
+    /// # Returns
+    /// * `Ok(())` if the data was written
+    /// * `Err(AppError::MeetNotFound)` if there is no finished meet with this ID
+    /// * `Err(AppError)` if the operation failed
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError>;
+
+    /// Replace everything stored for a current meet in one step
+    /// The update log, meet info and published CSV all become those in
+    /// `data`; a part `data` lacks is removed. If this fails, the meet keeps
+    /// what it had.
+    /// # Arguments
+    /// * `meet_id` - ID of the meet, which must already be reserved
+    /// * `data` - New data for the meet
+    /// # Returns
+    /// * `Ok(())` if the data was written
+    /// * `Err(AppError::MeetNotFound)` if there is no current meet with this ID
+    /// * `Err(AppError)` if the operation failed
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError>;
+}
+
+/// Open the storage backend selected in `settings`
+/// With encryption enabled, the backend is wrapped in `EncryptedStorage`.
+/// Nothing is rewritten, so this is safe for commands that only read; see
+/// `open_storage_and_migrate`.
+/// # Arguments
+/// * `settings` - Backend and data directory
+/// # Returns
+/// * `Ok(Arc<Box<dyn Storage>>)` - Storage ready for use
+/// * `Err(anyhow::Error)` if the storage could not be opened
+pub async fn open_storage(settings: &StorageSettings) -> anyhow::Result<Arc<Box<dyn Storage>>> {
+    Ok(open_storage_with(settings, false).await?.0)
+}
+
+/// Open the storage backend selected in `settings`, first encrypting meets
+/// still held in plaintext when encryption is enabled
+/// # Arguments
+/// * `settings` - Backend and data directory
+/// # Returns
+/// * `Ok((Arc<Box<dyn Storage>>, usize))` - Storage ready for use and the
+///   number of meets that were encrypted
+/// * `Err(anyhow::Error)` if the storage could not be opened or migrated
+pub async fn open_storage_and_migrate(
+    settings: &StorageSettings,
+) -> anyhow::Result<(Arc<Box<dyn Storage>>, usize)> {
+    open_storage_with(settings, true).await
+}
+
+async fn open_storage_with(
+    settings: &StorageSettings,
+    migrate: bool,
+) -> anyhow::Result<(Arc<Box<dyn Storage>>, usize)> {
+    let storage: Box<dyn Storage> = match settings.backend {
+        StorageBackend::Flat => Box::new(FlatFileStorage::new(&settings.path)?.with_durability(
+            settings.durability,
+            Duration::from_millis(settings.group_commit_ms),
+        )),
+        #[cfg(feature = "sqlite")]
+        StorageBackend::Sqlite => Box::new(SqliteStorage::open_with_durability(
+            settings.path.join("meets.db"),
+            settings.durability,
+        )?),
+        #[cfg(not(feature = "sqlite"))]
+        StorageBackend::Sqlite => {
+            anyhow::bail!("The sqlite storage backend needs the `sqlite` feature")
+        },
+    };
+    if !settings.encryption.enabled {
+        return Ok((Arc::new(storage), 0));
+    }
+
+    let keys_dir = settings.path.join("keys");
+    fs::create_dir_all(&keys_dir)?;
+    let master = match settings.encryption.key_secret() {
+        Some(secret) => Keyring::from_secrets(&secret, &settings.encryption.previous_key_secrets)?,
+        None => Keyring::load_or_create_file(keys_dir.join(MASTER_KEYRING_FILE))?,
+    };
+    let encrypted = EncryptedStorage::new(Arc::new(storage), master, keys_dir)?;
+    let migrated = if migrate {
+        encrypted.migrate_all().await?
+    } else {
+        0
+    };
+    Ok((Arc::new(Box::new(encrypted)), migrated))
 }
 
 /// Flat-file implementation of the Storage trait
@@ -106,10 +388,14 @@ pub trait Storage: Send + Sync {
 #[derive(Clone)]
 pub struct FlatFileStorage {
     root: PathBuf,
+    appends: Arc<AppendSync>,
+    /// Held per meet while its offset index may change, so reads don't
+    /// extend it twice
+    index_locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
 }
 
 impl FlatFileStorage {
-    /// Create a new flat-file storage instance
+    /// Create a new flat-file storage instance that syncs every append
     /// # Arguments
     /// * `root` - Root directory for storing meet data
     /// # Returns
@@ -119,74 +405,319 @@ impl FlatFileStorage {
         let root = root.as_ref().to_path_buf();
         fs::create_dir_all(root.join("current-meets"))?;
         fs::create_dir_all(root.join("finished-meets"))?;
-        Ok(Self { root })
+        Ok(Self {
+            root,
+            appends: Arc::new(AppendSync::new(Durability::default(), Duration::ZERO)),
+            index_locks: Arc::default(),
+        })
+    }
+
+    /// Use another sync policy for appended updates
+    /// # Arguments
+    /// * `durability` - When appends are synced to disk
+    /// * `group_interval` - Longest a sync with `Durability::Group` waits for
+    ///   appends still being written
+    pub fn with_durability(mut self, durability: Durability, group_interval: Duration) -> Self {
+        self.appends = Arc::new(AppendSync::new(durability, group_interval));
+        self
+    }
+
+    /// Lock the offset index of a meet's update log
+    async fn lock_index(&self, meet_id: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.index_locks.lock().unwrap_or_else(|e| e.into_inner());
+            // Forget the locks nobody holds or waits for
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(meet_id.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// Path of a current meet's update log
+    fn updates_path(&self, meet_id: &str) -> PathBuf {
+        self.root
+            .join("current-meets")
+            .join(meet_id)
+            .join("updates.log")
+    }
+
+    /// Check every record of a meet's update log without changing it
+    /// # Returns
+    /// * `Ok(LogScan)` - Valid records and the position of any damage
+    /// * `Err(AppError)` if the log could not be read
+    pub async fn verify_updates(&self, meet_id: &str) -> Result<LogScan, AppError> {
+        let path = self.updates_path(meet_id);
+        if !path.exists() {
+            return Ok(update_log::scan(&[]));
+        }
+        Ok(update_log::scan(&tokio_fs::read(&path).await?))
+    }
+
+    /// Rewrite a meet's update log keeping only its valid records
+    /// See `repair_update_log`.
+    pub async fn repair_updates(&self, meet_id: &str) -> Result<Salvage, AppError> {
+        let _index = self.lock_index(meet_id).await;
+        repair_update_log(&self.updates_path(meet_id)).await
+    }
+
+    /// Cut an unterminated record off the end of a log through an open,
+    /// writable handle. It was never acknowledged, so nothing is lost.
+    async fn truncate_torn_tail(
+        &self,
+        file: &tokio_fs::File,
+        path: &Path,
+        offset: u64,
+    ) -> Result<(), AppError> {
+        tracing::warn!(
+            "Dropping torn record at byte {} of {}",
+            offset,
+            path.display()
+        );
+        file.set_len(offset).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    /// Directory of a current or finished meet, if it exists
+    async fn existing_meet_dir(&self, meet_id: &str) -> Result<Option<PathBuf>, AppError> {
+        for dir in ["current-meets", "finished-meets"] {
+            let dir = self.root.join(dir).join(meet_id);
+            if tokio_fs::try_exists(&dir).await? {
+                return Ok(Some(dir));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Directory of a current meet, created (and made durable) if needed
+    async fn current_meet_dir(&self, meet_id: &str) -> Result<PathBuf, AppError> {
+        let dir = self.root.join("current-meets").join(meet_id);
+        if !tokio_fs::try_exists(&dir).await? {
+            tokio_fs::create_dir_all(&dir).await?;
+            if self.appends.durability() != Durability::Buffered {
+                sync_dir(&self.root.join("current-meets")).await?;
+            }
+        }
+        Ok(dir)
     }
 }
 
 #[async_trait]
 impl Storage for FlatFileStorage {
-    /// Append a JSON line to `updates.log`.
-    /// The file is created if it doesn't exist, and the update is appended
-    /// atomically using a temporary file.
+    /// Append a JSON line to `updates.log` as a framed record.
+    /// The file is created if it doesn't exist. A torn record left at the end
+    /// by a crash is dropped first, and a failed write is rolled back, so a
+    /// new record always starts on a record boundary. The record is synced
+    /// according to the durability policy before this returns.
     async fn append_update(&self, meet_id: &str, json_line: &str) -> Result<(), AppError> {
-        let path = self
-            .root
-            .join("current-meets")
-            .join(meet_id)
-            .join("updates.log");
-
-        // ensure directory exists
-        tokio_fs::create_dir_all(path.parent().unwrap()).await?;
+        let dir = self.current_meet_dir(meet_id).await?;
+        let path = dir.join("updates.log");
+        // Held until the record is written, so nothing else touches the log
+        // in between; the sync below doesn't need it
+        let index = self.lock_index(meet_id).await;
+        let created = !tokio_fs::try_exists(&path).await?;
 
+        // One handle serves the torn-tail check and the append: reads honour
+        // the seek position, while writes always go to the end of the file.
         let mut file = tokio_fs::OpenOptions::new()
+            .read(true)
             .create(true)
             .append(true)
             .open(&path)
             .await
             .map_err(AppError::from)?;
 
-        file.write_all(json_line.as_bytes()).await?;
-        file.write_all(b"\n").await?;
+        // Announced before writing, so a group sync waits for this append
+        let pending = self.appends.begin();
+        let mut len = file.metadata().await?.len();
+        if len > 0 {
+            let mut last = [0u8];
+            file.seek(std::io::SeekFrom::End(-1)).await?;
+            file.read_exact(&mut last).await?;
+            if last[0] != b'\n' {
+                let mut contents = Vec::with_capacity(len as usize);
+                file.seek(std::io::SeekFrom::Start(0)).await?;
+                file.read_to_end(&mut contents).await?;
+                if let Some(offset) = update_log::scan(&contents).torn_tail {
+                    self.truncate_torn_tail(&file, &path, offset).await?;
+                    len = offset;
+                }
+            }
+        }
+
+        let record = update_log::encode_record(json_line);
+        let written = async {
+            file.write_all(record.as_bytes()).await?;
+            file.flush().await
+        }
+        .await;
+        if let Err(err) = written {
+            // Roll back whatever part of the record reached the file
+            if let Err(rollback) = file.set_len(len).await {
+                tracing::error!(
+                    "Error rolling back failed append to {}: {}",
+                    path.display(),
+                    rollback
+                );
+            }
+            return Err(err.into());
+        }
+        drop(index);
+        pending.sync(&path, &file).await?;
+
+        if created && self.appends.durability() != Durability::Buffered {
+            sync_dir(&dir).await?;
+        }
         Ok(())
     }
 
-    /// Read all updates for a meet
+    /// Read all updates for a meet, verifying every record
     /// Returns an empty vector if the meet doesn't exist or has no updates.
+    /// A torn final record is skipped but left in place: it may be an append
+    /// still being written, and the next append drops it if it is not. Any
+    /// other damage fails with `AppError::CorruptLog` until the log is
+    /// repaired.
     async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError> {
-        let path = self
-            .root
-            .join("current-meets")
-            .join(meet_id)
-            .join("updates.log");
+        let path = self.updates_path(meet_id);
 
         if !path.exists() {
             return Ok(Vec::new());
         }
 
-        let content = tokio_fs::read_to_string(&path).await?;
-        let updates: Vec<String> = content
-            .lines()
-            .filter(|s| !s.is_empty())
-            .map(ToString::to_string)
-            .collect();
+        let scan = update_log::scan(&tokio_fs::read(&path).await?);
+        if let Some(offset) = scan.corrupt_at {
+            tracing::error!(
+                "Error reading update log of meet {}: corrupt record at byte {}",
+                meet_id,
+                offset
+            );
+            return Err(AppError::CorruptLog {
+                meet_id: meet_id.to_string(),
+                offset,
+            });
+        }
+        let updates: Vec<String> = scan.records.into_iter().filter(|s| !s.is_empty()).collect();
 
         Ok(updates)
     }
 
-    /// Archive a meet by moving it from current-meets to finished-meets
-    /// The operation is atomic - it either succeeds completely or fails
-    /// without modifying the filesystem.
+    /// Read a page of updates using the log's offset index
+    /// Only the records from the nearest index entry on are read, and the
+    /// index is extended as new records are passed. Damage before the end of
+    /// the page fails with `AppError::CorruptLog`; a torn final record ends
+    /// the page.
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        let path = self.updates_path(meet_id);
+        let _index = self.lock_index(meet_id).await;
+        let page = log_index::read_from(&path, first_seq, limit).await?;
+        if let Some(offset) = page.corrupt_at {
+            tracing::error!(
+                "Error reading update log of meet {}: corrupt record at byte {}",
+                meet_id,
+                offset
+            );
+            return Err(AppError::CorruptLog {
+                meet_id: meet_id.to_string(),
+                offset,
+            });
+        }
+        Ok(page.records)
+    }
+
+    /// Rewrite `updates.log` atomically using a temporary file
+    /// The offset index no longer matches and is dropped.
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        let path = self.current_meet_dir(meet_id).await?.join("updates.log");
+        let log: String = json_lines
+            .iter()
+            .map(|line| update_log::encode_record(line))
+            .collect();
+        let _index = self.lock_index(meet_id).await;
+        write_atomic(&path, log.as_bytes()).await?;
+        log_index::invalidate(&path).await?;
+        Ok(())
+    }
+
+    /// Archive a meet by moving it from current-meets to finished-meets.
+    /// `finished-at.txt` is written first and moves with the directory; it
+    /// only counts once the meet is under finished-meets, so a crash before
+    /// the move leaves an ordinary current meet that can be archived again.
     async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
         let src = self.root.join("current-meets").join(meet_id);
         let dst = self.root.join("finished-meets").join(meet_id);
 
         if src.exists() {
+            write_atomic(
+                &src.join(FINISHED_AT_FILE),
+                Utc::now().to_rfc3339().as_bytes(),
+            )
+            .await?;
             tokio_fs::rename(src, dst).await?;
+            sync_dir(&self.root.join("current-meets")).await?;
+            sync_dir(&self.root.join("finished-meets")).await?;
         }
 
         Ok(())
     }
 
+    /// List meets by scanning the current-meets and finished-meets directories
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        let mut meets = Vec::new();
+
+        for (dir, status) in [
+            ("current-meets", MeetStatus::Current),
+            ("finished-meets", MeetStatus::Finished),
+        ] {
+            let mut entries = tokio_fs::read_dir(self.root.join(dir)).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if !entry.file_type().await?.is_dir() {
+                    continue;
+                }
+                if let Some(meet_id) = entry.file_name().to_str() {
+                    meets.push(MeetRecord {
+                        meet_id: meet_id.to_string(),
+                        status,
+                    });
+                }
+            }
+        }
+
+        Ok(meets)
+    }
+
+    /// Reserve a meet ID by creating its directory under current-meets.
+    /// Creating the directory is atomic, so two concurrent reservations of the
+    /// same ID cannot both succeed. IDs of finished meets are never reused.
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        if self.root.join("finished-meets").join(meet_id).exists() {
+            return Ok(false);
+        }
+
+        match tokio_fs::create_dir(self.root.join("current-meets").join(meet_id)).await {
+            Ok(()) => {
+                sync_dir(&self.root.join("current-meets")).await?;
+                Ok(true)
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove the meet's directory under current-meets
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        let current = self.root.join("current-meets");
+        match tokio_fs::remove_dir_all(current.join(meet_id)).await {
+            Ok(()) => Ok(sync_dir(&current).await?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Store meet information in meet-info.json
     /// The file is created if it doesn't exist, and the information is written
     /// atomically using a temporary file.
@@ -196,14 +727,7 @@ impl Storage for FlatFileStorage {
         password_hash: &str,
         endpoints: &[EndpointPriority],
     ) -> Result<(), AppError> {
-        let path = self
-            .root
-            .join("current-meets")
-            .join(meet_id)
-            .join("meet-info.json");
-
-        // ensure directory exists
-        tokio_fs::create_dir_all(path.parent().unwrap()).await?;
+        let path = self.current_meet_dir(meet_id).await?.join("meet-info.json");
 
         let meet_info = MeetInfo {
             password_hash: password_hash.to_string(),
@@ -211,7 +735,7 @@ impl Storage for FlatFileStorage {
         };
 
         let json = serde_json::to_string_pretty(&meet_info)?;
-        tokio_fs::write(path, json).await?;
+        write_atomic(&path, json.as_bytes()).await?;
 
         Ok(())
     }
@@ -244,28 +768,250 @@ impl Storage for FlatFileStorage {
         opl_csv: &str,
         return_email: &str,
     ) -> Result<(), AppError> {
-        let path = self
-            .root
-            .join("current-meets")
-            .join(meet_id)
-            .join("meet.csv");
+        let dir = self.current_meet_dir(meet_id).await?;
 
-        // ensure directory exists
-        tokio_fs::create_dir_all(path.parent().unwrap()).await?;
-
-        tokio_fs::write(path, opl_csv).await?;
+        write_atomic(&dir.join("meet.csv"), opl_csv.as_bytes()).await?;
 
         // Store return email
-        let email_path = self
-            .root
-            .join("current-meets")
-            .join(meet_id)
-            .join("return-email.txt");
+        write_atomic(&dir.join("return-email.txt"), return_email.as_bytes()).await?;
+
+        Ok(())
+    }
+
+    /// Read meet.csv and return-email.txt of a current meet
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        let dir = self.root.join("current-meets").join(meet_id);
+        let csv_path = dir.join("meet.csv");
+
+        if !csv_path.exists() {
+            return Ok(None);
+        }
+
+        let opl_csv = tokio_fs::read_to_string(&csv_path).await?;
+        let return_email = tokio_fs::read_to_string(dir.join("return-email.txt")).await?;
+        Ok(Some((opl_csv, return_email)))
+    }
 
-        tokio_fs::write(email_path, return_email).await?;
+    /// Derive meet times from file modification times and the marker files
+    /// written when a meet is archived or purged. Finished meets archived
+    /// before markers existed count as finished at their last change.
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        let Some(dir) = self.existing_meet_dir(meet_id).await? else {
+            return Ok(None);
+        };
 
+        let finished = dir.starts_with(self.root.join("finished-meets"));
+        let mut activity = MeetActivity::default();
+        let mut entries = tokio_fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            match name {
+                // Left behind in a current meet if archiving was interrupted
+                FINISHED_AT_FILE if finished => {
+                    activity.finished_at = read_timestamp(&entry.path()).await;
+                },
+                PURGED_AT_FILE => activity.purged_at = read_timestamp(&entry.path()).await,
+                "meet.csv" => activity.published_at = Some(modified_at(&entry).await?),
+                "updates.log" | "meet-info.json" => {
+                    let modified = modified_at(&entry).await?;
+                    activity.last_change = activity.last_change.max(Some(modified));
+                },
+                _ => {},
+            }
+        }
+        if activity.last_change.is_none() {
+            // Reserved but never written
+            activity.last_change =
+                Some(DateTime::from(tokio_fs::metadata(&dir).await?.modified()?));
+        }
+        if activity.finished_at.is_none() && finished {
+            activity.finished_at = activity.last_change;
+        }
+        Ok(Some(activity))
+    }
+
+    /// Empty the finished meet's directory, leaving only `purged-at.txt`
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        let dir = self.root.join("finished-meets").join(meet_id);
+        if !tokio_fs::try_exists(&dir).await? {
+            return Err(AppError::MeetNotFound);
+        }
+
+        let mut entries = tokio_fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                tokio_fs::remove_dir_all(entry.path()).await?;
+            } else {
+                tokio_fs::remove_file(entry.path()).await?;
+            }
+        }
+        write_atomic(
+            &dir.join(PURGED_AT_FILE),
+            Utc::now().to_rfc3339().as_bytes(),
+        )
+        .await?;
         Ok(())
     }
+
+    /// Remove meet.csv and return-email.txt
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        let Some(dir) = self.existing_meet_dir(meet_id).await? else {
+            return Ok(());
+        };
+        for file in ["meet.csv", "return-email.txt"] {
+            match tokio_fs::remove_file(dir.join(file)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {},
+            }
+        }
+        sync_dir(&dir).await?;
+        Ok(())
+    }
+
+    /// Read the files left under finished-meets
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        let dir = self.root.join("finished-meets").join(meet_id);
+        if !tokio_fs::try_exists(&dir).await? {
+            return Ok(None);
+        }
+
+        let mut data = MeetData::default();
+        let log = dir.join("updates.log");
+        if tokio_fs::try_exists(&log).await? {
+            let scan = update_log::scan(&tokio_fs::read(&log).await?);
+            if let Some(offset) = scan.corrupt_at {
+                return Err(AppError::CorruptLog {
+                    meet_id: meet_id.to_string(),
+                    offset,
+                });
+            }
+            data.updates = scan.records;
+        }
+        let info = dir.join("meet-info.json");
+        if tokio_fs::try_exists(&info).await? {
+            data.info = Some(serde_json::from_str(
+                &tokio_fs::read_to_string(&info).await?,
+            )?);
+        }
+        let csv = dir.join("meet.csv");
+        if tokio_fs::try_exists(&csv).await? {
+            data.csv = Some((
+                tokio_fs::read_to_string(&csv).await?,
+                tokio_fs::read_to_string(dir.join("return-email.txt")).await?,
+            ));
+        }
+        Ok(Some(data))
+    }
+
+    /// Replace the files of a finished meet without changing their
+    /// modification times, which `meet_activity` reads
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let dir = self.root.join("finished-meets").join(meet_id);
+        if !tokio_fs::try_exists(&dir).await? {
+            return Err(AppError::MeetNotFound);
+        }
+
+        let log = dir.join("updates.log");
+        if !data.updates.is_empty() || tokio_fs::try_exists(&log).await? {
+            let records: String = data
+                .updates
+                .iter()
+                .map(|line| update_log::encode_record(line))
+                .collect();
+            let _index = self.lock_index(meet_id).await;
+            write_keeping_mtime(&log, records.as_bytes()).await?;
+            log_index::invalidate(&log).await?;
+        }
+        if let Some(info) = &data.info {
+            let json = serde_json::to_string_pretty(info)?;
+            write_keeping_mtime(&dir.join("meet-info.json"), json.as_bytes()).await?;
+        }
+        if let Some((opl_csv, return_email)) = &data.csv {
+            write_keeping_mtime(&dir.join("meet.csv"), opl_csv.as_bytes()).await?;
+            write_keeping_mtime(&dir.join("return-email.txt"), return_email.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    /// Write the meet into a staging directory, then swap it with the meet's
+    /// directory. The old directory is removed once the new one is in place.
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let current = self.root.join("current-meets");
+        let dir = current.join(meet_id);
+        if !tokio_fs::try_exists(&dir).await? {
+            return Err(AppError::MeetNotFound);
+        }
+
+        let staging = self.root.join(STAGING_DIR);
+        tokio_fs::create_dir_all(&staging).await?;
+        let new_dir = staging.join(format!("{meet_id}.{}", Uuid::new_v4()));
+        let staged = async {
+            tokio_fs::create_dir(&new_dir).await?;
+            let records: String = data
+                .updates
+                .iter()
+                .map(|line| update_log::encode_record(line))
+                .collect();
+            write_atomic(&new_dir.join("updates.log"), records.as_bytes()).await?;
+            if let Some(info) = &data.info {
+                let json = serde_json::to_string_pretty(info)?;
+                write_atomic(&new_dir.join("meet-info.json"), json.as_bytes()).await?;
+            }
+            if let Some((opl_csv, return_email)) = &data.csv {
+                write_atomic(&new_dir.join("meet.csv"), opl_csv.as_bytes()).await?;
+                write_atomic(&new_dir.join("return-email.txt"), return_email.as_bytes()).await?;
+            }
+            Ok::<_, AppError>(())
+        }
+        .await;
+        if let Err(err) = staged {
+            let _ = tokio_fs::remove_dir_all(&new_dir).await;
+            return Err(err);
+        }
+
+        let old_dir = staging.join(format!("{meet_id}.{}", Uuid::new_v4()));
+        let _index = self.lock_index(meet_id).await;
+        tokio_fs::rename(&dir, &old_dir).await?;
+        if let Err(err) = tokio_fs::rename(&new_dir, &dir).await {
+            tokio_fs::rename(&old_dir, &dir).await?;
+            let _ = tokio_fs::remove_dir_all(&new_dir).await;
+            return Err(err.into());
+        }
+        sync_dir(&current).await?;
+        sync_dir(&staging).await?;
+        tokio_fs::remove_dir_all(&old_dir).await?;
+        Ok(())
+    }
+}
+
+/// Replace a file atomically, then restore the modification time it had
+async fn write_keeping_mtime(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let modified = match tokio_fs::metadata(path).await {
+        Ok(metadata) => Some(metadata.modified()?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    write_atomic(path, contents).await?;
+    if let Some(modified) = modified {
+        let file = tokio_fs::OpenOptions::new().write(true).open(path).await?;
+        file.into_std().await.set_modified(modified)?;
+    }
+    Ok(())
+}
+
+/// Read an RFC 3339 timestamp from a marker file
+async fn read_timestamp(path: &Path) -> Option<DateTime<Utc>> {
+    let text = tokio_fs::read_to_string(path).await.ok()?;
+    DateTime::parse_from_rfc3339(text.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+async fn modified_at(entry: &tokio_fs::DirEntry) -> Result<DateTime<Utc>, AppError> {
+    Ok(DateTime::from(entry.metadata().await?.modified()?))
 }
 
 #[async_trait]
@@ -278,10 +1024,35 @@ impl<T: Storage + ?Sized> Storage for Arc<Box<T>> {
         (**self).read_updates(meet_id).await
     }
 
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        (**self).read_updates_from(meet_id, first_seq, limit).await
+    }
+
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        (**self).replace_updates(meet_id, json_lines).await
+    }
+
     async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
         (**self).archive_meet(meet_id).await
     }
 
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        (**self).list_meets().await
+    }
+
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        (**self).reserve_meet_id(meet_id).await
+    }
+
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        (**self).release_meet_id(meet_id).await
+    }
+
     async fn store_meet_info(
         &self,
         meet_id: &str,
@@ -307,13 +1078,390 @@ impl<T: Storage + ?Sized> Storage for Arc<Box<T>> {
             .store_meet_csv(meet_id, opl_csv, return_email)
             .await
     }
+
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        (**self).get_meet_csv(meet_id).await
+    }
+
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        (**self).meet_activity(meet_id).await
+    }
+
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        (**self).purge_meet(meet_id).await
+    }
+
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        (**self).purge_meet_csv(meet_id).await
+    }
+
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        (**self).read_finished_meet(meet_id).await
+    }
+
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        (**self).rewrite_finished_meet(meet_id, data).await
+    }
+
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        (**self).replace_meet(meet_id, data).await
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    //! Checks shared by every `Storage` implementation
     use super::*;
     use tempfile::TempDir;
 
+    pub(crate) async fn check_append_read_updates(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+
+        // Append some updates
+        storage.append_update(meet_id, "update1").await.unwrap();
+        storage.append_update(meet_id, "update2").await.unwrap();
+
+        // Read updates
+        let updates = storage.read_updates(meet_id).await.unwrap();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0], "update1");
+        assert_eq!(updates[1], "update2");
+    }
+
+    pub(crate) async fn check_store_get_meet_info(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        let password_hash = "hashed_password";
+        let endpoints = vec![EndpointPriority {
+            location_name: "location1".to_string(),
+            priority: 1,
+        }];
+        assert!(matches!(
+            storage.get_meet_info(meet_id).await,
+            Err(AppError::MeetNotFound)
+        ));
+
+        // Store meet info
+        storage
+            .store_meet_info(meet_id, password_hash, &endpoints)
+            .await
+            .unwrap();
+
+        // Get meet info
+        let info = storage.get_meet_info(meet_id).await.unwrap();
+        assert_eq!(info.password_hash, password_hash);
+        assert_eq!(info.endpoints.len(), 1);
+        assert_eq!(info.endpoints[0].location_name, "location1");
+        assert_eq!(info.endpoints[0].priority, 1);
+
+        // Storing again replaces the info
+        storage
+            .store_meet_info(meet_id, "new_hash", &[])
+            .await
+            .unwrap();
+        let info = storage.get_meet_info(meet_id).await.unwrap();
+        assert_eq!(info.password_hash, "new_hash");
+        assert!(info.endpoints.is_empty());
+    }
+
+    pub(crate) async fn check_archive_meet(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+
+        // Create some data
+        storage.append_update(meet_id, "test").await.unwrap();
+        storage.store_meet_info(meet_id, "hash", &[]).await.unwrap();
+        // Archive meet
+        storage.archive_meet(meet_id).await.unwrap();
+
+        // Finished meets are listed but no longer served as current
+        let meets = storage.list_meets().await.unwrap();
+        assert_eq!(
+            meets,
+            vec![MeetRecord {
+                meet_id: meet_id.to_string(),
+                status: MeetStatus::Finished,
+            }]
+        );
+        assert!(storage.read_updates(meet_id).await.unwrap().is_empty());
+        assert!(matches!(
+            storage.get_meet_info(meet_id).await,
+            Err(AppError::MeetNotFound)
+        ));
+
+        // Archiving an unknown meet is not an error
+        storage.archive_meet("unknown-meet").await.unwrap();
+    }
+
+    pub(crate) async fn check_reserve_meet_id(storage: &dyn Storage) {
+        assert!(storage.reserve_meet_id("111-222-333").await.unwrap());
+        assert!(!storage.reserve_meet_id("111-222-333").await.unwrap());
+
+        // Finished meets keep their IDs
+        storage.archive_meet("111-222-333").await.unwrap();
+        assert!(!storage.reserve_meet_id("111-222-333").await.unwrap());
+    }
+
+    pub(crate) async fn check_list_meets(storage: &dyn Storage) {
+        storage.reserve_meet_id("111-111-111").await.unwrap();
+        storage.reserve_meet_id("222-222-222").await.unwrap();
+        storage.archive_meet("222-222-222").await.unwrap();
+
+        let mut meets = storage.list_meets().await.unwrap();
+        meets.sort_by(|a, b| a.meet_id.cmp(&b.meet_id));
+        assert_eq!(
+            meets,
+            vec![
+                MeetRecord {
+                    meet_id: "111-111-111".to_string(),
+                    status: MeetStatus::Current,
+                },
+                MeetRecord {
+                    meet_id: "222-222-222".to_string(),
+                    status: MeetStatus::Finished,
+                },
+            ]
+        );
+    }
+
+    /// Stores a CSV for `test-meet`; callers check where it ended up
+    pub(crate) async fn check_store_csv_data(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        assert_eq!(storage.get_meet_csv(meet_id).await.unwrap(), None);
+        storage
+            .store_meet_csv(meet_id, "Old,Csv", "old@example.com")
+            .await
+            .unwrap();
+        storage
+            .store_meet_csv(meet_id, "Name,Weight,Squat", "test@example.com")
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_meet_csv(meet_id).await.unwrap(),
+            Some((
+                "Name,Weight,Squat".to_string(),
+                "test@example.com".to_string()
+            ))
+        );
+    }
+
+    pub(crate) async fn check_replace_updates(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        storage.append_update(meet_id, "update1").await.unwrap();
+        storage.append_update(meet_id, "update2").await.unwrap();
+
+        storage
+            .replace_updates(meet_id, &["new1".to_string(), "new2".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.read_updates(meet_id).await.unwrap(),
+            vec!["new1", "new2"]
+        );
+
+        // Appends continue after the replaced log
+        storage.append_update(meet_id, "new3").await.unwrap();
+        assert_eq!(
+            storage.read_updates(meet_id).await.unwrap(),
+            vec!["new1", "new2", "new3"]
+        );
+
+        storage.replace_updates(meet_id, &[]).await.unwrap();
+        assert!(storage.read_updates(meet_id).await.unwrap().is_empty());
+    }
+
+    /// An update line numbered `seq`
+    pub(crate) fn numbered(seq: u64) -> String {
+        format!(r#"{{"serverSeqNum":{seq}}}"#)
+    }
+
+    pub(crate) async fn check_read_updates_from(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        for n in 1..=600 {
+            storage.append_update(meet_id, &numbered(n)).await.unwrap();
+        }
+
+        let page = storage.read_updates_from(meet_id, 299, 3).await.unwrap();
+        assert_eq!(page, vec![numbered(299), numbered(300), numbered(301)]);
+        assert_eq!(
+            storage.read_updates_from(meet_id, 598, 10).await.unwrap(),
+            vec![numbered(598), numbered(599), numbered(600)]
+        );
+        // Sequence 0 reads from the start
+        assert_eq!(
+            storage.read_updates_from(meet_id, 0, 1).await.unwrap(),
+            vec![numbered(1)]
+        );
+        assert!(storage
+            .read_updates_from(meet_id, 601, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Pages follow a replaced log, numbered by the records, not their
+        // place in it
+        let lines: Vec<String> = [1, 2, 4, 5].into_iter().map(numbered).collect();
+        storage.replace_updates(meet_id, &lines).await.unwrap();
+        assert_eq!(
+            storage.read_updates_from(meet_id, 2, 2).await.unwrap(),
+            vec![numbered(2), numbered(4)]
+        );
+        assert_eq!(
+            storage.read_updates_from(meet_id, 3, 10).await.unwrap(),
+            vec![numbered(4), numbered(5)]
+        );
+
+        // Lines without a number are left out
+        storage.append_update(meet_id, "unnumbered").await.unwrap();
+        storage.append_update(meet_id, &numbered(6)).await.unwrap();
+        assert_eq!(
+            storage.read_updates_from(meet_id, 5, 10).await.unwrap(),
+            vec![numbered(5), numbered(6)]
+        );
+    }
+
+    pub(crate) async fn check_purge_meet(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        assert_eq!(storage.meet_activity(meet_id).await.unwrap(), None);
+
+        assert!(storage.reserve_meet_id(meet_id).await.unwrap());
+        storage.append_update(meet_id, "update1").await.unwrap();
+        storage.store_meet_info(meet_id, "hash", &[]).await.unwrap();
+        storage
+            .store_meet_csv(meet_id, "csv", "lifter@example.com")
+            .await
+            .unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert!(activity.last_change.is_some());
+        assert!(activity.published_at.is_some());
+        assert_eq!(activity.finished_at, None);
+        assert_eq!(activity.purged_at, None);
+
+        // Only finished meets can be purged
+        assert!(matches!(
+            storage.purge_meet(meet_id).await,
+            Err(AppError::MeetNotFound)
+        ));
+
+        storage.purge_meet_csv(meet_id).await.unwrap();
+        assert_eq!(storage.get_meet_csv(meet_id).await.unwrap(), None);
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert_eq!(activity.published_at, None);
+
+        storage.archive_meet(meet_id).await.unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert!(activity.finished_at.is_some());
+
+        storage.purge_meet(meet_id).await.unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert!(activity.purged_at.is_some());
+
+        // The ID stays taken
+        assert_eq!(
+            storage.list_meets().await.unwrap(),
+            vec![MeetRecord {
+                meet_id: meet_id.to_string(),
+                status: MeetStatus::Finished,
+            }]
+        );
+        assert!(!storage.reserve_meet_id(meet_id).await.unwrap());
+    }
+
+    pub(crate) async fn check_rewrite_finished_meet(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        let data = MeetData {
+            updates: vec!["update2".to_string()],
+            info: None,
+            csv: Some(("csv2".to_string(), "director@example.com".to_string())),
+        };
+
+        // Current meets are not served
+        storage.append_update(meet_id, "update1").await.unwrap();
+        assert!(storage.read_finished_meet(meet_id).await.unwrap().is_none());
+        assert!(matches!(
+            storage.rewrite_finished_meet(meet_id, &data).await,
+            Err(AppError::MeetNotFound)
+        ));
+
+        storage.store_meet_info(meet_id, "hash", &[]).await.unwrap();
+        storage
+            .store_meet_csv(meet_id, "csv", "lifter@example.com")
+            .await
+            .unwrap();
+        storage.archive_meet(meet_id).await.unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap();
+
+        // Meet info was not given, so it is kept
+        storage.rewrite_finished_meet(meet_id, &data).await.unwrap();
+        let stored = storage.read_finished_meet(meet_id).await.unwrap().unwrap();
+        assert_eq!(stored.updates, data.updates);
+        assert_eq!(stored.info.unwrap().password_hash, "hash");
+        assert_eq!(stored.csv, data.csv);
+        assert_eq!(storage.meet_activity(meet_id).await.unwrap(), activity);
+    }
+
+    pub(crate) async fn check_replace_meet(storage: &dyn Storage) {
+        let meet_id = "test-meet";
+        let data = MeetData {
+            updates: vec![numbered(1), numbered(2)],
+            info: Some(MeetInfo {
+                password_hash: "hash2".to_string(),
+                endpoints: Vec::new(),
+            }),
+            csv: None,
+        };
+
+        // The meet must be reserved
+        assert!(matches!(
+            storage.replace_meet(meet_id, &data).await,
+            Err(AppError::MeetNotFound)
+        ));
+
+        storage.append_update(meet_id, "update1").await.unwrap();
+        storage.store_meet_info(meet_id, "hash", &[]).await.unwrap();
+        storage
+            .store_meet_csv(meet_id, "csv", "lifter@example.com")
+            .await
+            .unwrap();
+
+        // Everything is replaced, and the CSV the new data lacks is gone
+        storage.replace_meet(meet_id, &data).await.unwrap();
+        assert_eq!(storage.read_updates(meet_id).await.unwrap(), data.updates);
+        assert_eq!(
+            storage.read_updates_from(meet_id, 2, 10).await.unwrap(),
+            vec![numbered(2)]
+        );
+        assert_eq!(
+            storage.get_meet_info(meet_id).await.unwrap().password_hash,
+            "hash2"
+        );
+        assert_eq!(storage.get_meet_csv(meet_id).await.unwrap(), None);
+        assert!(storage
+            .meet_activity(meet_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .published_at
+            .is_none());
+
+        // Finished meets are not replaced
+        storage.archive_meet(meet_id).await.unwrap();
+        assert!(matches!(
+            storage.replace_meet(meet_id, &data).await,
+            Err(AppError::MeetNotFound)
+        ));
+    }
+
+    pub(crate) async fn check_read_updates_nonexistent_meet(storage: &dyn Storage) {
+        let meet_id = "nonexistent-meet";
+
+        let updates = storage.read_updates(meet_id).await.unwrap();
+        assert!(updates.is_empty());
+        assert!(storage
+            .read_updates_from(meet_id, 1, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     fn setup() -> (FlatFileStorage, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
@@ -379,6 +1527,23 @@ mod tests {
         assert!(path.exists());
     }
 
+    #[tokio::test]
+    async fn test_interrupted_archive_leaves_meet_current() {
+        let (storage, _temp_dir) = setup();
+        let meet_id = "test-meet";
+        storage.append_update(meet_id, "test").await.unwrap();
+
+        // The marker was written but the directory never moved
+        let current = storage.root.join("current-meets").join(meet_id);
+        fs::write(current.join(FINISHED_AT_FILE), Utc::now().to_rfc3339()).unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert_eq!(activity.finished_at, None);
+
+        storage.archive_meet(meet_id).await.unwrap();
+        let activity = storage.meet_activity(meet_id).await.unwrap().unwrap();
+        assert!(activity.finished_at.is_some());
+    }
+
     #[tokio::test]
     async fn test_store_csv_data() {
         let (storage, _temp_dir) = setup();
@@ -414,4 +1579,294 @@ mod tests {
         let updates = storage.read_updates(meet_id).await.unwrap();
         assert!(updates.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_shared_checks() {
+        let (storage, _temp_dir) = setup();
+        check_append_read_updates(&storage).await;
+        let (storage, _temp_dir) = setup();
+        check_store_get_meet_info(&storage).await;
+        let (storage, _temp_dir) = setup();
+        check_archive_meet(&storage).await;
+        let (storage, _temp_dir) = setup();
+        check_store_csv_data(&storage).await;
+        let (storage, _temp_dir) = setup();
+        check_read_updates_nonexistent_meet(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_reserve_meet_id() {
+        let (storage, _temp_dir) = setup();
+        check_reserve_meet_id(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_meets() {
+        let (storage, _temp_dir) = setup();
+        check_list_meets(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_updates() {
+        let (storage, _temp_dir) = setup();
+        check_replace_updates(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_from() {
+        let (storage, _temp_dir) = setup();
+        check_read_updates_from(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_purge_meet() {
+        let (storage, temp_dir) = setup();
+        check_purge_meet(&storage).await;
+
+        // Only the marker is left on disk
+        let dir = temp_dir.path().join("finished-meets").join("test-meet");
+        let names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![PURGED_AT_FILE]);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_finished_meet() {
+        let (storage, _temp_dir) = setup();
+        check_rewrite_finished_meet(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_meet() {
+        let (storage, temp_dir) = setup();
+        check_replace_meet(&storage).await;
+
+        // Nothing is left behind in the staging directory
+        let mut staged = tokio_fs::read_dir(temp_dir.path().join(STAGING_DIR))
+            .await
+            .unwrap();
+        assert!(staged.next_entry().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_append_durability_policies() {
+        for durability in [Durability::Always, Durability::Group, Durability::Buffered] {
+            let (storage, _temp_dir) = setup();
+            let storage = storage.with_durability(durability, Duration::from_millis(5));
+            check_append_read_updates(&storage).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_group_commit_appends() {
+        let (storage, _temp_dir) = setup();
+        let storage = storage.with_durability(Durability::Group, Duration::from_millis(10));
+
+        let appends = (0..10).map(|i| {
+            let storage = storage.clone();
+            tokio::spawn(async move {
+                storage
+                    .append_update("test-meet", &format!("update{}", i))
+                    .await
+            })
+        });
+        for append in appends.collect::<Vec<_>>() {
+            append.await.unwrap().unwrap();
+        }
+
+        let mut updates = storage.read_updates("test-meet").await.unwrap();
+        updates.sort();
+        assert_eq!(updates.len(), 10);
+        assert!(updates.iter().all(|update| update.starts_with("update")));
+    }
+
+    #[tokio::test]
+    async fn test_store_meet_info_replaces_file() {
+        let (storage, _temp_dir) = setup();
+        storage
+            .store_meet_info("test-meet", "old_hash", &[])
+            .await
+            .unwrap();
+        storage
+            .store_meet_info("test-meet", "new_hash", &[])
+            .await
+            .unwrap();
+
+        let info = storage.get_meet_info("test-meet").await.unwrap();
+        assert_eq!(info.password_hash, "new_hash");
+
+        // No temporary files are left in the meet directory
+        let dir = storage.root.join("current-meets").join("test-meet");
+        let names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["meet-info.json"]);
+    }
+
+    #[tokio::test]
+    async fn test_torn_tail_is_left_to_the_writer() {
+        let (storage, _temp_dir) = setup();
+        storage.append_update("test-meet", "update1").await.unwrap();
+        let path = storage.updates_path("test-meet");
+        let intact_len = fs::metadata(&path).unwrap().len();
+
+        // A crash in the middle of the second append
+        let record = update_log::encode_record("update2");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.extend_from_slice(&record.as_bytes()[..10]);
+        fs::write(&path, bytes).unwrap();
+
+        // Readers skip the torn record without touching the file
+        assert_eq!(
+            storage.read_updates("test-meet").await.unwrap(),
+            vec!["update1"]
+        );
+        assert_eq!(fs::metadata(&path).unwrap().len(), intact_len + 10);
+
+        // The next append drops it
+        storage.append_update("test-meet", "update3").await.unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().len(),
+            intact_len + update_log::encode_record("update3").len() as u64
+        );
+        assert_eq!(
+            storage.read_updates("test-meet").await.unwrap(),
+            vec!["update1", "update3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_after_torn_tail() {
+        let (storage, _temp_dir) = setup();
+        storage.append_update("test-meet", "update1").await.unwrap();
+        let path = storage.updates_path("test-meet");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.extend_from_slice(b"0000");
+        fs::write(&path, bytes).unwrap();
+
+        storage.append_update("test-meet", "update2").await.unwrap();
+        assert_eq!(
+            storage.read_updates("test-meet").await.unwrap(),
+            vec!["update1", "update2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corruption_is_reported_and_repaired() {
+        let (storage, _temp_dir) = setup();
+        for update in ["update1", "update2", "update3"] {
+            storage.append_update("test-meet", update).await.unwrap();
+        }
+        let path = storage.updates_path("test-meet");
+        let offset = update_log::encode_record("update1").len();
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[offset + 20] ^= 0x20;
+        fs::write(&path, bytes).unwrap();
+
+        match storage.read_updates("test-meet").await {
+            Err(AppError::CorruptLog {
+                meet_id,
+                offset: at,
+            }) => {
+                assert_eq!(meet_id, "test-meet");
+                assert_eq!(at, offset as u64);
+            },
+            other => panic!("Expected CorruptLog error, got {other:?}"),
+        }
+        assert_eq!(
+            storage
+                .verify_updates("test-meet")
+                .await
+                .unwrap()
+                .corrupt_at,
+            Some(offset as u64)
+        );
+
+        let salvage = storage.repair_updates("test-meet").await.unwrap();
+        assert_eq!(salvage.dropped.len(), 1);
+        assert_eq!(
+            storage.read_updates("test-meet").await.unwrap(),
+            vec!["update1", "update3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_unframed_log() {
+        let (storage, _temp_dir) = setup();
+        let dir = storage.root.join("current-meets").join("test-meet");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("updates.log"), "{\"a\":1}\n{\"b\":2}\n").unwrap();
+
+        storage
+            .append_update("test-meet", "{\"c\":3}")
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.read_updates("test-meet").await.unwrap(),
+            vec!["{\"a\":1}", "{\"b\":2}", "{\"c\":3}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = StorageSettings {
+            path: temp_dir.path().to_path_buf(),
+            backend: StorageBackend::Flat,
+            durability: Durability::Group,
+            group_commit_ms: 5,
+            encryption: Default::default(),
+        };
+        let storage = open_storage(&settings).await.unwrap();
+        check_reserve_meet_id(&storage).await;
+        assert!(temp_dir.path().join("finished-meets/111-222-333").exists());
+    }
+
+    #[tokio::test]
+    async fn test_open_storage_encrypts_existing_meets() {
+        let temp_dir = TempDir::new().unwrap();
+        let plain = FlatFileStorage::new(temp_dir.path()).unwrap();
+        plain.reserve_meet_id("111-222-333").await.unwrap();
+        plain
+            .append_update("111-222-333", r#"{"name":"Jane"}"#)
+            .await
+            .unwrap();
+
+        let settings = StorageSettings {
+            path: temp_dir.path().to_path_buf(),
+            backend: StorageBackend::Flat,
+            durability: Durability::Always,
+            group_commit_ms: 10,
+            encryption: crate::config::EncryptionSettings {
+                enabled: true,
+                ..Default::default()
+            },
+        };
+        // Opening to read leaves the meet as it is
+        let storage = open_storage(&settings).await.unwrap();
+        assert_eq!(
+            storage.read_updates("111-222-333").await.unwrap(),
+            vec![r#"{"name":"Jane"}"#]
+        );
+        drop(storage);
+        let log = fs::read_to_string(plain.updates_path("111-222-333")).unwrap();
+        assert!(log.contains("Jane"));
+
+        let (storage, migrated) = open_storage_and_migrate(&settings).await.unwrap();
+        assert_eq!(migrated, 1);
+        let log = fs::read_to_string(plain.updates_path("111-222-333")).unwrap();
+        assert!(!log.contains("Jane"));
+        assert_eq!(
+            storage.read_updates("111-222-333").await.unwrap(),
+            vec![r#"{"name":"Jane"}"#]
+        );
+        assert!(temp_dir
+            .path()
+            .join("keys")
+            .join(MASTER_KEYRING_FILE)
+            .exists());
+    }
 }
@@ -0,0 +1,414 @@
+// crates/backend-lib/src/storage/durable.rs
+
+/** Durable file writes for `FlatFileStorage`.
+Whole files are replaced by writing a temporary file next to the target,
+syncing it, renaming it over the target and then syncing the directory, so a
+crash leaves either the old or the new contents and never a torn file.
+
+Appends are synced according to the configured `Durability`. With group
+commit, each append announces itself before writing and then joins the
+pending batch with a handle to the file it wrote. The batch is synced as soon
+as no other append is still being written, or once the group interval has
+passed, and a batch that fills up while a sync runs is synced right after it.
+A lone append is therefore synced at once, while concurrent appends to any
+meets share one round of syncs. The handles are kept rather than the paths,
+so a log replaced or moved in the meantime still has the written data
+synced. */
+use crate::config::Durability;
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::{
+    fs as tokio_fs,
+    io::AsyncWriteExt,
+    sync::{watch, Notify},
+    time::Instant,
+};
+
+/// Keeps temporary file names unique within the process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Sync a directory so that entries created, renamed or removed in it survive
+/// a crash. Only Unix allows opening a directory for this.
+pub(super) async fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    tokio_fs::File::open(dir).await?.sync_all().await?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Replace the contents of `path` atomically and durably
+pub(crate) async fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Not a file path: {}", path.display()),
+        ));
+    };
+    let temp_path = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let written = async {
+        let mut file = tokio_fs::File::create(&temp_path).await?;
+        file.write_all(contents).await?;
+        file.sync_all().await?;
+        tokio_fs::rename(&temp_path, path).await
+    }
+    .await;
+    if let Err(err) = written {
+        let _ = tokio_fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+
+    sync_dir(dir).await
+}
+
+/// Appends waiting for the same group sync
+struct Batch {
+    /// Path and a handle of every file written, one per append
+    files: Vec<(PathBuf, tokio_fs::File)>,
+    /// Set once the batch has been synced, to the error kind if a sync failed
+    done: watch::Sender<Option<Result<(), ErrorKind>>>,
+}
+
+/// Group commit bookkeeping
+#[derive(Default)]
+struct Group {
+    /// Appends announced with `begin` that have not joined a batch yet
+    writing: usize,
+    /// The batch the next sync covers
+    pending: Option<Batch>,
+    /// Whether a task is syncing batches
+    syncing: bool,
+    /// Batches synced so far
+    #[cfg(test)]
+    synced: usize,
+}
+
+/// Syncs appended files according to a `Durability` policy
+pub(super) struct AppendSync {
+    durability: Durability,
+    group_interval: Duration,
+    group: Mutex<Group>,
+    /// Notified whenever an announced append joins a batch or gives up
+    writer_done: Notify,
+}
+
+/// An append announced to `AppendSync`, to be synced once written
+pub(super) struct PendingAppend {
+    sync: Arc<AppendSync>,
+    /// Whether this append still counts as being written
+    writing: bool,
+}
+
+impl AppendSync {
+    pub(super) fn new(durability: Durability, group_interval: Duration) -> Self {
+        Self {
+            durability,
+            group_interval,
+            group: Mutex::new(Group::default()),
+            writer_done: Notify::new(),
+        }
+    }
+
+    pub(super) fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    /// Announce an append before writing it, so a group sync starting in the
+    /// meantime waits for it to join
+    pub(super) fn begin(self: &Arc<Self>) -> PendingAppend {
+        let writing = self.durability == Durability::Group;
+        if writing {
+            self.lock_group().writing += 1;
+        }
+        PendingAppend {
+            sync: self.clone(),
+            writing,
+        }
+    }
+
+    fn lock_group(&self) -> std::sync::MutexGuard<'_, Group> {
+        self.group.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add the written file to the pending batch, start syncing if nothing
+    /// is, and wait for the batch to be synced
+    async fn join_batch(
+        self: &Arc<Self>,
+        path: &Path,
+        handle: tokio_fs::File,
+        writing: bool,
+    ) -> io::Result<()> {
+        let mut done = {
+            let mut group = self.lock_group();
+            if writing {
+                group.writing -= 1;
+            }
+            let batch = group.pending.get_or_insert_with(|| Batch {
+                files: Vec::new(),
+                done: watch::channel(None).0,
+            });
+            batch.files.push((path.to_path_buf(), handle));
+            let done = batch.done.subscribe();
+            if !group.syncing {
+                group.syncing = true;
+                tokio::spawn(self.clone().sync_batches());
+            }
+            done
+        };
+        self.writer_done.notify_waiters();
+
+        let result = *done
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| io::Error::other("Group commit was dropped"))?;
+        match result {
+            Some(Err(kind)) => Err(io::Error::new(kind, "Group commit failed")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sync pending batches until none is left. Each waits for the appends
+    /// still being written to join it, at most the group interval.
+    async fn sync_batches(self: Arc<Self>) {
+        loop {
+            let deadline = Instant::now() + self.group_interval;
+            loop {
+                let notified = self.writer_done.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.lock_group().writing == 0 {
+                    break;
+                }
+                if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                    break;
+                }
+            }
+
+            let batch = {
+                let mut group = self.lock_group();
+                let batch = group.pending.take();
+                if batch.is_none() {
+                    group.syncing = false;
+                }
+                #[cfg(test)]
+                if batch.is_some() {
+                    group.synced += 1;
+                }
+                batch
+            };
+            let Some(batch) = batch else {
+                return;
+            };
+
+            let mut result = Ok(());
+            for (path, file) in &batch.files {
+                if let Err(err) = file.sync_data().await {
+                    tracing::error!("Error syncing {}: {}", path.display(), err);
+                    result = Err(err.kind());
+                }
+            }
+            batch.done.send_replace(Some(result));
+        }
+    }
+}
+
+impl PendingAppend {
+    /// Make the append to `file` durable as the policy requires. Returns once
+    /// the update may be acknowledged.
+    pub(super) async fn sync(mut self, path: &Path, file: &tokio_fs::File) -> io::Result<()> {
+        match self.sync.durability {
+            Durability::Always => file.sync_data().await,
+            Durability::Group => {
+                let handle = file.try_clone().await?;
+                let writing = std::mem::take(&mut self.writing);
+                self.sync.join_batch(path, handle, writing).await
+            },
+            Durability::Buffered => Ok(()),
+        }
+    }
+}
+
+impl Drop for PendingAppend {
+    /// An append that failed before joining a batch no longer holds up syncs
+    fn drop(&mut self) {
+        if self.writing {
+            self.sync.lock_group().writing -= 1;
+            self.sync.writer_done.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_write_atomic_replaces_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("meet-info.json");
+
+        write_atomic(&path, b"old").await.unwrap();
+        write_atomic(&path, b"new").await.unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // Nothing but the target is left behind
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic_missing_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing").join("meet.csv");
+
+        assert!(write_atomic(&path, b"data").await.is_err());
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_acknowledges_all_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let sync = Arc::new(AppendSync::new(
+            Durability::Group,
+            Duration::from_millis(20),
+        ));
+
+        let mut tasks = Vec::new();
+        for i in 0..5 {
+            let sync = sync.clone();
+            let path = temp_dir.path().join(format!("updates-{}.log", i % 2));
+            tasks.push(tokio::spawn(async move {
+                let file = tokio_fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .await
+                    .unwrap();
+                sync.begin().sync(&path, &file).await
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        // The batch is finished; the next append starts a new one
+        assert!(sync.lock_group().pending.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_shares_one_sync() {
+        let temp_dir = TempDir::new().unwrap();
+        let sync = Arc::new(AppendSync::new(Durability::Group, Duration::from_secs(10)));
+        let open = |name: &str| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(temp_dir.path().join(name))
+                .map(tokio_fs::File::from_std)
+        };
+        let (first_file, second_file) = (open("a.log").unwrap(), open("b.log").unwrap());
+
+        // Both appends are being written when the first one is done
+        let first = sync.begin();
+        let second = sync.begin();
+        let first_path = temp_dir.path().join("a.log");
+        let first_done = first.sync(&first_path, &first_file);
+        tokio::pin!(first_done);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut first_done)
+                .await
+                .is_err(),
+            "The sync should wait for the append still being written"
+        );
+
+        let second_path = temp_dir.path().join("b.log");
+        let (first_result, second_result) =
+            tokio::join!(first_done, second.sync(&second_path, &second_file));
+        first_result.unwrap();
+        second_result.unwrap();
+        assert_eq!(sync.lock_group().synced, 1);
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_syncs_a_lone_append_at_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        let file = tokio_fs::File::create(&path).await.unwrap();
+        // Far longer than the test may take
+        let sync = Arc::new(AppendSync::new(Durability::Group, Duration::from_secs(60)));
+
+        for _ in 0..3 {
+            tokio::time::timeout(Duration::from_secs(5), sync.begin().sync(&path, &file))
+                .await
+                .expect("A lone append should not wait for the interval")
+                .unwrap();
+        }
+        assert_eq!(sync.lock_group().synced, 3);
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_stops_waiting_for_failed_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        let file = tokio_fs::File::create(&path).await.unwrap();
+        let sync = Arc::new(AppendSync::new(Durability::Group, Duration::from_secs(60)));
+
+        // This append fails before it is written and never joins a batch
+        let failed = sync.begin();
+        let done = sync.begin().sync(&path, &file);
+        tokio::pin!(done);
+        assert!(tokio::time::timeout(Duration::from_millis(50), &mut done)
+            .await
+            .is_err());
+        drop(failed);
+        tokio::time::timeout(Duration::from_secs(5), done)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_group_commit_reports_failures() {
+        // Character devices cannot be synced
+        let path = Path::new("/dev/null");
+        let file = tokio_fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .unwrap();
+
+        let sync = Arc::new(AppendSync::new(Durability::Group, Duration::from_millis(1)));
+        let err = sync.begin().sync(path, &file).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_group_commit_syncs_the_written_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        let mut file = tokio_fs::File::create(&path).await.unwrap();
+        file.write_all(b"record").await.unwrap();
+
+        // The log is replaced before the batch is synced
+        fs::rename(&path, temp_dir.path().join("updates.log.old")).unwrap();
+
+        let sync = Arc::new(AppendSync::new(Durability::Group, Duration::from_millis(1)));
+        sync.begin().sync(&path, &file).await.unwrap();
+    }
+}
@@ -0,0 +1,902 @@
+// crates/backend-lib/src/storage/encrypted.rs
+
+/** Encryption at rest for meet data.
+`EncryptedStorage` wraps another `Storage` and encrypts every update, the
+meet password hash, endpoint names, the published CSV and the return email
+before they reach it. Meet IDs and the meet status stay readable so meets can
+still be listed and archived.
+
+Each meet has its own random data key. Data keys are wrapped with the server
+master key (a `Keyring`, so master keys can be rotated) and kept as
+`keys/{meet_id}.key`; deleting that file makes the meet unreadable, which
+`purge_meet` does after purging the meet from the inner storage. Values
+are encrypted with AES-256-GCM and bound to their meet and field, and are
+stored as `enc1:` followed by the base64-encoded nonce and ciphertext. An
+update is stored as a small JSON object holding that value under `enc`
+next to its plaintext `serverSeqNum`, so the inner storage can still page
+updates by number (see `update_seq`).
+
+Values without the `enc1:` prefix were written before encryption was turned
+on. `migrate_all` re-encrypts them in place, in current and finished meets
+alike, and then leaves a `migrated` marker in the keys directory. Until then
+plaintext is read as it is, but only from meets that have no data key yet;
+once the marker exists, or a meet has a key, a plaintext value is refused, so
+whoever can write to the data directory cannot swap ciphertext for a value of
+their choosing. */
+use super::{
+    durable::{sync_dir, write_atomic},
+    update_seq, MeetActivity, MeetData, Storage,
+};
+use crate::auth::Keyring;
+use crate::error::AppError;
+use crate::meet_registry::{MeetRecord, MeetStatus};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use openlifter_common::{EndpointPriority, MeetInfo};
+use rand::{rngs::OsRng, TryRngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{fs as tokio_fs, sync::Mutex};
+
+/// File holding the master keyring inside the keys directory
+pub const MASTER_KEYRING_FILE: &str = "master_keyring.json";
+
+/// Marker left in the keys directory once `migrate_all` has completed
+const MIGRATED_FILE: &str = "migrated";
+
+/// Marks an encrypted value
+const PREFIX: &str = "enc1:";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Parts of a meet that are encrypted, used to bind each value to its role
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Update,
+    PasswordHash,
+    LocationName,
+    OplCsv,
+    ReturnEmail,
+}
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::Update => "update",
+            Field::PasswordHash => "password_hash",
+            Field::LocationName => "location_name",
+            Field::OplCsv => "opl_csv",
+            Field::ReturnEmail => "return_email",
+        }
+    }
+}
+
+fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// An encrypted update as stored
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedUpdate {
+    /// Server sequence number of the update, left readable for paging
+    #[serde(
+        rename = "serverSeqNum",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    server_seq_num: Option<u64>,
+    /// The encrypted update
+    enc: String,
+}
+
+/// Whether a stored update is sealed; anything else was written before
+/// encryption was turned on
+fn is_sealed(record: &str) -> bool {
+    serde_json::from_str::<SealedUpdate>(record).is_ok_and(|sealed| is_encrypted(&sealed.enc))
+}
+
+fn crypto_error(err: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("Meet data encryption error: {err}"))
+}
+
+/// Storage wrapper that encrypts meet data with per-meet keys
+#[derive(Clone)]
+pub struct EncryptedStorage<S> {
+    inner: S,
+    master: Arc<Keyring>,
+    keys_dir: PathBuf,
+    /// Unwrapped data keys by meet ID
+    data_keys: Arc<Mutex<HashMap<String, Arc<Aes256Gcm>>>>,
+    /// Whether `migrate_all` has completed, after which plaintext is refused
+    migrated: Arc<AtomicBool>,
+}
+
+impl<S: Storage> EncryptedStorage<S> {
+    /// Wrap `inner`, keeping wrapped data keys in `keys_dir`
+    /// # Arguments
+    /// * `inner` - Storage that receives the encrypted data
+    /// * `master` - Keyring used to wrap data keys
+    /// * `keys_dir` - Directory for the wrapped data keys
+    /// # Returns
+    /// * `Ok(EncryptedStorage)` - New storage instance
+    /// * `Err(anyhow::Error)` if the keys directory could not be created
+    pub fn new<P: AsRef<Path>>(inner: S, master: Keyring, keys_dir: P) -> anyhow::Result<Self> {
+        let keys_dir = keys_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&keys_dir)?;
+        let migrated = keys_dir.join(MIGRATED_FILE).exists();
+        Ok(Self {
+            inner,
+            master: Arc::new(master),
+            keys_dir,
+            data_keys: Arc::new(Mutex::new(HashMap::new())),
+            migrated: Arc::new(AtomicBool::new(migrated)),
+        })
+    }
+
+    /// The wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Data key of a meet, loaded from its key file. With `create`, a missing
+    /// key is generated and stored first.
+    async fn data_key(
+        &self,
+        meet_id: &str,
+        create: bool,
+    ) -> Result<Option<Arc<Aes256Gcm>>, AppError> {
+        // Held across the file access so a key is only ever created once
+        let mut data_keys = self.data_keys.lock().await;
+        if let Some(cipher) = data_keys.get(meet_id) {
+            return Ok(Some(cipher.clone()));
+        }
+
+        let path = self.keys_dir.join(format!("{meet_id}.key"));
+        let key = if tokio_fs::try_exists(&path).await? {
+            let wrapped = BASE64
+                .decode(tokio_fs::read_to_string(&path).await?.trim())
+                .map_err(crypto_error)?;
+            let (_, key) = self.master.decrypt(&wrapped).map_err(crypto_error)?;
+            key
+        } else if create {
+            let mut key = vec![0u8; KEY_LEN];
+            OsRng.try_fill_bytes(&mut key).map_err(crypto_error)?;
+            let wrapped = self.master.encrypt(&key).map_err(crypto_error)?;
+            write_atomic(&path, BASE64.encode(wrapped).as_bytes()).await?;
+            key
+        } else {
+            return Ok(None);
+        };
+
+        let cipher = Arc::new(Aes256Gcm::new_from_slice(&key).map_err(crypto_error)?);
+        data_keys.insert(meet_id.to_string(), cipher.clone());
+        Ok(Some(cipher))
+    }
+
+    async fn encrypt(&self, meet_id: &str, field: Field, value: &str) -> Result<String, AppError> {
+        let cipher = self
+            .data_key(meet_id, true)
+            .await?
+            .ok_or_else(|| crypto_error("data key was not created"))?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.try_fill_bytes(&mut nonce).map_err(crypto_error)?;
+        let aad = format!("{meet_id}/{}", field.name());
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(crypto_error)?;
+
+        let mut data = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(format!("{PREFIX}{}", BASE64.encode(data)))
+    }
+
+    /// Whether plaintext read from a meet can only have been written before
+    /// encryption was turned on
+    async fn reads_plaintext(&self, meet_id: &str) -> Result<bool, AppError> {
+        Ok(
+            !self.migrated.load(Ordering::Acquire)
+                && self.data_key(meet_id, false).await?.is_none(),
+        )
+    }
+
+    /// Decrypt a value. Plaintext is returned as it is while `migrating`, or
+    /// while the meet can still hold data from before encryption was enabled.
+    async fn decrypt(
+        &self,
+        meet_id: &str,
+        field: Field,
+        value: &str,
+        migrating: bool,
+    ) -> Result<String, AppError> {
+        let Some(encoded) = value.strip_prefix(PREFIX) else {
+            if migrating || self.reads_plaintext(meet_id).await? {
+                return Ok(value.to_string());
+            }
+            return Err(crypto_error(format!(
+                "unencrypted {} in meet {meet_id}",
+                field.name()
+            )));
+        };
+        let cipher = self
+            .data_key(meet_id, false)
+            .await?
+            .ok_or_else(|| crypto_error(format!("no data key for meet {meet_id}")))?;
+
+        let data = BASE64.decode(encoded).map_err(crypto_error)?;
+        if data.len() < NONCE_LEN {
+            return Err(crypto_error("encrypted value is too short"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let aad = format!("{meet_id}/{}", field.name());
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(crypto_error)?;
+        String::from_utf8(plaintext).map_err(crypto_error)
+    }
+
+    async fn seal_update(&self, meet_id: &str, update: &str) -> Result<String, AppError> {
+        let sealed = SealedUpdate {
+            server_seq_num: update_seq(update),
+            enc: self.encrypt(meet_id, Field::Update, update).await?,
+        };
+        Ok(serde_json::to_string(&sealed)?)
+    }
+
+    async fn open_update(
+        &self,
+        meet_id: &str,
+        record: &str,
+        migrating: bool,
+    ) -> Result<String, AppError> {
+        if record.is_empty() {
+            return Ok(String::new());
+        }
+        match serde_json::from_str::<SealedUpdate>(record) {
+            Ok(sealed) => {
+                self.decrypt(meet_id, Field::Update, &sealed.enc, migrating)
+                    .await
+            },
+            Err(_) => {
+                self.decrypt(meet_id, Field::Update, record, migrating)
+                    .await
+            },
+        }
+    }
+
+    async fn encrypt_meet_info(
+        &self,
+        meet_id: &str,
+        info: &MeetInfo,
+    ) -> Result<MeetInfo, AppError> {
+        let mut endpoints = Vec::with_capacity(info.endpoints.len());
+        for endpoint in &info.endpoints {
+            endpoints.push(EndpointPriority {
+                location_name: self
+                    .encrypt(meet_id, Field::LocationName, &endpoint.location_name)
+                    .await?,
+                priority: endpoint.priority,
+            });
+        }
+        Ok(MeetInfo {
+            password_hash: self
+                .encrypt(meet_id, Field::PasswordHash, &info.password_hash)
+                .await?,
+            endpoints,
+        })
+    }
+
+    async fn decrypt_meet_info(
+        &self,
+        meet_id: &str,
+        info: MeetInfo,
+        migrating: bool,
+    ) -> Result<MeetInfo, AppError> {
+        let mut endpoints = Vec::with_capacity(info.endpoints.len());
+        for endpoint in info.endpoints {
+            endpoints.push(EndpointPriority {
+                location_name: self
+                    .decrypt(
+                        meet_id,
+                        Field::LocationName,
+                        &endpoint.location_name,
+                        migrating,
+                    )
+                    .await?,
+                priority: endpoint.priority,
+            });
+        }
+        Ok(MeetInfo {
+            password_hash: self
+                .decrypt(meet_id, Field::PasswordHash, &info.password_hash, migrating)
+                .await?,
+            endpoints,
+        })
+    }
+
+    /** Encrypt whatever a current meet still holds in plaintext.
+    Run this while nothing else writes to the meet, such as at startup: the
+    update log is rewritten as a whole.
+    # Returns
+    * `Ok(true)` if anything was rewritten
+    * `Err(AppError)` if the meet could not be read or rewritten */
+    pub async fn migrate_meet(&self, meet_id: &str) -> Result<bool, AppError> {
+        let mut migrated = false;
+
+        let updates = self.inner.read_updates(meet_id).await?;
+        if updates.iter().any(|update| !is_sealed(update)) {
+            let mut plaintext = Vec::with_capacity(updates.len());
+            for update in &updates {
+                plaintext.push(self.open_update(meet_id, update, true).await?);
+            }
+            self.replace_updates(meet_id, &plaintext).await?;
+            migrated = true;
+        }
+
+        match self.inner.get_meet_info(meet_id).await {
+            Ok(info)
+                if !is_encrypted(&info.password_hash)
+                    || info
+                        .endpoints
+                        .iter()
+                        .any(|endpoint| !is_encrypted(&endpoint.location_name)) =>
+            {
+                let info = self.decrypt_meet_info(meet_id, info, true).await?;
+                self.store_meet_info(meet_id, &info.password_hash, &info.endpoints)
+                    .await?;
+                migrated = true;
+            },
+            Ok(_) | Err(AppError::MeetNotFound) => {},
+            Err(err) => return Err(err),
+        }
+
+        if let Some((opl_csv, return_email)) = self.inner.get_meet_csv(meet_id).await? {
+            if !is_encrypted(&opl_csv) || !is_encrypted(&return_email) {
+                let opl_csv = self.decrypt(meet_id, Field::OplCsv, &opl_csv, true).await?;
+                let return_email = self
+                    .decrypt(meet_id, Field::ReturnEmail, &return_email, true)
+                    .await?;
+                self.store_meet_csv(meet_id, &opl_csv, &return_email)
+                    .await?;
+                migrated = true;
+            }
+        }
+
+        Ok(migrated)
+    }
+
+    /// Encrypt whatever a finished meet still holds in plaintext
+    /// # Returns
+    /// * `Ok(true)` if anything was rewritten
+    /// * `Err(AppError)` if the meet could not be read or rewritten
+    pub async fn migrate_finished_meet(&self, meet_id: &str) -> Result<bool, AppError> {
+        let Some(data) = self.inner.read_finished_meet(meet_id).await? else {
+            return Ok(false);
+        };
+        let plaintext = data.updates.iter().any(|update| !is_sealed(update))
+            || data.info.as_ref().is_some_and(|info| {
+                !is_encrypted(&info.password_hash)
+                    || info
+                        .endpoints
+                        .iter()
+                        .any(|endpoint| !is_encrypted(&endpoint.location_name))
+            })
+            || data.csv.as_ref().is_some_and(|(opl_csv, return_email)| {
+                !is_encrypted(opl_csv) || !is_encrypted(return_email)
+            });
+        if !plaintext {
+            return Ok(false);
+        }
+
+        let data = self.decrypt_meet_data(meet_id, data, true).await?;
+        self.rewrite_finished_meet(meet_id, &data).await?;
+        Ok(true)
+    }
+
+    /// Run `migrate_meet` or `migrate_finished_meet` on every meet, then
+    /// refuse plaintext from then on
+    /// # Returns
+    /// * `Ok(usize)` - Number of meets that were rewritten
+    /// * `Err(AppError)` if a meet could not be migrated
+    pub async fn migrate_all(&self) -> Result<usize, AppError> {
+        let mut migrated = 0;
+        for record in self.inner.list_meets().await? {
+            let rewritten = match record.status {
+                MeetStatus::Current => self.migrate_meet(&record.meet_id).await?,
+                MeetStatus::Finished => self.migrate_finished_meet(&record.meet_id).await?,
+            };
+            if rewritten {
+                migrated += 1;
+            }
+        }
+        if !self.migrated.load(Ordering::Acquire) {
+            write_atomic(&self.keys_dir.join(MIGRATED_FILE), b"").await?;
+            self.migrated.store(true, Ordering::Release);
+        }
+        Ok(migrated)
+    }
+
+    /// Encrypt every part of a meet's data
+    async fn encrypt_meet_data(
+        &self,
+        meet_id: &str,
+        data: &MeetData,
+    ) -> Result<MeetData, AppError> {
+        let mut updates = Vec::with_capacity(data.updates.len());
+        for update in &data.updates {
+            updates.push(self.seal_update(meet_id, update).await?);
+        }
+        let info = match &data.info {
+            Some(info) => Some(self.encrypt_meet_info(meet_id, info).await?),
+            None => None,
+        };
+        let csv = match &data.csv {
+            Some((opl_csv, return_email)) => Some((
+                self.encrypt(meet_id, Field::OplCsv, opl_csv).await?,
+                self.encrypt(meet_id, Field::ReturnEmail, return_email)
+                    .await?,
+            )),
+            None => None,
+        };
+        Ok(MeetData { updates, info, csv })
+    }
+
+    async fn decrypt_meet_data(
+        &self,
+        meet_id: &str,
+        data: MeetData,
+        migrating: bool,
+    ) -> Result<MeetData, AppError> {
+        let mut updates = Vec::with_capacity(data.updates.len());
+        for update in &data.updates {
+            updates.push(self.open_update(meet_id, update, migrating).await?);
+        }
+        let info = match data.info {
+            Some(info) => Some(self.decrypt_meet_info(meet_id, info, migrating).await?),
+            None => None,
+        };
+        let csv = match data.csv {
+            Some((opl_csv, return_email)) => Some((
+                self.decrypt(meet_id, Field::OplCsv, &opl_csv, migrating)
+                    .await?,
+                self.decrypt(meet_id, Field::ReturnEmail, &return_email, migrating)
+                    .await?,
+            )),
+            None => None,
+        };
+        Ok(MeetData { updates, info, csv })
+    }
+}
+
+#[async_trait]
+impl<S: Storage> Storage for EncryptedStorage<S> {
+    async fn append_update(&self, meet_id: &str, json_line: &str) -> Result<(), AppError> {
+        let record = self.seal_update(meet_id, json_line).await?;
+        self.inner.append_update(meet_id, &record).await
+    }
+
+    async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError> {
+        let mut updates = Vec::new();
+        for record in self.inner.read_updates(meet_id).await? {
+            let update = self.open_update(meet_id, &record, false).await?;
+            if !update.is_empty() {
+                updates.push(update);
+            }
+        }
+        Ok(updates)
+    }
+
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        let mut updates = Vec::new();
+        for record in self
+            .inner
+            .read_updates_from(meet_id, first_seq, limit)
+            .await?
+        {
+            let update = self.open_update(meet_id, &record, false).await?;
+            if !update.is_empty() {
+                updates.push(update);
+            }
+        }
+        Ok(updates)
+    }
+
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        let mut records = Vec::with_capacity(json_lines.len());
+        for line in json_lines {
+            records.push(self.seal_update(meet_id, line).await?);
+        }
+        self.inner.replace_updates(meet_id, &records).await
+    }
+
+    async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        self.inner.archive_meet(meet_id).await
+    }
+
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        self.inner.list_meets().await
+    }
+
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        self.inner.reserve_meet_id(meet_id).await
+    }
+
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        self.inner.release_meet_id(meet_id).await
+    }
+
+    async fn store_meet_info(
+        &self,
+        meet_id: &str,
+        password_hash: &str,
+        endpoints: &[EndpointPriority],
+    ) -> Result<(), AppError> {
+        let info = MeetInfo {
+            password_hash: password_hash.to_string(),
+            endpoints: endpoints.to_vec(),
+        };
+        let info = self.encrypt_meet_info(meet_id, &info).await?;
+        self.inner
+            .store_meet_info(meet_id, &info.password_hash, &info.endpoints)
+            .await
+    }
+
+    async fn get_meet_info(&self, meet_id: &str) -> Result<MeetInfo, AppError> {
+        let info = self.inner.get_meet_info(meet_id).await?;
+        self.decrypt_meet_info(meet_id, info, false).await
+    }
+
+    async fn store_meet_csv(
+        &self,
+        meet_id: &str,
+        opl_csv: &str,
+        return_email: &str,
+    ) -> Result<(), AppError> {
+        let opl_csv = self.encrypt(meet_id, Field::OplCsv, opl_csv).await?;
+        let return_email = self
+            .encrypt(meet_id, Field::ReturnEmail, return_email)
+            .await?;
+        self.inner
+            .store_meet_csv(meet_id, &opl_csv, &return_email)
+            .await
+    }
+
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        let Some((opl_csv, return_email)) = self.inner.get_meet_csv(meet_id).await? else {
+            return Ok(None);
+        };
+        Ok(Some((
+            self.decrypt(meet_id, Field::OplCsv, &opl_csv, false)
+                .await?,
+            self.decrypt(meet_id, Field::ReturnEmail, &return_email, false)
+                .await?,
+        )))
+    }
+
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        self.inner.meet_activity(meet_id).await
+    }
+
+    /// Purge the meet, then delete its data key so no copy of its data, such
+    /// as a backup, can be decrypted any more
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        self.inner.purge_meet(meet_id).await?;
+
+        let mut data_keys = self.data_keys.lock().await;
+        data_keys.remove(meet_id);
+        match tokio::fs::remove_file(self.keys_dir.join(format!("{meet_id}.key"))).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {},
+        }
+        sync_dir(&self.keys_dir).await?;
+        Ok(())
+    }
+
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        self.inner.purge_meet_csv(meet_id).await
+    }
+
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        match self.inner.read_finished_meet(meet_id).await? {
+            Some(data) => Ok(Some(self.decrypt_meet_data(meet_id, data, false).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let data = self.encrypt_meet_data(meet_id, data).await?;
+        self.inner.rewrite_finished_meet(meet_id, &data).await
+    }
+
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let data = self.encrypt_meet_data(meet_id, data).await?;
+        self.inner.replace_meet(meet_id, &data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::*;
+    use crate::storage::MemoryStorage;
+    use tempfile::TempDir;
+
+    fn setup() -> (EncryptedStorage<MemoryStorage>, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let keys_dir = temp_dir.path().join("keys");
+        let master =
+            Keyring::load_or_create_file(temp_dir.path().join(MASTER_KEYRING_FILE)).unwrap();
+        let storage = EncryptedStorage::new(MemoryStorage::new(), master, keys_dir).unwrap();
+        (storage, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_append_read_updates() {
+        let (storage, _temp_dir) = setup();
+        check_append_read_updates(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_get_meet_info() {
+        let (storage, _temp_dir) = setup();
+        check_store_get_meet_info(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_archive_meet() {
+        let (storage, _temp_dir) = setup();
+        check_archive_meet(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_csv_data() {
+        let (storage, _temp_dir) = setup();
+        check_store_csv_data(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_updates() {
+        let (storage, _temp_dir) = setup();
+        check_replace_updates(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_from() {
+        let (storage, _temp_dir) = setup();
+        check_read_updates_from(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_finished_meet() {
+        let (storage, _temp_dir) = setup();
+        check_rewrite_finished_meet(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_meet() {
+        let (storage, _temp_dir) = setup();
+        check_replace_meet(&storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_purge_meet_deletes_data_key() {
+        let (storage, temp_dir) = setup();
+        check_purge_meet(&storage).await;
+        assert!(!temp_dir.path().join("keys").join("test-meet.key").exists());
+    }
+
+    #[tokio::test]
+    async fn test_inner_storage_only_sees_ciphertext() {
+        let (storage, _temp_dir) = setup();
+        let endpoints = vec![EndpointPriority {
+            location_name: "Platform A".to_string(),
+            priority: 1,
+        }];
+        storage
+            .append_update("meet", r#"{"serverSeqNum":1,"lifter":"Jane"}"#)
+            .await
+            .unwrap();
+        storage
+            .store_meet_info("meet", "hash", &endpoints)
+            .await
+            .unwrap();
+        storage
+            .store_meet_csv("meet", "Name\nJane", "director@example.com")
+            .await
+            .unwrap();
+
+        let inner = storage.inner();
+        let update = &inner.read_updates("meet").await.unwrap()[0];
+        assert!(is_sealed(update) && !update.contains("Jane"));
+        // The number stays readable so the inner storage can page by it
+        assert_eq!(update_seq(update), Some(1));
+        let info = inner.get_meet_info("meet").await.unwrap();
+        assert!(is_encrypted(&info.password_hash));
+        assert!(is_encrypted(&info.endpoints[0].location_name));
+        assert_eq!(info.endpoints[0].priority, 1);
+        let (csv, email) = inner.get_meet_csv("meet").await.unwrap().unwrap();
+        assert!(is_encrypted(&csv) && is_encrypted(&email));
+    }
+
+    #[tokio::test]
+    async fn test_data_keys_survive_restart() {
+        let (storage, temp_dir) = setup();
+        storage.append_update("meet", "update1").await.unwrap();
+        assert!(temp_dir.path().join("keys/meet.key").exists());
+
+        let master =
+            Keyring::load_or_create_file(temp_dir.path().join(MASTER_KEYRING_FILE)).unwrap();
+        let reopened = EncryptedStorage::new(
+            storage.inner().clone(),
+            master,
+            temp_dir.path().join("keys"),
+        )
+        .unwrap();
+        assert_eq!(
+            reopened.read_updates("meet").await.unwrap(),
+            vec!["update1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_values_are_bound_to_their_meet() {
+        let (storage, _temp_dir) = setup();
+        storage.append_update("meet-a", "update1").await.unwrap();
+        storage.append_update("meet-b", "update2").await.unwrap();
+
+        // Copying a record into another meet's log does not decrypt
+        let record = storage.inner().read_updates("meet-a").await.unwrap()[0].clone();
+        storage
+            .inner()
+            .append_update("meet-b", &record)
+            .await
+            .unwrap();
+        assert!(storage.read_updates("meet-b").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_plaintext_meet() {
+        let (storage, _temp_dir) = setup();
+        let inner = storage.inner();
+        inner.reserve_meet_id("meet").await.unwrap();
+        inner.append_update("meet", "update1").await.unwrap();
+        inner.store_meet_info("meet", "hash", &[]).await.unwrap();
+        inner
+            .store_meet_csv("meet", "Name", "director@example.com")
+            .await
+            .unwrap();
+
+        // Plaintext is readable before migration
+        assert_eq!(storage.read_updates("meet").await.unwrap(), vec!["update1"]);
+
+        assert_eq!(storage.migrate_all().await.unwrap(), 1);
+        assert!(is_sealed(&inner.read_updates("meet").await.unwrap()[0]));
+        assert!(is_encrypted(
+            &inner.get_meet_info("meet").await.unwrap().password_hash
+        ));
+        assert_eq!(
+            storage.get_meet_csv("meet").await.unwrap(),
+            Some(("Name".to_string(), "director@example.com".to_string()))
+        );
+        assert_eq!(storage.read_updates("meet").await.unwrap(), vec!["update1"]);
+
+        // Nothing is left to migrate
+        assert_eq!(storage.migrate_all().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_is_refused_once_a_meet_has_a_key() {
+        let (storage, _temp_dir) = setup();
+        storage.append_update("meet", "update1").await.unwrap();
+        storage.store_meet_info("meet", "hash", &[]).await.unwrap();
+
+        // Values swapped for plaintext in the inner storage are not trusted
+        storage
+            .inner()
+            .append_update("meet", "injected")
+            .await
+            .unwrap();
+        assert!(storage.read_updates("meet").await.is_err());
+        storage
+            .inner()
+            .store_meet_info("meet", "injected", &[])
+            .await
+            .unwrap();
+        assert!(storage.get_meet_info("meet").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_is_refused_after_migration() {
+        let (storage, temp_dir) = setup();
+        assert_eq!(storage.migrate_all().await.unwrap(), 0);
+
+        storage.inner().reserve_meet_id("meet").await.unwrap();
+        storage
+            .inner()
+            .append_update("meet", "injected")
+            .await
+            .unwrap();
+        assert!(storage.read_updates("meet").await.is_err());
+
+        // The marker outlives the process
+        let master =
+            Keyring::load_or_create_file(temp_dir.path().join(MASTER_KEYRING_FILE)).unwrap();
+        let reopened = EncryptedStorage::new(
+            storage.inner().clone(),
+            master,
+            temp_dir.path().join("keys"),
+        )
+        .unwrap();
+        assert!(reopened.read_updates("meet").await.is_err());
+
+        // Migrating still encrypts plaintext written since
+        assert_eq!(reopened.migrate_all().await.unwrap(), 1);
+        assert_eq!(
+            reopened.read_updates("meet").await.unwrap(),
+            vec!["injected"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_plaintext_finished_meet() {
+        let (storage, _temp_dir) = setup();
+        let inner = storage.inner();
+        inner.reserve_meet_id("meet").await.unwrap();
+        inner.append_update("meet", "update1").await.unwrap();
+        inner.store_meet_info("meet", "hash", &[]).await.unwrap();
+        inner
+            .store_meet_csv("meet", "Name", "director@example.com")
+            .await
+            .unwrap();
+        inner.archive_meet("meet").await.unwrap();
+        let activity = inner.meet_activity("meet").await.unwrap();
+
+        assert_eq!(storage.migrate_all().await.unwrap(), 1);
+        let stored = inner.read_finished_meet("meet").await.unwrap().unwrap();
+        assert!(is_sealed(&stored.updates[0]));
+        assert!(is_encrypted(&stored.info.unwrap().password_hash));
+        assert!(is_encrypted(&stored.csv.unwrap().1));
+        let data = storage.read_finished_meet("meet").await.unwrap().unwrap();
+        assert_eq!(data.updates, vec!["update1"]);
+        assert_eq!(data.info.unwrap().password_hash, "hash");
+        assert_eq!(
+            data.csv,
+            Some(("Name".to_string(), "director@example.com".to_string()))
+        );
+        // The meet stays finished and keeps its retention times
+        assert_eq!(inner.meet_activity("meet").await.unwrap(), activity);
+
+        assert_eq!(storage.migrate_all().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,449 @@
+// crates/backend-lib/src/storage/faulty.rs
+
+/** Fault-injecting Storage wrapper for tests.
+`FaultyStorage` passes every call through to another `Storage`, except where
+its `FaultPlan` says otherwise. A plan is a script of rules, each naming an
+operation, which of its calls to hit (counted from 1) and the fault to inject:
+
+```
+use backend_lib::storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, StorageOp};
+use std::{io::ErrorKind, time::Duration};
+
+let plan = FaultPlan::new()
+    .on_call(StorageOp::AppendUpdate, 3, Fault::Error(ErrorKind::StorageFull))
+    .from_call(StorageOp::GetMeetInfo, 1, Fault::Latency(Duration::from_millis(50)));
+let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+```
+
+Several rules may match one call; latency is applied first, then the first
+error or partial write. */
+use super::{MeetActivity, MeetData, Storage};
+use crate::error::AppError;
+use crate::meet_registry::MeetRecord;
+use async_trait::async_trait;
+use openlifter_common::{EndpointPriority, MeetInfo};
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A `Storage` method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageOp {
+    AppendUpdate,
+    ReadUpdates,
+    ReadUpdatesFrom,
+    ReplaceUpdates,
+    ArchiveMeet,
+    ListMeets,
+    ReserveMeetId,
+    ReleaseMeetId,
+    StoreMeetInfo,
+    GetMeetInfo,
+    StoreMeetCsv,
+    GetMeetCsv,
+    MeetActivity,
+    PurgeMeet,
+    PurgeMeetCsv,
+    ReadFinishedMeet,
+    RewriteFinishedMeet,
+    ReplaceMeet,
+}
+
+/// Something that goes wrong during a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Fail with an I/O error of this kind without touching the inner storage
+    Error(ErrorKind),
+    /// Wait this long before running the call
+    Latency(Duration),
+    /// Write only the first `n` bytes, then fail. Applies to the update line of
+    /// `append_update` and the CSV of `store_meet_csv`; other calls just fail.
+    PartialWrite(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Calls {
+    /// Only the nth call
+    Nth(u64),
+    /// The nth call and every one after it
+    From(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    op: StorageOp,
+    calls: Calls,
+    fault: Fault,
+}
+
+impl Rule {
+    fn matches(&self, op: StorageOp, call: u64) -> bool {
+        self.op == op
+            && match self.calls {
+                Calls::Nth(n) => call == n,
+                Calls::From(n) => call >= n,
+            }
+    }
+}
+
+/// Scripted faults for a `FaultyStorage`
+#[derive(Debug, Clone, Default)]
+pub struct FaultPlan {
+    rules: Vec<Rule>,
+}
+
+impl FaultPlan {
+    /// A plan that injects nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inject `fault` into the `n`th call of `op` only
+    pub fn on_call(mut self, op: StorageOp, n: u64, fault: Fault) -> Self {
+        self.rules.push(Rule {
+            op,
+            calls: Calls::Nth(n),
+            fault,
+        });
+        self
+    }
+
+    /// Inject `fault` into the `n`th call of `op` and every later one
+    pub fn from_call(mut self, op: StorageOp, n: u64, fault: Fault) -> Self {
+        self.rules.push(Rule {
+            op,
+            calls: Calls::From(n),
+            fault,
+        });
+        self
+    }
+
+    /// Inject `fault` into every call of `op`
+    pub fn always(self, op: StorageOp, fault: Fault) -> Self {
+        self.from_call(op, 1, fault)
+    }
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    plan: FaultPlan,
+    calls: HashMap<StorageOp, u64>,
+}
+
+/// Storage wrapper that injects faults according to a `FaultPlan`
+#[derive(Debug, Clone)]
+pub struct FaultyStorage<S> {
+    inner: S,
+    state: Arc<Mutex<FaultState>>,
+}
+
+/// Outcome of consulting the plan for one call
+enum Injected {
+    None,
+    Error(ErrorKind),
+    PartialWrite(usize),
+}
+
+fn injected_error(kind: ErrorKind) -> AppError {
+    AppError::Io(io::Error::new(kind, "injected storage fault"))
+}
+
+/// The first `n` bytes of `s`, cut back to a character boundary
+fn truncate(s: &str, n: usize) -> &str {
+    let mut end = n.min(s.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+impl<S: Storage> FaultyStorage<S> {
+    /// Wrap `inner`, injecting the faults in `plan`
+    pub fn new(inner: S, plan: FaultPlan) -> Self {
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(FaultState {
+                plan,
+                calls: HashMap::new(),
+            })),
+        }
+    }
+
+    /// The wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Replace the plan; call counts carry on from where they were
+    pub fn set_plan(&self, plan: FaultPlan) {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).plan = plan;
+    }
+
+    /// How many times `op` has been called, faulty or not
+    pub fn calls(&self, op: StorageOp) -> u64 {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.calls.get(&op).copied().unwrap_or(0)
+    }
+
+    /// Count the call, apply any latency and report what else to inject
+    async fn begin(&self, op: StorageOp) -> Injected {
+        let faults: Vec<Fault> = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let call = state.calls.entry(op).or_insert(0);
+            *call += 1;
+            let call = *call;
+            state
+                .plan
+                .rules
+                .iter()
+                .filter(|rule| rule.matches(op, call))
+                .map(|rule| rule.fault)
+                .collect()
+        };
+
+        let delay: Duration = faults
+            .iter()
+            .filter_map(|fault| match fault {
+                Fault::Latency(delay) => Some(*delay),
+                _ => None,
+            })
+            .sum();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        faults
+            .iter()
+            .find_map(|fault| match fault {
+                Fault::Error(kind) => Some(Injected::Error(*kind)),
+                Fault::PartialWrite(n) => Some(Injected::PartialWrite(*n)),
+                Fault::Latency(_) => None,
+            })
+            .unwrap_or(Injected::None)
+    }
+
+    /// Fail the call if the plan says so
+    async fn check(&self, op: StorageOp) -> Result<(), AppError> {
+        match self.begin(op).await {
+            Injected::None => Ok(()),
+            Injected::Error(kind) => Err(injected_error(kind)),
+            Injected::PartialWrite(_) => Err(injected_error(ErrorKind::WriteZero)),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Storage> Storage for FaultyStorage<S> {
+    async fn append_update(&self, meet_id: &str, json_line: &str) -> Result<(), AppError> {
+        match self.begin(StorageOp::AppendUpdate).await {
+            Injected::None => self.inner.append_update(meet_id, json_line).await,
+            Injected::Error(kind) => Err(injected_error(kind)),
+            Injected::PartialWrite(n) => {
+                self.inner
+                    .append_update(meet_id, truncate(json_line, n))
+                    .await?;
+                Err(injected_error(ErrorKind::WriteZero))
+            },
+        }
+    }
+
+    async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError> {
+        self.check(StorageOp::ReadUpdates).await?;
+        self.inner.read_updates(meet_id).await
+    }
+
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        self.check(StorageOp::ReadUpdatesFrom).await?;
+        self.inner
+            .read_updates_from(meet_id, first_seq, limit)
+            .await
+    }
+
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        self.check(StorageOp::ReplaceUpdates).await?;
+        self.inner.replace_updates(meet_id, json_lines).await
+    }
+
+    async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        self.check(StorageOp::ArchiveMeet).await?;
+        self.inner.archive_meet(meet_id).await
+    }
+
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        self.check(StorageOp::ListMeets).await?;
+        self.inner.list_meets().await
+    }
+
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        self.check(StorageOp::ReserveMeetId).await?;
+        self.inner.reserve_meet_id(meet_id).await
+    }
+
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        self.check(StorageOp::ReleaseMeetId).await?;
+        self.inner.release_meet_id(meet_id).await
+    }
+
+    async fn store_meet_info(
+        &self,
+        meet_id: &str,
+        password_hash: &str,
+        endpoints: &[EndpointPriority],
+    ) -> Result<(), AppError> {
+        self.check(StorageOp::StoreMeetInfo).await?;
+        self.inner
+            .store_meet_info(meet_id, password_hash, endpoints)
+            .await
+    }
+
+    async fn get_meet_info(&self, meet_id: &str) -> Result<MeetInfo, AppError> {
+        self.check(StorageOp::GetMeetInfo).await?;
+        self.inner.get_meet_info(meet_id).await
+    }
+
+    async fn store_meet_csv(
+        &self,
+        meet_id: &str,
+        opl_csv: &str,
+        return_email: &str,
+    ) -> Result<(), AppError> {
+        match self.begin(StorageOp::StoreMeetCsv).await {
+            Injected::None => {
+                self.inner
+                    .store_meet_csv(meet_id, opl_csv, return_email)
+                    .await
+            },
+            Injected::Error(kind) => Err(injected_error(kind)),
+            Injected::PartialWrite(n) => {
+                self.inner
+                    .store_meet_csv(meet_id, truncate(opl_csv, n), return_email)
+                    .await?;
+                Err(injected_error(ErrorKind::WriteZero))
+            },
+        }
+    }
+
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        self.check(StorageOp::GetMeetCsv).await?;
+        self.inner.get_meet_csv(meet_id).await
+    }
+
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        self.check(StorageOp::MeetActivity).await?;
+        self.inner.meet_activity(meet_id).await
+    }
+
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        self.check(StorageOp::PurgeMeet).await?;
+        self.inner.purge_meet(meet_id).await
+    }
+
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        self.check(StorageOp::PurgeMeetCsv).await?;
+        self.inner.purge_meet_csv(meet_id).await
+    }
+
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        self.check(StorageOp::ReadFinishedMeet).await?;
+        self.inner.read_finished_meet(meet_id).await
+    }
+
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        self.check(StorageOp::RewriteFinishedMeet).await?;
+        self.inner.rewrite_finished_meet(meet_id, data).await
+    }
+
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        self.check(StorageOp::ReplaceMeet).await?;
+        self.inner.replace_meet(meet_id, data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_error_on_nth_call() {
+        let plan = FaultPlan::new().on_call(
+            StorageOp::AppendUpdate,
+            2,
+            Fault::Error(ErrorKind::StorageFull),
+        );
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+
+        storage.append_update("meet", "update1").await.unwrap();
+        let err = storage.append_update("meet", "update2").await.unwrap_err();
+        assert!(matches!(err, AppError::Io(e) if e.kind() == ErrorKind::StorageFull));
+        storage.append_update("meet", "update3").await.unwrap();
+
+        assert_eq!(storage.calls(StorageOp::AppendUpdate), 3);
+        assert_eq!(
+            storage.read_updates("meet").await.unwrap(),
+            vec!["update1".to_string(), "update3".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_from_call() {
+        let plan = FaultPlan::new().from_call(
+            StorageOp::ReserveMeetId,
+            2,
+            Fault::Error(ErrorKind::PermissionDenied),
+        );
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+
+        assert!(storage.reserve_meet_id("111-111-111").await.unwrap());
+        assert!(storage.reserve_meet_id("222-222-222").await.is_err());
+        assert!(storage.reserve_meet_id("333-333-333").await.is_err());
+
+        // Clearing the plan restores normal behaviour
+        storage.set_plan(FaultPlan::new());
+        assert!(storage.reserve_meet_id("333-333-333").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_partial_write() {
+        let plan = FaultPlan::new().on_call(StorageOp::AppendUpdate, 1, Fault::PartialWrite(5));
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+
+        assert!(storage
+            .append_update("meet", r#"{"key":"value"}"#)
+            .await
+            .is_err());
+        assert_eq!(
+            storage.inner().read_updates("meet").await.unwrap(),
+            vec![r#"{"key"#.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latency() {
+        let plan = FaultPlan::new().always(
+            StorageOp::GetMeetInfo,
+            Fault::Latency(Duration::from_millis(50)),
+        );
+        let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+        storage.store_meet_info("meet", "hash", &[]).await.unwrap();
+
+        let start = Instant::now();
+        storage.get_meet_info("meet").await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        assert_eq!(truncate("héllo", 2), "h");
+        assert_eq!(truncate("abc", 10), "abc");
+    }
+}
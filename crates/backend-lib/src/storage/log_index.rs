@@ -0,0 +1,306 @@
+// crates/backend-lib/src/storage/log_index.rs
+
+/** Sparse offset index for `updates.log`.
+`updates.idx` next to a log holds an entry for every `INDEX_INTERVAL`th
+record: its byte offset as a little-endian `u64`, the CRC-32 of its whole
+line, and the highest server sequence number stored in any record up to and
+including it, also as a little-endian `u64`. Sequence numbers grow along the
+log, so the updates from a sequence number on are found by seeking to the
+last entry below it and skipping the records before it from there. Numbers
+come from the records themselves (see `update_seq`), so records lost from
+the middle of a log do not shift the numbers after them.
+
+The index is only a cache. Reads extend it as they pass records that are not
+indexed yet, rewriting a log drops it, and an entry whose line does not match
+its CRC causes it to be rebuilt from the beginning of the log. */
+use super::update_log::{decode_at, Decoded};
+use super::update_seq;
+use std::{
+    io::{self, ErrorKind, SeekFrom},
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs as tokio_fs,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader},
+};
+
+/// Number of records between two index entries
+const INDEX_INTERVAL: u64 = 256;
+
+/// Size of one index entry
+const ENTRY_LEN: usize = 20;
+
+/// Offset, line CRC and highest sequence number so far of an indexed record
+type Entry = (u64, u32, u64);
+
+/// Path of the index kept for a log
+fn index_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension("idx")
+}
+
+/// Drop the index of a log after the log has been rewritten
+pub(super) async fn invalidate(log_path: &Path) -> io::Result<()> {
+    match tokio_fs::remove_file(index_path(log_path)).await {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Load the entries of an index. A missing or torn index reads as empty.
+async fn load(log_path: &Path) -> io::Result<Vec<Entry>> {
+    let bytes = match tokio_fs::read(index_path(log_path)).await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    if bytes.len() % ENTRY_LEN != 0 {
+        invalidate(log_path).await?;
+        return Ok(Vec::new());
+    }
+    Ok(bytes
+        .chunks_exact(ENTRY_LEN)
+        .map(|entry| {
+            let (offset, rest) = entry.split_at(8);
+            let (crc, seq) = rest.split_at(4);
+            (
+                u64::from_le_bytes(offset.try_into().expect("offset is 8 bytes")),
+                u32::from_le_bytes(crc.try_into().expect("CRC is 4 bytes")),
+                u64::from_le_bytes(seq.try_into().expect("sequence number is 8 bytes")),
+            )
+        })
+        .collect())
+}
+
+/// Append new entries to an index
+async fn extend(log_path: &Path, entries: &[Entry]) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(entries.len() * ENTRY_LEN);
+    for (offset, crc, seq) in entries {
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes.extend_from_slice(&seq.to_le_bytes());
+    }
+    let mut file = tokio_fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(index_path(log_path))
+        .await?;
+    file.write_all(&bytes).await?;
+    file.flush().await
+}
+
+/// Whether the record an entry was made for still starts at its offset
+async fn entry_matches(file: &mut tokio_fs::File, (offset, crc, seq): Entry) -> io::Result<bool> {
+    if offset > 0 {
+        let mut previous = [0u8];
+        file.seek(SeekFrom::Start(offset - 1)).await?;
+        file.read_exact(&mut previous).await?;
+        if previous[0] != b'\n' {
+            return Ok(false);
+        }
+    } else {
+        file.seek(SeekFrom::Start(0)).await?;
+    }
+    let mut line = Vec::new();
+    BufReader::new(&mut *file)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if crc32fast::hash(&line) != crc {
+        return Ok(false);
+    }
+    Ok(match decode_at(&line, 0) {
+        Decoded::Record(payload, _) => update_seq(payload).is_none_or(|own| own <= seq),
+        _ => false,
+    })
+}
+
+/// Records read from part of a log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LogPage {
+    /// Payloads of the records read, in order
+    pub records: Vec<String>,
+    /// Offset of a damaged record that stopped the read, if there was one
+    pub corrupt_at: Option<u64>,
+}
+
+/// Read at most `limit` records numbered `first_seq` or higher
+/// Records without a sequence number are skipped. The read stops early at the
+/// end of the log, at a torn final record or at a damaged record. Callers must
+/// not run two reads of the same log at once, since both could extend the
+/// index.
+pub(super) async fn read_from(
+    log_path: &Path,
+    first_seq: u64,
+    limit: usize,
+) -> io::Result<LogPage> {
+    let mut page = LogPage {
+        records: Vec::new(),
+        corrupt_at: None,
+    };
+    let mut file = match tokio_fs::File::open(log_path).await {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(page),
+        Err(err) => return Err(err),
+    };
+    if limit == 0 {
+        return Ok(page);
+    }
+
+    let mut index = load(log_path).await?;
+    // Position of the next record, its offset and the highest number before it
+    let (mut record, mut offset, mut last_seq) = (0, 0, 0);
+    if let Some(entry) = index
+        .partition_point(|&(_, _, seq)| seq < first_seq)
+        .checked_sub(1)
+    {
+        (record, offset, last_seq) = (
+            entry as u64 * INDEX_INTERVAL,
+            index[entry].0,
+            index[entry].2,
+        );
+        if !entry_matches(&mut file, index[entry]).await? {
+            tracing::warn!("Rebuilding stale index of {}", log_path.display());
+            invalidate(log_path).await?;
+            index.clear();
+            (record, offset, last_seq) = (0, 0, 0);
+        }
+    }
+
+    file.seek(SeekFrom::Start(offset)).await?;
+    let mut reader = BufReader::new(file);
+    let mut new_entries = Vec::new();
+    let mut line = Vec::new();
+    while page.records.len() < limit {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).await?;
+        if read == 0 {
+            break;
+        }
+        match decode_at(&line, 0) {
+            Decoded::Record(payload, _) => {
+                let seq = update_seq(payload);
+                last_seq = last_seq.max(seq.unwrap_or(0));
+                if record % INDEX_INTERVAL == 0 && record / INDEX_INTERVAL == index.len() as u64 {
+                    let entry = (offset, crc32fast::hash(&line), last_seq);
+                    index.push(entry);
+                    new_entries.push(entry);
+                }
+                if seq.is_some_and(|seq| seq >= first_seq) {
+                    page.records.push(payload.to_string());
+                }
+                record += 1;
+            },
+            Decoded::Blank(_) => {},
+            Decoded::Torn => break,
+            Decoded::Corrupt(_) => {
+                page.corrupt_at = Some(offset);
+                break;
+            },
+        }
+        offset += read as u64;
+    }
+
+    if !new_entries.is_empty() {
+        extend(log_path, &new_entries).await?;
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::update_log::encode_record;
+    use tempfile::TempDir;
+
+    fn write_log(path: &Path, seqs: impl IntoIterator<Item = u64>) {
+        let log: String = seqs
+            .into_iter()
+            .map(|n| encode_record(&record(n)))
+            .collect();
+        std::fs::write(path, log).unwrap();
+    }
+
+    fn record(n: u64) -> String {
+        format!("{{\"serverSeqNum\":{n}}}")
+    }
+
+    #[tokio::test]
+    async fn test_read_from_builds_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        write_log(&path, 1..=1000);
+
+        let page = read_from(&path, 600, 3).await.unwrap();
+        assert_eq!(page.records, vec![record(600), record(601), record(602)]);
+        assert_eq!(page.corrupt_at, None);
+        // Entries for records 0, 256 and 512 were written on the way
+        assert_eq!(load(&path).await.unwrap().len(), 3);
+
+        // Later reads start from the index
+        let page = read_from(&path, 999, 10).await.unwrap();
+        assert_eq!(page.records, vec![record(999), record(1000)]);
+        assert_eq!(load(&path).await.unwrap().len(), 4);
+        assert!(read_from(&path, 1001, 10).await.unwrap().records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_index_is_rebuilt() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        write_log(&path, 1..=600);
+        read_from(&path, 600, 1).await.unwrap();
+
+        // Rewrite the log behind the index's back with longer records
+        let log: String = (1..=600)
+            .map(|n| encode_record(&format!("{{\"serverSeqNum\":{n},\"pad\":0}}")))
+            .collect();
+        std::fs::write(&path, log).unwrap();
+
+        let page = read_from(&path, 300, 2).await.unwrap();
+        assert_eq!(
+            page.records,
+            vec![
+                r#"{"serverSeqNum":300,"pad":0}"#,
+                r#"{"serverSeqNum":301,"pad":0}"#
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_from_stops_at_damage() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        let mut bytes = [encode_record(&record(1)), encode_record(&record(2))]
+            .concat()
+            .into_bytes();
+        let second = encode_record(&record(1)).len();
+        bytes[second + 20] ^= 0x01;
+        bytes.extend_from_slice(b"00000003 ");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let page = read_from(&path, 0, 10).await.unwrap();
+        assert_eq!(page.records, vec![record(1)]);
+        assert_eq!(page.corrupt_at, Some(second as u64));
+
+        // A missing log reads as empty
+        let missing = temp_dir.path().join("missing.log");
+        assert!(read_from(&missing, 0, 10).await.unwrap().records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_from_follows_stored_numbers() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        // A repair dropped update 300, shifting every later record
+        write_log(&path, (1..=600).filter(|&n| n != 300));
+
+        let page = read_from(&path, 299, 3).await.unwrap();
+        assert_eq!(page.records, vec![record(299), record(301), record(302)]);
+        assert_eq!(load(&path).await.unwrap().len(), 2);
+
+        // The index finds numbers past the gap
+        let page = read_from(&path, 514, 1).await.unwrap();
+        assert_eq!(page.records, vec![record(514)]);
+        let page = read_from(&path, 300, 1).await.unwrap();
+        assert_eq!(page.records, vec![record(301)]);
+    }
+}
@@ -0,0 +1,336 @@
+// crates/backend-lib/src/storage/memory.rs
+
+/** In-memory implementation of the Storage trait.
+Nothing touches the disk, which makes it the quickest backend for tests. It
+follows the same rules as `FlatFileStorage`: writes create the meet as a
+current meet, and only current meets serve updates and meet info. */
+use super::{update_seq, MeetActivity, MeetData, Storage};
+use crate::error::AppError;
+use crate::meet_registry::{MeetRecord, MeetStatus};
+use async_trait::async_trait;
+use chrono::Utc;
+use openlifter_common::{EndpointPriority, MeetInfo};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+#[derive(Debug, Default)]
+struct MemoryMeet {
+    finished: bool,
+    updates: Vec<String>,
+    info: Option<MeetInfo>,
+    /// Published CSV and return email
+    csv: Option<(String, String)>,
+    activity: MeetActivity,
+}
+
+impl MemoryMeet {
+    /// Record a change to the meet's updates or info
+    fn touch(&mut self) -> &mut Self {
+        self.activity.last_change = Some(Utc::now());
+        self
+    }
+}
+
+/// In-memory implementation of the Storage trait
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    meets: Arc<Mutex<HashMap<String, MemoryMeet>>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, MemoryMeet>> {
+        // A panic while holding the lock cannot leave a meet half-written
+        self.meets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn append_update(&self, meet_id: &str, json_line: &str) -> Result<(), AppError> {
+        self.lock()
+            .entry(meet_id.to_string())
+            .or_default()
+            .touch()
+            .updates
+            .push(json_line.to_string());
+        Ok(())
+    }
+
+    async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .lock()
+            .get(meet_id)
+            .filter(|meet| !meet.finished)
+            .map(|meet| {
+                meet.updates
+                    .iter()
+                    .filter(|line| !line.is_empty())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .lock()
+            .get(meet_id)
+            .filter(|meet| !meet.finished)
+            .map(|meet| {
+                meet.updates
+                    .iter()
+                    .filter(|line| update_seq(line).is_some_and(|seq| seq >= first_seq))
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        self.lock()
+            .entry(meet_id.to_string())
+            .or_default()
+            .touch()
+            .updates = json_lines.to_vec();
+        Ok(())
+    }
+
+    async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        if let Some(meet) = self.lock().get_mut(meet_id) {
+            meet.finished = true;
+            meet.activity.finished_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        Ok(self
+            .lock()
+            .iter()
+            .map(|(meet_id, meet)| MeetRecord {
+                meet_id: meet_id.clone(),
+                status: if meet.finished {
+                    MeetStatus::Finished
+                } else {
+                    MeetStatus::Current
+                },
+            })
+            .collect())
+    }
+
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        let mut meets = self.lock();
+        if meets.contains_key(meet_id) {
+            return Ok(false);
+        }
+        let mut meet = MemoryMeet::default();
+        meet.touch();
+        meets.insert(meet_id.to_string(), meet);
+        Ok(true)
+    }
+
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        let mut meets = self.lock();
+        if meets.get(meet_id).is_some_and(|meet| !meet.finished) {
+            meets.remove(meet_id);
+        }
+        Ok(())
+    }
+
+    async fn store_meet_info(
+        &self,
+        meet_id: &str,
+        password_hash: &str,
+        endpoints: &[EndpointPriority],
+    ) -> Result<(), AppError> {
+        self.lock()
+            .entry(meet_id.to_string())
+            .or_default()
+            .touch()
+            .info = Some(MeetInfo {
+            password_hash: password_hash.to_string(),
+            endpoints: endpoints.to_vec(),
+        });
+        Ok(())
+    }
+
+    async fn get_meet_info(&self, meet_id: &str) -> Result<MeetInfo, AppError> {
+        self.lock()
+            .get(meet_id)
+            .filter(|meet| !meet.finished)
+            .and_then(|meet| meet.info.clone())
+            .ok_or(AppError::MeetNotFound)
+    }
+
+    async fn store_meet_csv(
+        &self,
+        meet_id: &str,
+        opl_csv: &str,
+        return_email: &str,
+    ) -> Result<(), AppError> {
+        let mut meets = self.lock();
+        let meet = meets.entry(meet_id.to_string()).or_default();
+        meet.csv = Some((opl_csv.to_string(), return_email.to_string()));
+        meet.activity.published_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        Ok(self
+            .lock()
+            .get(meet_id)
+            .filter(|meet| !meet.finished)
+            .and_then(|meet| meet.csv.clone()))
+    }
+
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        Ok(self.lock().get(meet_id).map(|meet| meet.activity.clone()))
+    }
+
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        let mut meets = self.lock();
+        let meet = meets
+            .get_mut(meet_id)
+            .filter(|meet| meet.finished)
+            .ok_or(AppError::MeetNotFound)?;
+        *meet = MemoryMeet {
+            finished: true,
+            activity: MeetActivity {
+                purged_at: Some(Utc::now()),
+                ..meet.activity.clone()
+            },
+            ..MemoryMeet::default()
+        };
+        meet.activity.published_at = None;
+        Ok(())
+    }
+
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        if let Some(meet) = self.lock().get_mut(meet_id) {
+            meet.csv = None;
+            meet.activity.published_at = None;
+        }
+        Ok(())
+    }
+
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        Ok(self
+            .lock()
+            .get(meet_id)
+            .filter(|meet| meet.finished)
+            .map(|meet| MeetData {
+                updates: meet.updates.clone(),
+                info: meet.info.clone(),
+                csv: meet.csv.clone(),
+            }))
+    }
+
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let mut meets = self.lock();
+        let meet = meets
+            .get_mut(meet_id)
+            .filter(|meet| meet.finished)
+            .ok_or(AppError::MeetNotFound)?;
+        meet.updates.clone_from(&data.updates);
+        if data.info.is_some() {
+            meet.info.clone_from(&data.info);
+        }
+        if data.csv.is_some() {
+            meet.csv.clone_from(&data.csv);
+        }
+        Ok(())
+    }
+
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let mut meets = self.lock();
+        let meet = meets
+            .get_mut(meet_id)
+            .filter(|meet| !meet.finished)
+            .ok_or(AppError::MeetNotFound)?;
+        meet.touch();
+        meet.updates.clone_from(&data.updates);
+        meet.info.clone_from(&data.info);
+        meet.csv.clone_from(&data.csv);
+        meet.activity.published_at = data.csv.as_ref().map(|_| Utc::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::*;
+
+    #[tokio::test]
+    async fn test_append_read_updates() {
+        check_append_read_updates(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_get_meet_info() {
+        check_store_get_meet_info(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_archive_meet() {
+        check_archive_meet(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_reserve_meet_id() {
+        check_reserve_meet_id(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_meets() {
+        check_list_meets(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_csv_data() {
+        check_store_csv_data(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_updates() {
+        check_replace_updates(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_from() {
+        check_read_updates_from(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_purge_meet() {
+        check_purge_meet(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_finished_meet() {
+        check_rewrite_finished_meet(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_meet() {
+        check_replace_meet(&MemoryStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_nonexistent_meet() {
+        check_read_updates_nonexistent_meet(&MemoryStorage::new()).await;
+    }
+}
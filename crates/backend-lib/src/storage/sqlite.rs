@@ -0,0 +1,810 @@
+// crates/backend-lib/src/storage/sqlite.rs
+
+/** SQLite implementation of the Storage trait.
+Every meet lives in a single database file:
+- `meets` holds one row per meet with its status, meet info and published CSV
+- `updates` holds the update logs, ordered by a per-meet sequence number and
+  paged by the server sequence number stored in each update
+- `meet_times` holds the times the retention policy works from, as Unix seconds
+
+Appends and meet info writes each run in their own transaction, so a crash
+leaves either the whole change or none of it. The `Durability` policy maps
+to SQLite's `synchronous` setting. As with `FlatFileStorage`,
+updates and meet info are only read back for current meets. */
+use super::{update_seq, MeetActivity, MeetData, Storage};
+use crate::config::Durability;
+use crate::error::AppError;
+use crate::meet_registry::{MeetRecord, MeetStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use openlifter_common::{EndpointPriority, MeetInfo};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meets (
+    meet_id       TEXT PRIMARY KEY,
+    finished      INTEGER NOT NULL DEFAULT 0,
+    password_hash TEXT,
+    endpoints     TEXT,
+    opl_csv       TEXT,
+    return_email  TEXT
+);
+CREATE TABLE IF NOT EXISTS updates (
+    meet_id    TEXT NOT NULL REFERENCES meets (meet_id),
+    seq        INTEGER NOT NULL,
+    line       TEXT NOT NULL,
+    server_seq INTEGER,
+    PRIMARY KEY (meet_id, seq)
+) WITHOUT ROWID;
+CREATE TABLE IF NOT EXISTS meet_times (
+    meet_id      TEXT PRIMARY KEY REFERENCES meets (meet_id),
+    changed_at   INTEGER,
+    finished_at  INTEGER,
+    published_at INTEGER,
+    purged_at    INTEGER
+);
+";
+
+/// Meets from before `meet_times` existed start ageing when they are first opened
+const BACKFILL_TIMES: &str = "
+INSERT OR IGNORE INTO meet_times (meet_id, changed_at, finished_at, published_at)
+SELECT meet_id, ?1, CASE WHEN finished THEN ?1 END, CASE WHEN opl_csv IS NOT NULL THEN ?1 END
+FROM meets
+";
+
+/// Give `updates` the server sequence number of each line, which databases
+/// from before it was kept lack, and index it
+fn add_server_seq(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    let has_column = tx
+        .prepare("SELECT 1 FROM pragma_table_info('updates') WHERE name = 'server_seq'")?
+        .exists([])?;
+    if !has_column {
+        tx.execute("ALTER TABLE updates ADD COLUMN server_seq INTEGER", [])?;
+        let rows = tx
+            .prepare("SELECT meet_id, seq, line FROM updates")?
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut stmt =
+            tx.prepare("UPDATE updates SET server_seq = ?3 WHERE meet_id = ?1 AND seq = ?2")?;
+        for (meet_id, seq, line) in rows {
+            stmt.execute(params![meet_id, seq, server_seq(&line)])?;
+        }
+    }
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS updates_by_server_seq ON updates (meet_id, server_seq)",
+        [],
+    )?;
+    tx.commit()
+}
+
+/// Server sequence number of an update line, as stored in `updates.server_seq`
+fn server_seq(line: &str) -> Option<i64> {
+    update_seq(line).and_then(|seq| i64::try_from(seq).ok())
+}
+
+/// Insert the rows of a meet's log, numbered from 1
+fn insert_updates(tx: &Transaction<'_>, meet_id: &str, lines: &[String]) -> rusqlite::Result<()> {
+    let mut stmt =
+        tx.prepare("INSERT INTO updates (meet_id, seq, line, server_seq) VALUES (?1, ?2, ?3, ?4)")?;
+    for (seq, line) in (1i64..).zip(lines) {
+        stmt.execute(params![meet_id, seq, line, server_seq(line)])?;
+    }
+    Ok(())
+}
+
+/// Make sure a row exists for the meet; new rows are current meets
+fn ensure_meet(tx: &Transaction<'_>, meet_id: &str) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT OR IGNORE INTO meets (meet_id) VALUES (?1)",
+        [meet_id],
+    )?;
+    Ok(())
+}
+
+/// Set one of the `meet_times` columns of a meet to the current time
+fn touch(tx: &Transaction<'_>, meet_id: &str, column: &str) -> rusqlite::Result<()> {
+    tx.execute(
+        &format!(
+            "INSERT INTO meet_times (meet_id, {column}) VALUES (?1, ?2)
+             ON CONFLICT (meet_id) DO UPDATE SET {column} = excluded.{column}"
+        ),
+        params![meet_id, Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+fn to_time(secs: Option<i64>) -> Option<DateTime<Utc>> {
+    secs.and_then(|secs| DateTime::from_timestamp(secs, 0))
+}
+
+/// SQLite implementation of the Storage trait
+#[derive(Clone)]
+pub struct SqliteStorage {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStorage {
+    /// Open a database file, creating it and its schema if needed. Every
+    /// commit is synced to disk.
+    /// # Arguments
+    /// * `path` - Database file
+    /// # Returns
+    /// * `Ok(SqliteStorage)` - New storage instance
+    /// * `Err(anyhow::Error)` if the database could not be opened
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        Self::open_with_durability(path, Durability::default())
+    }
+
+    /// Open a database file with the given sync policy. With WAL, SQLite
+    /// already groups syncs under `synchronous = NORMAL`, which is used for
+    /// `Durability::Group`; it may lose the latest commits on power loss.
+    pub fn open_with_durability<P: AsRef<Path>>(
+        path: P,
+        durability: Durability,
+    ) -> anyhow::Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let synchronous = match durability {
+            Durability::Always => "FULL",
+            Durability::Group => "NORMAL",
+            Durability::Buffered => "OFF",
+        };
+        conn.pragma_update(None, "synchronous", synchronous)?;
+        Self::init(conn)
+    }
+
+    /// Open a private in-memory database
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> anyhow::Result<Self> {
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        // Overwrite deleted rows, so purged meet data does not linger in free pages
+        conn.pragma_update(None, "secure_delete", "ON")?;
+        conn.execute_batch(SCHEMA)?;
+        add_server_seq(&mut conn)?;
+        conn.execute(BACKFILL_TIMES, [Utc::now().timestamp()])?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `f` against the connection on the blocking thread pool
+    async fn with_conn<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| AppError::Internal("Database lock poisoned".to_string()))?;
+            f(&mut conn).map_err(AppError::from)
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Database task failed: {e}")))?
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    /// Append an update as the next row of the meet's log
+    async fn append_update(&self, meet_id: &str, json_line: &str) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let line = json_line.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_meet(&tx, &meet_id)?;
+            tx.execute(
+                "INSERT INTO updates (meet_id, seq, line, server_seq)
+                 SELECT ?1, COALESCE(MAX(seq), 0) + 1, ?2, ?3 FROM updates WHERE meet_id = ?1",
+                params![meet_id, line, server_seq(&line)],
+            )?;
+            touch(&tx, &meet_id, "changed_at")?;
+            tx.commit()
+        })
+        .await
+    }
+
+    /// Read the update log of a current meet in append order
+    async fn read_updates(&self, meet_id: &str) -> Result<Vec<String>, AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT u.line FROM updates u JOIN meets m ON m.meet_id = u.meet_id
+                 WHERE u.meet_id = ?1 AND m.finished = 0 AND u.line != ''
+                 ORDER BY u.seq",
+            )?;
+            let lines = stmt
+                .query_map([meet_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok(lines)
+        })
+        .await
+    }
+
+    /// Read a page of the update log using the `(meet_id, server_seq)` index
+    async fn read_updates_from(
+        &self,
+        meet_id: &str,
+        first_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        let meet_id = meet_id.to_string();
+        let first_seq = i64::try_from(first_seq).unwrap_or(i64::MAX);
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT u.line FROM updates u JOIN meets m ON m.meet_id = u.meet_id
+                 WHERE u.meet_id = ?1 AND m.finished = 0 AND u.server_seq >= ?2
+                 ORDER BY u.seq LIMIT ?3",
+            )?;
+            let lines = stmt
+                .query_map(params![meet_id, first_seq, limit], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok(lines)
+        })
+        .await
+    }
+
+    /// Replace the meet's log rows in one transaction
+    async fn replace_updates(&self, meet_id: &str, json_lines: &[String]) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let lines = json_lines.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_meet(&tx, &meet_id)?;
+            tx.execute("DELETE FROM updates WHERE meet_id = ?1", [&meet_id])?;
+            insert_updates(&tx, &meet_id, &lines)?;
+            touch(&tx, &meet_id, "changed_at")?;
+            tx.commit()
+        })
+        .await
+    }
+
+    /// Mark a meet as finished
+    async fn archive_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            if tx.execute(
+                "UPDATE meets SET finished = 1 WHERE meet_id = ?1 AND finished = 0",
+                [&meet_id],
+            )? == 1
+            {
+                touch(&tx, &meet_id, "finished_at")?;
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn list_meets(&self) -> Result<Vec<MeetRecord>, AppError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT meet_id, finished FROM meets")?;
+            let meets = stmt
+                .query_map([], |row| {
+                    let finished: bool = row.get(1)?;
+                    Ok(MeetRecord {
+                        meet_id: row.get(0)?,
+                        status: if finished {
+                            MeetStatus::Finished
+                        } else {
+                            MeetStatus::Current
+                        },
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<MeetRecord>>>()?;
+            Ok(meets)
+        })
+        .await
+    }
+
+    /// Reserve a meet ID by inserting its row; the primary key makes this atomic
+    async fn reserve_meet_id(&self, meet_id: &str) -> Result<bool, AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO meets (meet_id) VALUES (?1)",
+                [&meet_id],
+            )?;
+            if inserted == 1 {
+                touch(&tx, &meet_id, "changed_at")?;
+            }
+            tx.commit()?;
+            Ok(inserted == 1)
+        })
+        .await
+    }
+
+    /// Delete the meet's rows, unless it is finished
+    async fn release_meet_id(&self, meet_id: &str) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let current: Option<bool> = tx
+                .query_row(
+                    "SELECT finished = 0 FROM meets WHERE meet_id = ?1",
+                    [&meet_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if current == Some(true) {
+                tx.execute("DELETE FROM updates WHERE meet_id = ?1", [&meet_id])?;
+                tx.execute("DELETE FROM meet_times WHERE meet_id = ?1", [&meet_id])?;
+                tx.execute("DELETE FROM meets WHERE meet_id = ?1", [&meet_id])?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn store_meet_info(
+        &self,
+        meet_id: &str,
+        password_hash: &str,
+        endpoints: &[EndpointPriority],
+    ) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let password_hash = password_hash.to_string();
+        let endpoints = serde_json::to_string(endpoints)?;
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_meet(&tx, &meet_id)?;
+            tx.execute(
+                "UPDATE meets SET password_hash = ?2, endpoints = ?3 WHERE meet_id = ?1",
+                params![meet_id, password_hash, endpoints],
+            )?;
+            touch(&tx, &meet_id, "changed_at")?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn get_meet_info(&self, meet_id: &str) -> Result<MeetInfo, AppError> {
+        let meet_id = meet_id.to_string();
+        let row = self
+            .with_conn(move |conn| {
+                conn.query_row(
+                    "SELECT password_hash, endpoints FROM meets
+                     WHERE meet_id = ?1 AND finished = 0 AND password_hash IS NOT NULL",
+                    [meet_id],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                )
+                .optional()
+            })
+            .await?;
+
+        let (password_hash, endpoints) = row.ok_or(AppError::MeetNotFound)?;
+        Ok(MeetInfo {
+            password_hash,
+            endpoints: serde_json::from_str(&endpoints)?,
+        })
+    }
+
+    async fn store_meet_csv(
+        &self,
+        meet_id: &str,
+        opl_csv: &str,
+        return_email: &str,
+    ) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let opl_csv = opl_csv.to_string();
+        let return_email = return_email.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            ensure_meet(&tx, &meet_id)?;
+            tx.execute(
+                "UPDATE meets SET opl_csv = ?2, return_email = ?3 WHERE meet_id = ?1",
+                params![meet_id, opl_csv, return_email],
+            )?;
+            touch(&tx, &meet_id, "published_at")?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn get_meet_csv(&self, meet_id: &str) -> Result<Option<(String, String)>, AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT opl_csv, return_email FROM meets
+                 WHERE meet_id = ?1 AND finished = 0 AND opl_csv IS NOT NULL",
+                [meet_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn meet_activity(&self, meet_id: &str) -> Result<Option<MeetActivity>, AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT t.changed_at, t.finished_at, t.published_at, t.purged_at
+                 FROM meets m LEFT JOIN meet_times t ON t.meet_id = m.meet_id
+                 WHERE m.meet_id = ?1",
+                [meet_id],
+                |row| {
+                    Ok(MeetActivity {
+                        last_change: to_time(row.get(0)?),
+                        finished_at: to_time(row.get(1)?),
+                        published_at: to_time(row.get(2)?),
+                        purged_at: to_time(row.get(3)?),
+                    })
+                },
+            )
+            .optional()
+        })
+        .await
+    }
+
+    /// Delete the meet's log and clear its row, keeping the row itself
+    async fn purge_meet(&self, meet_id: &str) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let purged = self
+            .with_conn(move |conn| {
+                let tx = conn.transaction()?;
+                let cleared = tx.execute(
+                    "UPDATE meets SET password_hash = NULL, endpoints = NULL, opl_csv = NULL,
+                     return_email = NULL WHERE meet_id = ?1 AND finished = 1",
+                    [&meet_id],
+                )?;
+                if cleared == 1 {
+                    tx.execute("DELETE FROM updates WHERE meet_id = ?1", [&meet_id])?;
+                    tx.execute(
+                        "UPDATE meet_times SET published_at = NULL WHERE meet_id = ?1",
+                        [&meet_id],
+                    )?;
+                    touch(&tx, &meet_id, "purged_at")?;
+                }
+                tx.commit()?;
+                Ok(cleared == 1)
+            })
+            .await?;
+        if !purged {
+            return Err(AppError::MeetNotFound);
+        }
+        Ok(())
+    }
+
+    async fn purge_meet_csv(&self, meet_id: &str) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "UPDATE meets SET opl_csv = NULL, return_email = NULL WHERE meet_id = ?1",
+                [&meet_id],
+            )?;
+            tx.execute(
+                "UPDATE meet_times SET published_at = NULL WHERE meet_id = ?1",
+                [&meet_id],
+            )?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn read_finished_meet(&self, meet_id: &str) -> Result<Option<MeetData>, AppError> {
+        let meet_id = meet_id.to_string();
+        let row = self
+            .with_conn(move |conn| {
+                let Some(row) = conn
+                    .query_row(
+                        "SELECT password_hash, endpoints, opl_csv, return_email FROM meets
+                         WHERE meet_id = ?1 AND finished = 1",
+                        [&meet_id],
+                        |row| {
+                            Ok((
+                                row.get::<_, Option<String>>(0)?,
+                                row.get::<_, Option<String>>(1)?,
+                                row.get::<_, Option<String>>(2)?,
+                                row.get::<_, Option<String>>(3)?,
+                            ))
+                        },
+                    )
+                    .optional()?
+                else {
+                    return Ok(None);
+                };
+                let mut stmt =
+                    conn.prepare("SELECT line FROM updates WHERE meet_id = ?1 ORDER BY seq")?;
+                let updates = stmt
+                    .query_map([&meet_id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                Ok(Some((updates, row)))
+            })
+            .await?;
+
+        let Some((updates, (password_hash, endpoints, opl_csv, return_email))) = row else {
+            return Ok(None);
+        };
+        let info = match (password_hash, endpoints) {
+            (Some(password_hash), Some(endpoints)) => Some(MeetInfo {
+                password_hash,
+                endpoints: serde_json::from_str(&endpoints)?,
+            }),
+            _ => None,
+        };
+        Ok(Some(MeetData {
+            updates,
+            info,
+            csv: opl_csv.zip(return_email),
+        }))
+    }
+
+    /// Replace the meet's rows in one transaction, leaving `meet_times` alone
+    async fn rewrite_finished_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let lines = data.updates.clone();
+        let info = match &data.info {
+            Some(info) => Some((
+                info.password_hash.clone(),
+                serde_json::to_string(&info.endpoints)?,
+            )),
+            None => None,
+        };
+        let csv = data.csv.clone();
+        let found = self
+            .with_conn(move |conn| {
+                let tx = conn.transaction()?;
+                let finished: Option<bool> = tx
+                    .query_row(
+                        "SELECT finished = 1 FROM meets WHERE meet_id = ?1",
+                        [&meet_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if finished != Some(true) {
+                    return Ok(false);
+                }
+                tx.execute("DELETE FROM updates WHERE meet_id = ?1", [&meet_id])?;
+                insert_updates(&tx, &meet_id, &lines)?;
+                if let Some((password_hash, endpoints)) = info {
+                    tx.execute(
+                        "UPDATE meets SET password_hash = ?2, endpoints = ?3 WHERE meet_id = ?1",
+                        params![meet_id, password_hash, endpoints],
+                    )?;
+                }
+                if let Some((opl_csv, return_email)) = csv {
+                    tx.execute(
+                        "UPDATE meets SET opl_csv = ?2, return_email = ?3 WHERE meet_id = ?1",
+                        params![meet_id, opl_csv, return_email],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(true)
+            })
+            .await?;
+        if !found {
+            return Err(AppError::MeetNotFound);
+        }
+        Ok(())
+    }
+
+    /// Replace the meet's rows in one transaction
+    async fn replace_meet(&self, meet_id: &str, data: &MeetData) -> Result<(), AppError> {
+        let meet_id = meet_id.to_string();
+        let lines = data.updates.clone();
+        let (password_hash, endpoints) = match &data.info {
+            Some(info) => (
+                Some(info.password_hash.clone()),
+                Some(serde_json::to_string(&info.endpoints)?),
+            ),
+            None => (None, None),
+        };
+        let (opl_csv, return_email) = data.csv.clone().unzip();
+        let found = self
+            .with_conn(move |conn| {
+                let tx = conn.transaction()?;
+                let replaced = tx.execute(
+                    "UPDATE meets SET password_hash = ?2, endpoints = ?3, opl_csv = ?4,
+                     return_email = ?5 WHERE meet_id = ?1 AND finished = 0",
+                    params![meet_id, password_hash, endpoints, opl_csv, return_email],
+                )?;
+                if replaced == 0 {
+                    return Ok(false);
+                }
+                tx.execute("DELETE FROM updates WHERE meet_id = ?1", [&meet_id])?;
+                insert_updates(&tx, &meet_id, &lines)?;
+                touch(&tx, &meet_id, "changed_at")?;
+                if opl_csv.is_some() {
+                    touch(&tx, &meet_id, "published_at")?;
+                } else {
+                    tx.execute(
+                        "UPDATE meet_times SET published_at = NULL WHERE meet_id = ?1",
+                        [&meet_id],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(true)
+            })
+            .await?;
+        if !found {
+            return Err(AppError::MeetNotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::*;
+    use tempfile::TempDir;
+
+    fn setup() -> SqliteStorage {
+        SqliteStorage::open_in_memory().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_append_read_updates() {
+        check_append_read_updates(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_get_meet_info() {
+        check_store_get_meet_info(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_archive_meet() {
+        check_archive_meet(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_reserve_meet_id() {
+        check_reserve_meet_id(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_meets() {
+        check_list_meets(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_store_csv_data() {
+        let storage = setup();
+        check_store_csv_data(&storage).await;
+
+        let (csv, email): (String, String) = storage
+            .with_conn(|conn| {
+                conn.query_row(
+                    "SELECT opl_csv, return_email FROM meets WHERE meet_id = 'test-meet'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(csv, "Name,Weight,Squat");
+        assert_eq!(email, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_replace_updates() {
+        check_replace_updates(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_from() {
+        check_read_updates_from(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_purge_meet() {
+        check_purge_meet(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_finished_meet() {
+        check_rewrite_finished_meet(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_meet() {
+        check_replace_meet(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_updates_nonexistent_meet() {
+        check_read_updates_nonexistent_meet(&setup()).await;
+    }
+
+    #[tokio::test]
+    async fn test_data_survives_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("meets.db");
+
+        {
+            let storage = SqliteStorage::open(&path).unwrap();
+            storage.reserve_meet_id("111-111-111").await.unwrap();
+            storage
+                .store_meet_info("111-111-111", "hash", &[])
+                .await
+                .unwrap();
+            storage
+                .append_update("111-111-111", "update1")
+                .await
+                .unwrap();
+        }
+
+        let storage = SqliteStorage::open(&path).unwrap();
+        assert_eq!(
+            storage.read_updates("111-111-111").await.unwrap(),
+            vec!["update1".to_string()]
+        );
+        assert_eq!(
+            storage
+                .get_meet_info("111-111-111")
+                .await
+                .unwrap()
+                .password_hash,
+            "hash"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_old_database_gets_server_seq() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("meets.db");
+
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE meets (
+                     meet_id       TEXT PRIMARY KEY,
+                     finished      INTEGER NOT NULL DEFAULT 0,
+                     password_hash TEXT,
+                     endpoints     TEXT,
+                     opl_csv       TEXT,
+                     return_email  TEXT
+                 );
+                 CREATE TABLE updates (
+                     meet_id TEXT NOT NULL REFERENCES meets (meet_id),
+                     seq     INTEGER NOT NULL,
+                     line    TEXT NOT NULL,
+                     PRIMARY KEY (meet_id, seq)
+                 ) WITHOUT ROWID;
+                 INSERT INTO meets (meet_id) VALUES ('111-111-111');",
+            )
+            .unwrap();
+            for (seq, server_seq) in [(1, 1), (2, 2), (3, 4)] {
+                conn.execute(
+                    "INSERT INTO updates (meet_id, seq, line) VALUES ('111-111-111', ?1, ?2)",
+                    params![seq, numbered(server_seq)],
+                )
+                .unwrap();
+            }
+        }
+
+        let storage = SqliteStorage::open(&path).unwrap();
+        assert_eq!(
+            storage
+                .read_updates_from("111-111-111", 3, 10)
+                .await
+                .unwrap(),
+            vec![numbered(4)]
+        );
+    }
+}
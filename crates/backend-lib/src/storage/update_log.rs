@@ -0,0 +1,308 @@
+// crates/backend-lib/src/storage/update_log.rs
+
+/** Record framing for `updates.log`.
+Every update is written as one line holding its payload length and CRC-32 in
+hex, followed by the payload:
+
+```text
+00000017 343a4b71 {"key":"a","value":"b"}
+```
+
+The length shows where a record should end, so a record cut short by a crash
+is told apart from one that was damaged after it was written. Lines starting
+with `{` are unframed records from before framing was introduced and are
+accepted as they are.
+
+Reading verifies every record. A torn final record (no terminating newline)
+is reported as a torn tail, which is safe to cut off since it was never
+acknowledged. Any other damage is reported as corruption at a byte offset and
+needs `repair_update_log` to recover the records around it. */
+use super::{durable::write_atomic, log_index};
+use crate::error::AppError;
+use std::{ops::Range, path::Path};
+use tokio::fs as tokio_fs;
+
+/// Length of the record header: 8 hex digits, a space, 8 hex digits, a space
+const HEADER_LEN: usize = 18;
+
+/// Frame a payload as one log record, including the trailing newline
+pub fn encode_record(payload: &str) -> String {
+    format!(
+        "{:08x} {:08x} {}\n",
+        payload.len(),
+        crc32fast::hash(payload.as_bytes()),
+        payload
+    )
+}
+
+/// Result of decoding the record starting at an offset
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Decoded<'a> {
+    /// A valid record and the offset of the next one
+    Record(&'a str, usize),
+    /// An empty line
+    Blank(usize),
+    /// The rest of the log is an unterminated record
+    Torn,
+    /// A damaged record; decoding can resume at the next line, if any
+    Corrupt(Option<usize>),
+}
+
+fn parse_hex(bytes: &[u8]) -> Option<usize> {
+    let digits = std::str::from_utf8(bytes).ok()?;
+    usize::from_str_radix(digits, 16).ok()
+}
+
+/// Offset just past the next newline at or after `from`
+fn next_line(bytes: &[u8], from: usize) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|i| from + i + 1)
+}
+
+/// Decode the record starting at `offset`
+pub(super) fn decode_at(bytes: &[u8], offset: usize) -> Decoded<'_> {
+    let rest = &bytes[offset..];
+    let Some(line_end) = next_line(bytes, offset) else {
+        return Decoded::Torn;
+    };
+
+    if rest[0] == b'\n' {
+        return Decoded::Blank(offset + 1);
+    }
+
+    // Unframed record from an older log
+    if rest[0] == b'{' {
+        return match std::str::from_utf8(&bytes[offset..line_end - 1]) {
+            Ok(payload) => Decoded::Record(payload, line_end),
+            Err(_) => Decoded::Corrupt(Some(line_end)),
+        };
+    }
+
+    let header = match rest.get(..HEADER_LEN) {
+        Some(header) if header[8] == b' ' && header[17] == b' ' => header,
+        _ => return Decoded::Corrupt(Some(line_end)),
+    };
+    let (Some(len), Some(crc)) = (parse_hex(&header[..8]), parse_hex(&header[9..17])) else {
+        return Decoded::Corrupt(Some(line_end));
+    };
+
+    let start = offset + HEADER_LEN;
+    let end = start + len;
+    if bytes.get(end) != Some(&b'\n') {
+        // The length is wrong, so this line cannot be trusted either
+        return Decoded::Corrupt(Some(line_end));
+    }
+    let payload = &bytes[start..end];
+    if crc32fast::hash(payload) as usize != crc {
+        return Decoded::Corrupt(Some(end + 1));
+    }
+    match std::str::from_utf8(payload) {
+        Ok(payload) => Decoded::Record(payload, end + 1),
+        Err(_) => Decoded::Corrupt(Some(end + 1)),
+    }
+}
+
+/// Outcome of verifying a log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogScan {
+    /// Payloads of the valid records before any damage, in order
+    pub records: Vec<String>,
+    /// Offset of an unterminated final record, if there is one
+    pub torn_tail: Option<u64>,
+    /// Offset of the first damaged record, if there is one. Records after it
+    /// are not read.
+    pub corrupt_at: Option<u64>,
+}
+
+/// Verify a log, stopping at the first damaged record
+pub fn scan(bytes: &[u8]) -> LogScan {
+    let mut scan = LogScan {
+        records: Vec::new(),
+        torn_tail: None,
+        corrupt_at: None,
+    };
+    let mut offset = 0;
+    while offset < bytes.len() {
+        match decode_at(bytes, offset) {
+            Decoded::Record(payload, next) => {
+                scan.records.push(payload.to_string());
+                offset = next;
+            },
+            Decoded::Blank(next) => offset = next,
+            Decoded::Torn => {
+                scan.torn_tail = Some(offset as u64);
+                break;
+            },
+            Decoded::Corrupt(_) => {
+                scan.corrupt_at = Some(offset as u64);
+                break;
+            },
+        }
+    }
+    scan
+}
+
+/// Records recovered from a damaged log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Salvage {
+    /// Payloads of every valid record, in order
+    pub records: Vec<String>,
+    /// Byte ranges that were dropped, including any torn tail
+    pub dropped: Vec<Range<u64>>,
+}
+
+/// Recover every valid record, skipping damaged lines
+pub fn salvage(bytes: &[u8]) -> Salvage {
+    let mut salvage = Salvage {
+        records: Vec::new(),
+        dropped: Vec::new(),
+    };
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let (next, damaged) = match decode_at(bytes, offset) {
+            Decoded::Record(payload, next) => {
+                salvage.records.push(payload.to_string());
+                (next, false)
+            },
+            Decoded::Blank(next) => (next, false),
+            Decoded::Torn => (bytes.len(), true),
+            Decoded::Corrupt(next) => (next.unwrap_or(bytes.len()), true),
+        };
+        if damaged {
+            // Merge neighbouring damaged lines into one range
+            match salvage.dropped.last_mut() {
+                Some(range) if range.end == offset as u64 => range.end = next as u64,
+                _ => salvage.dropped.push(offset as u64..next as u64),
+            }
+        }
+        offset = next;
+    }
+    salvage
+}
+
+/// Rewrite a damaged log file with only its valid records. The original is
+/// kept next to it as `updates.log.<timestamp>.corrupt`. A log without damage
+/// is left untouched. The log's offset index is dropped along with it.
+/// # Returns
+/// * `Ok(Salvage)` - What was kept and what was dropped
+/// * `Err(AppError)` if the log could not be read or rewritten
+pub async fn repair_update_log(path: &Path) -> Result<Salvage, AppError> {
+    let bytes = tokio_fs::read(path).await?;
+    let salvage = salvage(&bytes);
+    if salvage.dropped.is_empty() {
+        return Ok(salvage);
+    }
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(
+        ".{}.corrupt",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    write_atomic(Path::new(&backup), &bytes).await?;
+
+    let repaired: String = salvage
+        .records
+        .iter()
+        .map(|record| encode_record(record))
+        .collect();
+    write_atomic(path, repaired.as_bytes()).await?;
+    log_index::invalidate(path).await?;
+
+    Ok(salvage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(payloads: &[&str]) -> Vec<u8> {
+        payloads
+            .iter()
+            .map(|payload| encode_record(payload))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let bytes = log(&[r#"{"a":1}"#, r#"{"b":"é"}"#]);
+        let scan = scan(&bytes);
+        assert_eq!(scan.records, vec![r#"{"a":1}"#, r#"{"b":"é"}"#]);
+        assert_eq!(scan.torn_tail, None);
+        assert_eq!(scan.corrupt_at, None);
+    }
+
+    #[test]
+    fn test_unframed_records_are_accepted() {
+        let mut bytes = b"{\"old\":1}\n\n".to_vec();
+        bytes.extend(log(&[r#"{"new":2}"#]));
+        assert_eq!(scan(&bytes).records, vec![r#"{"old":1}"#, r#"{"new":2}"#]);
+    }
+
+    #[test]
+    fn test_torn_tail() {
+        let mut bytes = log(&["one", "two"]);
+        let full_len = bytes.len();
+        bytes.truncate(full_len - 3);
+
+        let scan = scan(&bytes);
+        assert_eq!(scan.records, vec!["one"]);
+        assert_eq!(scan.torn_tail, Some(encode_record("one").len() as u64));
+        assert_eq!(scan.corrupt_at, None);
+    }
+
+    #[test]
+    fn test_flipped_byte_is_corruption() {
+        let mut bytes = log(&["one", "two", "three"]);
+        let second = encode_record("one").len();
+        bytes[second + HEADER_LEN] ^= 0x01;
+
+        let scan = scan(&bytes);
+        assert_eq!(scan.records, vec!["one"]);
+        assert_eq!(scan.corrupt_at, Some(second as u64));
+    }
+
+    #[tokio::test]
+    async fn test_repair_update_log() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("updates.log");
+        let mut bytes = log(&["one", "two", "three"]);
+        bytes[encode_record("one").len() + 2] = b'x';
+        std::fs::write(&path, &bytes).unwrap();
+
+        let salvage = repair_update_log(&path).await.unwrap();
+        assert_eq!(salvage.records, vec!["one", "three"]);
+
+        let repaired = std::fs::read(&path).unwrap();
+        assert_eq!(scan(&repaired).records, vec!["one", "three"]);
+        assert_eq!(scan(&repaired).corrupt_at, None);
+        // The damaged original is kept
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        // A healthy log is not touched again
+        assert!(repair_update_log(&path).await.unwrap().dropped.is_empty());
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_salvage_skips_damaged_records() {
+        let mut bytes = log(&["one", "two", "three"]);
+        let second = encode_record("one").len();
+        let third = second + encode_record("two").len();
+        bytes[second + 2] = b'x';
+        bytes.extend_from_slice(b"0000000");
+
+        let salvage = salvage(&bytes);
+        assert_eq!(salvage.records, vec!["one", "three"]);
+        assert_eq!(
+            salvage.dropped,
+            vec![
+                second as u64..third as u64,
+                bytes.len() as u64 - 7..bytes.len() as u64
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+// crates/backend-lib/src/test_support.rs
+
+//! Fixtures shared by the unit tests
+use crate::config::{SessionBackend, Settings};
+use crate::storage::Storage;
+use crate::AppState;
+use tempfile::TempDir;
+
+/// Application state over `storage`, with in-memory sessions and a temporary
+/// data directory that lives as long as the returned `TempDir`
+/// # Arguments
+/// * `storage` - Meet storage, which may already hold meets
+/// * `configure` - Changes to the default settings
+pub async fn app_state<S: Storage + Clone + Send + Sync + 'static>(
+    storage: S,
+    configure: impl FnOnce(&mut Settings),
+) -> (AppState<S>, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let mut settings = Settings::default();
+    settings.storage.path = temp_dir.path().to_path_buf();
+    settings.sessions.backend = SessionBackend::Memory;
+    configure(&mut settings);
+
+    let state = AppState::new(storage, &settings).await.unwrap();
+    (state, temp_dir)
+}
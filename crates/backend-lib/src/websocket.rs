@@ -24,18 +24,30 @@ define the protocol between the client and server.
 When multiple clients update the same "location" (data entity), the handler
 resolves conflicts based on client priority levels, with higher priority updates
 taking precedence.*/
-
 use crate::{
-    messages::{ClientMessage, ServerMessage, Update, UpdateWithMetadata},
+    audit::{self, AuditEvent, AuditKind},
+    auth::{AuthzError, ConnectionAuthz},
+    error::AppError,
+    meet_actor::MeetHandle,
+    meet_registry::MeetStatus,
+    messages::{
+        ClientMessage, ServerMessage, Session, SessionRole, SessionSummary, Update,
+        UpdateWithMetadata,
+    },
+    metrics::{self as metric_names, LABEL_MEET_ID},
+    relay,
     storage::Storage,
     validation, AppState,
 };
 use anyhow::{anyhow, Result};
+use metrics::counter;
+use openlifter_common::{EndpointPriority, UpdateWithServerSeq};
 use serde_json;
 use std::{net::IpAddr, sync::Arc};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 use tokio::time::{self, Duration};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn, Span};
 use uuid::Uuid;
 /// Maximum number of reconnection attempts before giving up
 const MAX_RECONNECT_ATTEMPTS: u8 = 5;
@@ -53,6 +65,10 @@ pub struct WebSocketHandler<S> {
     client_tx: Option<mpsc::Sender<ServerMessage>>,
     client_priority: u8,
     reconnect_attempts: u8,
+    /// Relay forwarder for the meet this client is subscribed to
+    relay_task: Option<(String, JoinHandle<()>)>,
+    /// Session resolved for this connection
+    authz: ConnectionAuthz,
 }
 
 impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
@@ -64,12 +80,30 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
             client_priority: 0,
             reconnect_attempts: 0,
             client_ip: None,
+            relay_task: None,
+            authz: ConnectionAuthz::new(),
         }
     }
 
     /// Set client IP address
     pub fn set_client_ip(&mut self, ip: IpAddr) {
         self.client_ip = Some(ip);
+        self.authz.set_client_ip(ip);
+    }
+
+    /// Audit event from this client's address
+    fn audit_event(&self, kind: AuditKind) -> AuditEvent {
+        AuditEvent::new(kind).with_ip(self.client_ip)
+    }
+
+    /// Server-assigned identifier of this client
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Set the channel used to push messages to this client
+    pub fn set_client_tx(&mut self, tx: mpsc::Sender<ServerMessage>) {
+        self.client_tx = Some(tx);
     }
 
     // Register this client for a specific meet
@@ -77,12 +111,15 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
         // Store the client's transmission channel
         self.client_tx = Some(tx.clone());
 
-        // Add client to the clients map for the meet
+        // Add client to the clients map for the meet, once
         let mut meet_clients = self.state.clients.entry(meet_id.to_string()).or_default();
 
+        if meet_clients.iter().any(|c| c.same_channel(&tx)) {
+            return;
+        }
         meet_clients.push(tx);
 
-        println!("Client {} registered for meet {}", self.client_id, meet_id);
+        debug!("Client registered for meet {}", meet_id);
 
         // Reset reconnect attempts on successful registration
         self.reconnect_attempts = 0;
@@ -94,17 +131,96 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
     }
 
     // Unregister this client when disconnecting
-    pub fn unregister_client(&self, meet_id: &str) {
+    pub fn unregister_client(&mut self, meet_id: &str) {
+        self.stop_relay();
+
         if let Some(client_tx) = &self.client_tx {
             if let Some(mut clients) = self.state.clients.get_mut(meet_id) {
                 // Remove this client from the list
-                clients.retain(|tx| !std::ptr::eq(tx, client_tx));
-                println!(
-                    "Client {} unregistered from meet {}",
-                    self.client_id, meet_id
-                );
+                clients.retain(|tx| !tx.same_channel(client_tx));
+                debug!("Client unregistered from meet {}", meet_id);
+            }
+        }
+    }
+
+    /// Get the handle for a meet's actor, spawning the actor if needed
+    async fn meet_handle(&self, meet_id: &str) -> MeetHandle {
+        self.state.meet_handle(meet_id).await
+    }
+
+    /// Register this client for its session's meet and start relaying the
+    /// meet's updates to it. Does nothing beyond registration if the client
+    /// is already subscribed.
+    async fn subscribe(&mut self, session: &Session) {
+        let meet_id = session.meet_id.as_str();
+        // Fill in the connection span opened by the router
+        Span::current()
+            .record("meet_id", meet_id)
+            .record("location", session.location_name.as_str());
+        self.state
+            .connections
+            .bind_session(&self.client_id, session);
+
+        let Some(tx) = self.client_tx.clone() else {
+            return;
+        };
+        self.register_client(meet_id, tx.clone());
+
+        if let Some((subscribed, task)) = &self.relay_task {
+            if subscribed == meet_id && !task.is_finished() {
+                return;
             }
         }
+
+        let handle = self.meet_handle(meet_id).await;
+        self.start_relay(meet_id, handle.relay_tx.subscribe(), 0, tx);
+    }
+
+    /// Subscribe using a freshly issued session token
+    async fn subscribe_with_token(&mut self, session_token: &str) {
+        if let Ok(session) = self
+            .authz
+            .resolve(self.state.auth.as_ref(), session_token)
+            .await
+        {
+            let session = session.clone();
+            self.subscribe(&session).await;
+        }
+    }
+
+    /// Replace any running relay forwarder with one for `meet_id`
+    fn start_relay(
+        &mut self,
+        meet_id: &str,
+        relay_rx: broadcast::Receiver<UpdateWithServerSeq>,
+        skip_through: u64,
+        tx: mpsc::Sender<ServerMessage>,
+    ) {
+        self.stop_relay();
+
+        let task = relay::spawn_relay_forwarder(
+            meet_id.to_string(),
+            relay_rx,
+            tx,
+            self.client_id.clone(),
+            skip_through,
+            &self.state.settings.websocket,
+        );
+        self.relay_task = Some((meet_id.to_string(), task));
+    }
+
+    /// Meet this client is currently receiving relays for
+    pub fn subscribed_meet(&self) -> Option<&str> {
+        self.relay_task
+            .as_ref()
+            .map(|(meet_id, _)| meet_id.as_str())
+    }
+
+    /// Stop relaying meet updates to this client
+    fn stop_relay(&mut self) {
+        if let Some((_, task)) = self.relay_task.take() {
+            task.abort();
+        }
     }
 
     // Try to send a message to a client with retry logic
@@ -132,7 +248,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                     }
 
                     // Log the error
-                    println!("Error sending message, attempt {attempts}/{max_attempts}: {e}");
+                    warn!("Error sending message, attempt {attempts}/{max_attempts}: {e}");
 
                     // Wait before retrying with exponential backoff
                     time::sleep(Duration::from_millis(delay)).await;
@@ -210,7 +326,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
 
             // Log if many clients failed to receive the update
             if failed_clients > 0 {
-                println!("Warning: {failed_clients} clients failed to receive update");
+                warn!("{failed_clients} clients failed to receive update");
             }
         }
 
@@ -262,9 +378,9 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
         self.reconnect_attempts += 1;
 
         // Log reconnection attempt
-        println!(
-            "Attempting to reconnect client {} to meet {} (attempt {}/{})",
-            self.client_id, meet_id, self.reconnect_attempts, MAX_RECONNECT_ATTEMPTS
+        info!(
+            "Attempting to reconnect to meet {} (attempt {}/{})",
+            meet_id, self.reconnect_attempts, MAX_RECONNECT_ATTEMPTS
         );
 
         // Wait before reconnecting
@@ -278,10 +394,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
 
         if session_valid {
             // Session is still valid - we can recover
-            println!(
-                "Reconnection successful for client {} to meet {}",
-                self.client_id, meet_id
-            );
+            info!("Reconnection successful to meet {}", meet_id);
             return Ok(true);
         }
 
@@ -289,6 +402,381 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
         Err(anyhow!("Session is no longer valid"))
     }
 
+    /// Rebind this socket to an existing session after a reconnect.
+    /// Everything the client missed since `last_server_seq` is streamed to it
+    /// (or a snapshot, when it is too far behind) before live relays resume.
+    async fn handle_resume(
+        &mut self,
+        session_token: String,
+        last_server_seq: u64,
+    ) -> Result<ServerMessage> {
+        let session = self
+            .authz
+            .resolve(self.state.auth.as_ref(), &session_token)
+            .await
+            .ok()
+            .cloned();
+        let Some(session) = session else {
+            // If failed login, record it
+            if let Some(ip) = self.client_ip {
+                if let Some(auth) = self
+                    .state
+                    .auth
+                    .as_any()
+                    .downcast_ref::<crate::auth::DefaultAuth>()
+                {
+                    auth.record_failed_attempt(ip);
+                }
+            }
+            return Ok(ServerMessage::InvalidSession { session_token });
+        };
+
+        info!(
+            "Resuming session for meet {} at {} from seq {}",
+            session.meet_id, session.location_name, last_server_seq
+        );
+
+        // Restore the session's priority and meet binding
+        self.set_priority(session.priority);
+        self.reconnect_attempts = 0;
+        let meet_id = session.meet_id.clone();
+        self.state
+            .connections
+            .bind_session(&self.client_id, &session);
+
+        // Subscribe before reading the backlog so nothing committed in between is lost
+        let handle = self.meet_handle(&meet_id).await;
+        let relay_rx = handle.relay_tx.subscribe();
+
+        // One more than the threshold is enough to tell whether a snapshot is needed
+        let threshold = self.state.settings.websocket.resume_snapshot_threshold;
+        let missed = match handle
+            .get_update_page(last_server_seq, threshold.saturating_add(1))
+            .await
+        {
+            Ok(page) => page.updates,
+            Err(e) => {
+                return Ok(ServerMessage::Error {
+                    code: "PULL_ERROR".to_string(),
+                    message: e.to_string(),
+                });
+            },
+        };
+
+        let (catch_up, server_seq) = if missed.len() > threshold {
+            let (server_seq, state) = match handle.snapshot().await {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    return Ok(ServerMessage::Error {
+                        code: "PULL_ERROR".to_string(),
+                        message: e.to_string(),
+                    });
+                },
+            };
+            let snapshot = ServerMessage::MeetSnapshot {
+                meet_id: meet_id.clone(),
+                server_seq,
+                state,
+            };
+            (snapshot, server_seq)
+        } else {
+            let server_seq = missed.last().map_or(last_server_seq, |u| u.server_seq_num);
+            let pull = ServerMessage::ServerPull {
+                meet_id: meet_id.clone(),
+                last_server_seq,
+                updates_relayed: missed.iter().map(UpdateWithMetadata::from).collect(),
+                has_more: false,
+            };
+            (pull, server_seq)
+        };
+
+        if let Some(tx) = self.client_tx.clone() {
+            self.register_client(&meet_id, tx.clone());
+
+            // The catch-up must reach the client before any live relay does
+            tx.send(catch_up)
+                .await
+                .map_err(|e| anyhow!("Failed to send catch-up to client: {}", e))?;
+            self.start_relay(&meet_id, relay_rx, server_seq, tx);
+        }
+
+        Ok(ServerMessage::Resumed {
+            meet_id,
+            location_name: session.location_name,
+            priority: session.priority,
+            server_seq,
+        })
+    }
+
+    /// End the session used by this connection
+    async fn handle_logout(&mut self, session_token: String) -> Result<ServerMessage> {
+        let Ok(session) = self
+            .authz
+            .resolve(self.state.auth.as_ref(), &session_token)
+            .await
+            .cloned()
+        else {
+            return Ok(ServerMessage::InvalidSession { session_token });
+        };
+
+        info!(
+            "Logging out {} from meet {}",
+            session.location_name, session.meet_id
+        );
+        self.state.auth.remove_session(&session_token).await;
+        self.authz.clear();
+
+        // Keep the socket open but stop treating it as part of the meet
+        self.unregister_client(&session.meet_id);
+        self.state.connections.unbind_session(&self.client_id);
+
+        // Any other socket still using the session goes away with it
+        self.state.connections.disconnect_session(
+            &session_token,
+            &ServerMessage::SessionRevoked {
+                meet_id: session.meet_id.clone(),
+                reason: "Session logged out".to_string(),
+            },
+        );
+
+        Ok(ServerMessage::LoggedOut {
+            meet_id: session.meet_id,
+        })
+    }
+
+    /// Replace the token of the session used by this connection
+    async fn handle_rotate_session(&mut self, session_token: String) -> Result<ServerMessage> {
+        if self
+            .authz
+            .resolve(self.state.auth.as_ref(), &session_token)
+            .await
+            .is_err()
+        {
+            return Ok(ServerMessage::InvalidSession { session_token });
+        }
+
+        let Some(new_token) = self.state.auth.rotate_session(&session_token).await else {
+            return Ok(ServerMessage::InvalidSession { session_token });
+        };
+
+        // Re-resolve so the cache and the connection listing carry the new token
+        let Ok(session) = self
+            .authz
+            .resolve(self.state.auth.as_ref(), &new_token)
+            .await
+            .cloned()
+        else {
+            return Ok(ServerMessage::InvalidSession {
+                session_token: new_token,
+            });
+        };
+        self.state
+            .connections
+            .bind_session(&self.client_id, &session);
+
+        Ok(ServerMessage::SessionRotated {
+            meet_id: session.meet_id,
+            session_token: new_token,
+        })
+    }
+
+    /// Resolve a director's session for a session management request
+    async fn require_director(
+        &mut self,
+        meet_id: &str,
+        session_token: &str,
+    ) -> std::result::Result<Session, ServerMessage> {
+        let session = match self
+            .authz
+            .authorize(self.state.auth.as_ref(), meet_id, session_token)
+            .await
+        {
+            Ok(session) => session.clone(),
+            Err(AuthzError::InvalidSession) => {
+                return Err(ServerMessage::InvalidSession {
+                    session_token: session_token.to_string(),
+                });
+            },
+            Err(e @ AuthzError::WrongMeet) => {
+                return Err(ServerMessage::Forbidden {
+                    meet_id: meet_id.to_string(),
+                    reason: e.to_string(),
+                });
+            },
+        };
+
+        if session.role != SessionRole::Director {
+            audit::record(
+                self.audit_event(AuditKind::PermissionDenied)
+                    .with_meet(meet_id)
+                    .with_location(&session.location_name)
+                    .with_detail("Session management needs the director role"),
+            );
+            return Err(ServerMessage::Forbidden {
+                meet_id: meet_id.to_string(),
+                reason: "Only the meet director can manage sessions".to_string(),
+            });
+        }
+
+        Ok(session)
+    }
+
+    /// List the sessions of a meet for its director
+    async fn handle_list_sessions(
+        &mut self,
+        meet_id: String,
+        session_token: String,
+    ) -> Result<ServerMessage> {
+        if let Err(response) = self.require_director(&meet_id, &session_token).await {
+            return Ok(response);
+        }
+
+        let mut sessions: Vec<SessionSummary> = self
+            .state
+            .auth
+            .sessions_for_meet(&meet_id)
+            .await
+            .into_iter()
+            .map(|session| SessionSummary {
+                connections: self.state.connections.count_for_session(&session.token),
+                location_name: session.location_name,
+                priority: session.priority,
+                role: session.role,
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.location_name.cmp(&b.location_name));
+
+        Ok(ServerMessage::SessionList { meet_id, sessions })
+    }
+
+    /// Revoke sessions of a meet on behalf of its director and close their sockets
+    async fn handle_revoke_session(
+        &mut self,
+        meet_id: String,
+        session_token: String,
+        location_name: Option<String>,
+        target_token: Option<String>,
+    ) -> Result<ServerMessage> {
+        let director = match self.require_director(&meet_id, &session_token).await {
+            Ok(session) => session,
+            Err(response) => return Ok(response),
+        };
+
+        // The director never revokes their own session this way
+        let targets: Vec<Session> = self
+            .state
+            .auth
+            .sessions_for_meet(&meet_id)
+            .await
+            .into_iter()
+            .filter(|s| s.token != director.token)
+            .filter(|s| {
+                location_name.as_deref() == Some(s.location_name.as_str())
+                    || target_token.as_deref() == Some(s.token.as_str())
+            })
+            .collect();
+
+        let notice = ServerMessage::SessionRevoked {
+            meet_id: meet_id.clone(),
+            reason: "Session revoked by the meet director".to_string(),
+        };
+        for target in &targets {
+            self.state.auth.remove_session(&target.token).await;
+            let closed = self
+                .state
+                .connections
+                .disconnect_session(&target.token, &notice);
+            info!(
+                "Revoked session for {} in meet {} ({} connections closed)",
+                target.location_name, meet_id, closed
+            );
+        }
+
+        Ok(ServerMessage::SessionsRevoked {
+            meet_id,
+            revoked: targets.len(),
+        })
+    }
+
+    /// Replace a meet's password on behalf of its director and sign out every other session
+    async fn handle_change_meet_password(
+        &mut self,
+        meet_id: String,
+        session_token: String,
+        old_password: String,
+        new_password: String,
+    ) -> Result<ServerMessage> {
+        let director = match self.require_director(&meet_id, &session_token).await {
+            Ok(session) => session,
+            Err(response) => return Ok(response),
+        };
+
+        let info = match self.state.storage.get_meet_info(&meet_id).await {
+            Ok(info) => info,
+            Err(AppError::MeetNotFound) => {
+                return Ok(ServerMessage::Error {
+                    code: "MEET_NOT_FOUND".to_string(),
+                    message: "No password is stored for this meet".to_string(),
+                });
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        if !crate::auth::verify_password(&info.password_hash, &old_password) {
+            audit::record(
+                self.audit_event(AuditKind::LoginFailed)
+                    .with_meet(&meet_id)
+                    .with_location(&director.location_name)
+                    .with_detail("Incorrect meet password given to change it"),
+            );
+            if let Some(ip) = self.client_ip {
+                self.state.auth_rate_limiter.record_failed_attempt(ip);
+            }
+            return Ok(ServerMessage::Error {
+                code: "INVALID_CREDENTIALS".to_string(),
+                message: "Incorrect meet password".to_string(),
+            });
+        }
+
+        let password_hash = crate::auth::hash_password(&new_password)?;
+        self.state
+            .storage
+            .store_meet_info(&meet_id, &password_hash, &info.endpoints)
+            .await?;
+
+        // Everyone who got in with the old password has to join again
+        let removed = self
+            .state
+            .sessions
+            .remove_sessions_for_meet(&meet_id, &session_token)
+            .await;
+        let notice = ServerMessage::SessionRevoked {
+            meet_id: meet_id.clone(),
+            reason: "The meet password was changed; join again with the new password".to_string(),
+        };
+        for session in &removed {
+            self.state
+                .connections
+                .disconnect_session(&session.token, &notice);
+        }
+        audit::record(
+            self.audit_event(AuditKind::PasswordChanged)
+                .with_meet(&meet_id)
+                .with_location(&director.location_name)
+                .with_detail(format!("{} sessions invalidated", removed.len())),
+        );
+        info!(
+            "Password changed for meet {}; {} sessions invalidated",
+            meet_id,
+            removed.len()
+        );
+
+        Ok(ServerMessage::MeetPasswordChanged {
+            meet_id,
+            sessions_invalidated: removed.len(),
+        })
+    }
+
     /// Initiate state recovery for a meet
     /// This method is called when the server detects a state inconsistency
     /// or after restart. It broadcasts a request to all connected clients
@@ -333,24 +821,13 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
             });
         }
 
-        println!(
-            "Processing state recovery response from client {} with {} updates",
-            self.client_id,
+        info!(
+            "Processing state recovery response with {} updates",
             updates.len()
         );
 
-        // Get handle to the meet actor using if let instead of match
-        let meet_handle = if let Some(handle) = self.state.meet_handles.get(meet_id) {
-            handle.clone()
-        } else {
-            // Create a new meet actor if one doesn't exist
-            let storage = self.state.storage.clone();
-            let handle = crate::meet_actor::spawn_meet_actor(meet_id, storage).await;
-            self.state
-                .meet_handles
-                .insert(meet_id.to_string(), handle.clone());
-            handle
-        };
+        // Get handle to the meet actor
+        let meet_handle = self.meet_handle(meet_id).await;
 
         // Process the recovery updates
         let (new_seq, updates_recovered) = match meet_handle
@@ -387,6 +864,10 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
     /// - `ClientPull`: Request updates from the server since a specific sequence number
     /// - `PublishMeet`: Publish meet results and generate CSV output
     /// - `StateRecoveryResponse`: Handle state recovery responses
+    /// - `Resume`: Rebind a new socket to an existing session
+    /// - `Logout` / `RotateSession`: End or re-key the current session
+    /// - `ListSessions` / `RevokeSession`: Director-only session management
+    /// - `ChangeMeetPassword`: Director-only password change that signs out other sessions
     ///
     /// # Network Resilience
     /// If a message arrives with an invalid session token (e.g., after a network
@@ -425,39 +906,34 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
             });
         }
 
+        // Resolve the session once and hold it to the meet it was issued for.
+        // Invalid sessions are left to the individual handlers, which may
+        // attempt to reconnect.
+        if let Some((meet_id, session_token)) = msg.session_scope() {
+            if let Err(AuthzError::WrongMeet) = self
+                .authz
+                .authorize(self.state.auth.as_ref(), meet_id, session_token)
+                .await
+            {
+                return Ok(ServerMessage::Forbidden {
+                    meet_id: meet_id.to_string(),
+                    reason: AuthzError::WrongMeet.to_string(),
+                });
+            }
+        }
+
         // Process the message based on its type
         match msg {
             ClientMessage::CreateMeet {
-                meet_id,
                 password,
                 location_name,
                 priority,
             } => {
-                info!("Creating meet: {}", meet_id);
                 debug!(
-                    "Creating meet '{}' with location '{}' at priority {}",
-                    meet_id, location_name, priority
+                    "Creating meet with location '{}' at priority {}",
+                    location_name, priority
                 );
 
-                // Validate inputs
-                let meet_id = match crate::validation::validate_meet_id(&meet_id) {
-                    Ok(id) => id,
-                    Err(e) => {
-                        return Ok(ServerMessage::Error {
-                            code: "INVALID_MEET_ID".to_string(),
-                            message: e.to_string(),
-                        });
-                    },
-                };
-
-                // Check meet ID uniqueness
-                if !crate::validation::is_meet_id_unique(meet_id) {
-                    return Ok(ServerMessage::Error {
-                        code: "MEET_ID_EXISTS".to_string(),
-                        message: "Meet ID already exists".to_string(),
-                    });
-                }
-
                 // Validate password
                 match crate::validation::validate_password(&password) {
                     Ok(_) => {},
@@ -490,7 +966,12 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                         .downcast_ref::<crate::auth::DefaultAuth>()
                     {
                         if auth.check_auth_rate_limit(ip).is_err() {
-                            println!("Auth rate limit exceeded for IP {ip}");
+                            warn!("Auth rate limit exceeded for IP {ip}");
+                            audit::record(
+                                self.audit_event(AuditKind::LoginFailed)
+                                    .with_location(&location_name)
+                                    .with_detail("Meet creation refused while locked out"),
+                            );
                             return Ok(ServerMessage::Error {
                                 code: "AUTH_RATE_LIMITED".to_string(),
                                 message:
@@ -504,19 +985,70 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                     }
                 }
 
-                // Register the meet ID as used
-                crate::validation::register_meet_id(meet_id);
+                // Keep the password hash so joins and password changes can be checked
+                let password_hash = crate::auth::hash_password(&password)?;
+
+                // Allocate the meet ID; clients never choose their own
+                let allocated = crate::meet_id::allocate_meet_id(self.state.meets.as_ref()).await;
+                let meet_id = match allocated {
+                    Ok(id) => id,
+                    Err(e) => {
+                        error!("Failed to allocate a meet ID: {}", e);
+                        return Ok(ServerMessage::Error {
+                            code: "STORAGE_ERROR".to_string(),
+                            message: "Failed to create meet".to_string(),
+                        });
+                    },
+                };
+                let meet_id = meet_id.as_str();
+                info!("Creating meet: {}", meet_id);
+
+                let endpoints = [EndpointPriority {
+                    location_name: location_name.clone(),
+                    priority,
+                }];
+                if let Err(e) = self
+                    .state
+                    .storage
+                    .store_meet_info(meet_id, &password_hash, &endpoints)
+                    .await
+                {
+                    error!("Failed to store meet info for {}: {}", meet_id, e);
+                    // The meet was never set up, so its ID can go back in the pool
+                    if let Err(e) = self.state.meets.release(meet_id).await {
+                        error!("Failed to release meet ID {}: {}", meet_id, e);
+                    }
+                    return Ok(ServerMessage::Error {
+                        code: "STORAGE_ERROR".to_string(),
+                        message: "Failed to create meet".to_string(),
+                    });
+                }
 
                 // Set client priority
                 self.set_priority(priority);
 
-                // Handle meet creation
+                audit::record(
+                    self.audit_event(AuditKind::MeetCreated)
+                        .with_meet(meet_id)
+                        .with_location(&location_name),
+                );
+
+                // Handle meet creation; the creator directs the meet
                 let session = self
                     .state
                     .auth
-                    .new_session(meet_id.to_string(), location_name, priority)
+                    .new_session_with_role(
+                        meet_id.to_string(),
+                        location_name,
+                        priority,
+                        SessionRole::Director,
+                    )
                     .await;
 
+                // Start receiving updates for the new meet
+                self.subscribe_with_token(&session).await;
+                counter!(metric_names::MEET_CREATED).increment(1);
+
                 // Return create response
                 Ok(ServerMessage::MeetCreated {
                     meet_id: meet_id.to_string(),
@@ -578,7 +1110,13 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                         .downcast_ref::<crate::auth::DefaultAuth>()
                     {
                         if auth.check_auth_rate_limit(ip).is_err() {
-                            println!("Auth rate limit exceeded for IP {ip}");
+                            warn!("Auth rate limit exceeded for IP {ip}");
+                            audit::record(
+                                self.audit_event(AuditKind::LoginFailed)
+                                    .with_meet(meet_id)
+                                    .with_location(&location_name)
+                                    .with_detail("Join refused while locked out"),
+                            );
                             return Ok(ServerMessage::Error {
                                 code: "AUTH_RATE_LIMITED".to_string(),
                                 message:
@@ -586,25 +1124,87 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                                         .to_string(),
                             });
                         }
+                    }
+                }
 
-                        // Record success
-                        auth.record_success(ip);
+                // Only live meets can be joined
+                match self.state.meets.get(meet_id).await {
+                    Some(record) if record.status == MeetStatus::Current => {},
+                    Some(_) => {
+                        audit::record(
+                            self.audit_event(AuditKind::LoginFailed)
+                                .with_meet(meet_id)
+                                .with_location(&location_name)
+                                .with_detail("Meet has finished"),
+                        );
+                        return Ok(ServerMessage::Error {
+                            code: "MEET_FINISHED".to_string(),
+                            message: "This meet has finished".to_string(),
+                        });
+                    },
+                    None => {
+                        audit::record(
+                            self.audit_event(AuditKind::LoginFailed)
+                                .with_meet(meet_id)
+                                .with_location(&location_name)
+                                .with_detail("No such meet"),
+                        );
+                        return Ok(ServerMessage::Error {
+                            code: "MEET_NOT_FOUND".to_string(),
+                            message: "No meet with this ID exists".to_string(),
+                        });
+                    },
+                }
+
+                // Check the password against the hash stored when the meet was created.
+                // A meet without stored info has nothing to check against, so it
+                // cannot be joined at all.
+                let refusal = match self.state.storage.get_meet_info(meet_id).await {
+                    Ok(info) if crate::auth::verify_password(&info.password_hash, &password) => {
+                        None
+                    },
+                    Ok(_) => Some("Incorrect meet password"),
+                    Err(AppError::MeetNotFound) => Some("No password is stored for the meet"),
+                    Err(e) => return Err(e.into()),
+                };
+                if let Some(detail) = refusal {
+                    audit::record(
+                        self.audit_event(AuditKind::LoginFailed)
+                            .with_meet(meet_id)
+                            .with_location(&location_name)
+                            .with_detail(detail),
+                    );
+                    if let Some(ip) = self.client_ip {
+                        self.state.auth_rate_limiter.record_failed_attempt(ip);
                     }
+                    return Ok(ServerMessage::Error {
+                        code: "INVALID_CREDENTIALS".to_string(),
+                        message: "Incorrect meet password".to_string(),
+                    });
+                }
+                if let Some(ip) = self.client_ip {
+                    self.state.auth_rate_limiter.record_success(ip);
                 }
+                audit::record(
+                    self.audit_event(AuditKind::LoginSucceeded)
+                        .with_meet(meet_id)
+                        .with_location(&location_name),
+                );
 
                 // Set client priority
                 self.set_priority(priority);
 
-                // Check if the meet exists and the password is correct
-                // In a real implementation, this would verify against stored data
-
-                // For now, always accept the join request
                 let session = self
                     .state
                     .auth
                     .new_session(meet_id.to_string(), location_name, priority)
                     .await;
 
+                // Start receiving updates for the joined meet
+                self.subscribe_with_token(&session).await;
+                counter!(metric_names::MEET_JOINED, LABEL_MEET_ID => meet_id.to_string())
+                    .increment(1);
+
                 // Return join response
                 Ok(ServerMessage::MeetJoined {
                     meet_id: meet_id.to_string(),
@@ -719,22 +1319,12 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                 }
 
                 // Get session to retrieve priority
-                if let Some(session) = self.state.auth.get_session(&session_token).await {
+                if let Some(session) = self.authz.session(&session_token).cloned() {
                     // Update client priority from session
                     self.set_priority(session.priority);
 
-                    // Get handle to the meet actor using if let instead of unwrap
-                    let meet_handle = if let Some(handle) = self.state.meet_handles.get(&meet_id) {
-                        handle.clone()
-                    } else {
-                        // Create a new meet actor if one doesn't exist
-                        let storage = self.state.storage.clone();
-                        let handle = crate::meet_actor::spawn_meet_actor(&meet_id, storage).await;
-                        self.state
-                            .meet_handles
-                            .insert(meet_id.clone(), handle.clone());
-                        handle
-                    };
+                    // Get handle to the meet actor
+                    let meet_handle = self.meet_handle(&meet_id).await;
 
                     // Create openlifter_common::Update from our messages::Update
                     let ol_updates = valid_updates
@@ -755,9 +1345,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                     {
                         Ok(update_acks) => {
                             // Register client for this meet if not already
-                            if let Some(tx) = &self.client_tx {
-                                self.register_client(&meet_id, tx.clone());
-                            }
+                            self.subscribe(&session).await;
 
                             // Convert to a format expected by UpdateAck
                             let update_ids =
@@ -776,7 +1364,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                             } = e
                             {
                                 // Automatically initiate state recovery
-                                println!(
+                                info!(
                                     "State recovery needed for meet {meet_id}: last_known_seq={last_known_seq}"
                                 );
 
@@ -843,34 +1431,19 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
 
                 if self.state.auth.validate_session(&session_token).await {
                     // Get session to retrieve priority
-                    if let Some(_session) = self.state.auth.get_session(&session_token).await {
-                        // Get handle to the meet actor
-                        let updates = if let Some(handle) = self.state.meet_handles.get(&meet_id) {
-                            // Get updates since last_server_seq
-                            match handle.get_updates_since(last_server_seq).await {
-                                Ok(updates) => {
+                    if let Some(session) = self.authz.session(&session_token).cloned() {
+                        // Get handle to the meet actor, loading the meet if
+                        // its actor was stopped or never started
+                        let page_size = self.state.settings.websocket.pull_page_size;
+                        let handle = self.meet_handle(&meet_id).await;
+                        // Get one page of updates since last_server_seq
+                        let (updates, has_more) =
+                            match handle.get_update_page(last_server_seq, page_size).await {
+                                Ok(page) => {
                                     // Convert UpdateWithServerSeq to UpdateWithMetadata
-                                    let updates_with_metadata: Vec<UpdateWithMetadata> = updates
-                                        .iter()
-                                        .map(|u| {
-                                            let update = Update {
-                                                location: u.update.update_key.clone(),
-                                                value: u.update.update_value.to_string(),
-                                                #[allow(
-                                                    clippy::cast_possible_wrap,
-                                                    clippy::cast_sign_loss
-                                                )]
-                                                timestamp: u.update.local_seq_num as i64,
-                                            };
-                                            UpdateWithMetadata {
-                                                update,
-                                                source_client: u.source_client_id.clone(),
-                                                server_seq: u.server_seq_num,
-                                                priority: u.source_client_priority,
-                                            }
-                                        })
-                                        .collect();
-                                    updates_with_metadata
+                                    let updates =
+                                        page.updates.iter().map(UpdateWithMetadata::from).collect();
+                                    (updates, page.has_more())
                                 },
                                 Err(e) => {
                                     return Ok(ServerMessage::Error {
@@ -878,22 +1451,17 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                                         message: e.to_string(),
                                     });
                                 },
-                            }
-                        } else {
-                            // Meet does not exist yet (no updates)
-                            Vec::new()
-                        };
+                            };
 
                         // Register client for this meet if not already
-                        if let Some(tx) = &self.client_tx {
-                            self.register_client(&meet_id, tx.clone());
-                        }
+                        self.subscribe(&session).await;
 
                         // Return updates
                         Ok(ServerMessage::ServerPull {
                             meet_id,
                             last_server_seq,
                             updates_relayed: updates,
+                            has_more,
                         })
                     } else {
                         // Session not found but token was valid (should not happen)
@@ -986,7 +1554,7 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
 
                 if self.state.auth.validate_session(&session_token).await {
                     // TODO: Implement meet publishing
-                    println!(
+                    info!(
                         "Publishing meet {meet_id} with return email {return_email} (CSV length: {})",
                         sanitized_csv.len()
                     );
@@ -1060,6 +1628,44 @@ impl<S: Storage + Send + Sync + Clone + 'static> WebSocketHandler<S> {
                 )
                 .await
             },
+            ClientMessage::Resume {
+                session_token,
+                last_server_seq,
+            } => self.handle_resume(session_token, last_server_seq).await,
+            ClientMessage::Logout { session_token } => self.handle_logout(session_token).await,
+            ClientMessage::RotateSession { session_token } => {
+                self.handle_rotate_session(session_token).await
+            },
+            ClientMessage::ListSessions {
+                meet_id,
+                session_token,
+            } => self.handle_list_sessions(meet_id, session_token).await,
+            ClientMessage::RevokeSession {
+                meet_id,
+                session_token,
+                location_name,
+                target_token,
+            } => {
+                self.handle_revoke_session(meet_id, session_token, location_name, target_token)
+                    .await
+            },
+            ClientMessage::ChangeMeetPassword {
+                meet_id,
+                session_token,
+                old_password,
+                new_password,
+            } => {
+                self.handle_change_meet_password(meet_id, session_token, old_password, new_password)
+                    .await
+            },
+        }
+    }
+}
+
+impl<S> Drop for WebSocketHandler<S> {
+    fn drop(&mut self) {
+        if let Some((_, task)) = self.relay_task.take() {
+            task.abort();
         }
     }
 }
@@ -1073,16 +1679,36 @@ mod tests {
     use tempfile::TempDir;
     use tokio::sync::mpsc;
 
-    async fn setup() -> (
-        WebSocketHandler<FlatFileStorage>,
+    /// Register a current meet with the password tests join it with
+    async fn register_meet<S: Storage>(state: &AppState<S>, meet_id: &str) {
+        state.meets.register(meet_id).await.unwrap();
+        let hash = crate::auth::hash_password("Password123!").unwrap();
+        state
+            .storage
+            .store_meet_info(meet_id, &hash, &[])
+            .await
+            .unwrap();
+    }
+
+    async fn setup() -> (
+        WebSocketHandler<FlatFileStorage>,
+        Arc<AppState<FlatFileStorage>>,
+        TempDir,
+    ) {
+        setup_with_settings(crate::config::Settings::default()).await
+    }
+
+    async fn setup_with_settings(
+        mut settings: crate::config::Settings,
+    ) -> (
+        WebSocketHandler<FlatFileStorage>,
         Arc<AppState<FlatFileStorage>>,
         TempDir,
     ) {
         let temp_dir = TempDir::new().unwrap();
         let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
 
-        // Create settings with the temp directory path
-        let mut settings = crate::config::Settings::default();
+        // Point settings at the temp directory path
         settings.storage.path = temp_dir.path().to_path_buf();
 
         // Ensure the sessions directory exists
@@ -1090,11 +1716,13 @@ mod tests {
         std::fs::create_dir_all(&sessions_dir).expect("Failed to create sessions directory");
 
         // Create app state with proper error handling
-        let state = Arc::new(
-            AppState::new(storage.clone(), &settings)
-                .await
-                .expect("Failed to create AppState for test"),
-        );
+        let mut state = AppState::new(storage.clone(), &settings)
+            .await
+            .expect("Failed to create AppState for test");
+
+        // Keep meets in memory so tests can add them without creating them
+        state.meets = Arc::new(crate::meet_registry::InMemoryMeetRegistry::new());
+        let state = Arc::new(state);
 
         // Create handler
         let handler = WebSocketHandler::new(state.clone());
@@ -1135,12 +1763,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_create_meet() {
-        let (mut handler, _state, _temp_dir) = setup().await;
+        let (mut handler, state, _temp_dir) = setup().await;
 
         // Create a meet
         let result = handler
             .handle_message(ClientMessage::CreateMeet {
-                meet_id: "test-meet".to_string(),
                 password: "Password123!".to_string(),
                 location_name: "Test Location".to_string(),
                 priority: 3,
@@ -1154,16 +1781,85 @@ mod tests {
                 meet_id,
                 session_token,
             } => {
-                assert_eq!(meet_id, "test-meet");
+                // The server picks a `ddd-ddd-ddd` ID and registers it
+                assert_eq!(meet_id.len(), 11);
+                assert!(state.meets.meet_exists(&meet_id).await);
                 assert!(!session_token.is_empty());
             },
             other => panic!("Expected MeetCreated, got {other:?}"),
         }
     }
 
+    #[tokio::test]
+    async fn test_join_unknown_or_finished_meet() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        let join = |meet_id: &str| ClientMessage::JoinMeet {
+            meet_id: meet_id.to_string(),
+            password: "Password123!".to_string(),
+            location_name: "Test Location".to_string(),
+            priority: 2,
+        };
+
+        match handler.handle_message(join("123-456-789")).await.unwrap() {
+            ServerMessage::Error { code, .. } => assert_eq!(code, "MEET_NOT_FOUND"),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+
+        state.meets.register("123-456-789").await.unwrap();
+        state.meets.finish("123-456-789").await.unwrap();
+        match handler.handle_message(join("123-456-789")).await.unwrap() {
+            ServerMessage::Error { code, .. } => assert_eq!(code, "MEET_FINISHED"),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_meet_without_stored_password() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        state.meets.register("test-meet").await.unwrap();
+
+        let result = handler
+            .handle_message(ClientMessage::JoinMeet {
+                meet_id: "test-meet".to_string(),
+                password: "Password123!".to_string(),
+                location_name: "Test Location".to_string(),
+                priority: 2,
+            })
+            .await
+            .unwrap();
+        match result {
+            ServerMessage::Error { code, .. } => assert_eq!(code, "INVALID_CREDENTIALS"),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+        assert!(state.auth.sessions_for_meet("test-meet").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_join_meet_lists_connection() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        register_meet(&state, "test-meet").await;
+        register_connection(&state, &handler);
+
+        handler
+            .handle_message(ClientMessage::JoinMeet {
+                meet_id: "test-meet".to_string(),
+                password: "Password123!".to_string(),
+                location_name: "Test Location".to_string(),
+                priority: 2,
+            })
+            .await
+            .unwrap();
+
+        let listed = state.connections.for_meet("test-meet");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].client_id, handler.client_id());
+        assert_eq!(listed[0].location_name.as_deref(), Some("Test Location"));
+    }
+
     #[tokio::test]
     async fn test_handle_join_meet() {
-        let (mut handler, _state, _temp_dir) = setup().await;
+        let (mut handler, state, _temp_dir) = setup().await;
+        register_meet(&state, "test-meet").await;
 
         // Join a meet
         let result = handler
@@ -1241,6 +1937,545 @@ mod tests {
         .expect("Test timed out");
     }
 
+    fn numbered_updates(range: std::ops::RangeInclusive<i64>) -> Vec<Update> {
+        range
+            .map(|n| Update {
+                location: format!("item{n}"),
+                value: serde_json::json!({ "n": n }).to_string(),
+                timestamp: n,
+            })
+            .collect()
+    }
+
+    async fn recv_message(rx: &mut mpsc::Receiver<ServerMessage>) -> ServerMessage {
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("Timed out waiting for message")
+            .expect("Channel closed")
+    }
+
+    #[tokio::test]
+    async fn test_resume_streams_missed_updates_then_relays() {
+        let (mut writer, state, _temp_dir) = setup().await;
+        let session = state
+            .auth
+            .new_session("test-meet".to_string(), "Scoring Table".to_string(), 4)
+            .await;
+
+        writer
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "test-meet".to_string(),
+                session_token: session.clone(),
+                updates: numbered_updates(1..=3),
+            })
+            .await
+            .unwrap();
+
+        // A new socket resumes the same session having seen only seq 1
+        let mut resumed = WebSocketHandler::new(state.clone());
+        let (tx, mut rx) = mpsc::channel::<ServerMessage>(10);
+        resumed.set_client_tx(tx);
+
+        let response = resumed
+            .handle_message(ClientMessage::Resume {
+                session_token: session.clone(),
+                last_server_seq: 1,
+            })
+            .await
+            .unwrap();
+        match response {
+            ServerMessage::Resumed {
+                meet_id,
+                location_name,
+                priority,
+                server_seq,
+            } => {
+                assert_eq!(meet_id, "test-meet");
+                assert_eq!(location_name, "Scoring Table");
+                assert_eq!(priority, 4);
+                assert_eq!(server_seq, 3);
+            },
+            other => panic!("Expected Resumed, got {other:?}"),
+        }
+        assert_eq!(resumed.subscribed_meet(), Some("test-meet"));
+
+        match recv_message(&mut rx).await {
+            ServerMessage::ServerPull {
+                updates_relayed, ..
+            } => {
+                let seqs: Vec<u64> = updates_relayed.iter().map(|u| u.server_seq).collect();
+                assert_eq!(seqs, vec![2, 3]);
+            },
+            other => panic!("Expected ServerPull, got {other:?}"),
+        }
+
+        // Live relays continue after the catch-up
+        writer
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "test-meet".to_string(),
+                session_token: session,
+                updates: numbered_updates(4..=4),
+            })
+            .await
+            .unwrap();
+        match recv_message(&mut rx).await {
+            ServerMessage::UpdateRelay { updates, .. } => {
+                assert_eq!(updates.len(), 1);
+                assert_eq!(updates[0].server_seq, 4);
+            },
+            other => panic!("Expected UpdateRelay, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_far_behind_gets_snapshot() {
+        let mut settings = crate::config::Settings::default();
+        settings.websocket.resume_snapshot_threshold = 2;
+        let (mut writer, state, _temp_dir) = setup_with_settings(settings).await;
+        let session = state
+            .auth
+            .new_session("test-meet".to_string(), "Scoring Table".to_string(), 1)
+            .await;
+
+        writer
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "test-meet".to_string(),
+                session_token: session.clone(),
+                updates: numbered_updates(1..=5),
+            })
+            .await
+            .unwrap();
+
+        let mut resumed = WebSocketHandler::new(state.clone());
+        let (tx, mut rx) = mpsc::channel::<ServerMessage>(10);
+        resumed.set_client_tx(tx);
+        resumed
+            .handle_message(ClientMessage::Resume {
+                session_token: session,
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+
+        match recv_message(&mut rx).await {
+            ServerMessage::MeetSnapshot {
+                server_seq, state, ..
+            } => {
+                assert_eq!(server_seq, 5);
+                assert_eq!(state["item3"], serde_json::json!({ "n": 3 }));
+            },
+            other => panic!("Expected MeetSnapshot, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_pull_is_paginated() {
+        let mut settings = crate::config::Settings::default();
+        settings.websocket.pull_page_size = 2;
+        let (mut handler, state, _temp_dir) = setup_with_settings(settings).await;
+        let session = state
+            .auth
+            .new_session("test-meet".to_string(), "Scoring Table".to_string(), 1)
+            .await;
+
+        handler
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "test-meet".to_string(),
+                session_token: session.clone(),
+                updates: numbered_updates(1..=5),
+            })
+            .await
+            .unwrap();
+
+        // Pull page by page until the server has nothing more
+        let mut last_server_seq = 0;
+        let mut pages = Vec::new();
+        loop {
+            let pull = handler
+                .handle_message(ClientMessage::ClientPull {
+                    meet_id: "test-meet".to_string(),
+                    session_token: session.clone(),
+                    last_server_seq,
+                })
+                .await
+                .unwrap();
+            let ServerMessage::ServerPull {
+                updates_relayed,
+                has_more,
+                ..
+            } = pull
+            else {
+                panic!("Expected ServerPull, got {pull:?}");
+            };
+            let seqs: Vec<u64> = updates_relayed.iter().map(|u| u.server_seq).collect();
+            last_server_seq = *seqs.last().unwrap();
+            pages.push(seqs);
+            if !has_more {
+                break;
+            }
+        }
+        assert_eq!(pages, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[tokio::test]
+    async fn test_pull_after_actor_stopped() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        let session = state
+            .auth
+            .new_session("test-meet".to_string(), "Scoring Table".to_string(), 1)
+            .await;
+        handler
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "test-meet".to_string(),
+                session_token: session.clone(),
+                updates: numbered_updates(1..=2),
+            })
+            .await
+            .unwrap();
+
+        // Stop the actor as an idle stop or an eviction would
+        let (_, handle) = state.meet_handles.remove("test-meet").unwrap();
+        assert!(handle.shutdown(false).await);
+
+        let pull = handler
+            .handle_message(ClientMessage::ClientPull {
+                meet_id: "test-meet".to_string(),
+                session_token: session,
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        let ServerMessage::ServerPull {
+            updates_relayed,
+            has_more,
+            ..
+        } = pull
+        else {
+            panic!("Expected ServerPull, got {pull:?}");
+        };
+        let seqs: Vec<u64> = updates_relayed.iter().map(|u| u.server_seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
+        assert!(!has_more);
+    }
+
+    #[tokio::test]
+    async fn test_session_cannot_cross_meets() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        let session = state
+            .auth
+            .new_session("meet-a".to_string(), "Scoring Table".to_string(), 1)
+            .await;
+
+        let write = handler
+            .handle_message(ClientMessage::UpdateInit {
+                meet_id: "meet-b".to_string(),
+                session_token: session.clone(),
+                updates: numbered_updates(1..=1),
+            })
+            .await
+            .unwrap();
+        match write {
+            ServerMessage::Forbidden { meet_id, .. } => assert_eq!(meet_id, "meet-b"),
+            other => panic!("Expected Forbidden, got {other:?}"),
+        }
+
+        let read = handler
+            .handle_message(ClientMessage::ClientPull {
+                meet_id: "meet-b".to_string(),
+                session_token: session.clone(),
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(read, ServerMessage::Forbidden { .. }));
+
+        // Nothing was written to meet B
+        assert!(!state.meet_handles.contains_key("meet-b"));
+
+        // The same session still works for its own meet
+        let own = handler
+            .handle_message(ClientMessage::ClientPull {
+                meet_id: "meet-a".to_string(),
+                session_token: session,
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(own, ServerMessage::ServerPull { .. }));
+    }
+
+    /// Record the handler's connection, returning its outgoing channel and close signal
+    fn register_connection(
+        state: &AppState<FlatFileStorage>,
+        handler: &WebSocketHandler<FlatFileStorage>,
+    ) -> (mpsc::Receiver<ServerMessage>, Arc<tokio::sync::Notify>) {
+        let (tx, rx) = mpsc::channel(8);
+        let close = Arc::new(tokio::sync::Notify::new());
+        state.connections.register(
+            handler.client_id(),
+            [127, 0, 0, 1].into(),
+            crate::connections::ConnectionControl {
+                tx,
+                close: close.clone(),
+            },
+        );
+        (rx, close)
+    }
+
+    async fn join(handler: &mut WebSocketHandler<FlatFileStorage>, meet_id: &str) -> String {
+        // Meets created through CreateMeet are already registered
+        register_meet(&handler.state, meet_id).await;
+        match handler
+            .handle_message(ClientMessage::JoinMeet {
+                meet_id: meet_id.to_string(),
+                password: "Password123!".to_string(),
+                location_name: "Marshal".to_string(),
+                priority: 2,
+            })
+            .await
+            .unwrap()
+        {
+            ServerMessage::MeetJoined { session_token, .. } => session_token,
+            other => panic!("Expected MeetJoined, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logout_ends_session() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        register_connection(&state, &handler);
+        let token = join(&mut handler, "logout-meet").await;
+
+        let response = handler
+            .handle_message(ClientMessage::Logout {
+                session_token: token.clone(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(response, ServerMessage::LoggedOut { .. }));
+        assert!(!state.auth.validate_session(&token).await);
+        assert!(handler.subscribed_meet().is_none());
+        assert!(state.connections.for_meet("logout-meet").is_empty());
+
+        let pull = handler
+            .handle_message(ClientMessage::ClientPull {
+                meet_id: "logout-meet".to_string(),
+                session_token: token,
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(pull, ServerMessage::InvalidSession { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_session() {
+        let (mut handler, state, _temp_dir) = setup().await;
+        let old_token = join(&mut handler, "rotate-meet").await;
+
+        let new_token = match handler
+            .handle_message(ClientMessage::RotateSession {
+                session_token: old_token.clone(),
+            })
+            .await
+            .unwrap()
+        {
+            ServerMessage::SessionRotated { session_token, .. } => session_token,
+            other => panic!("Expected SessionRotated, got {other:?}"),
+        };
+        assert_ne!(new_token, old_token);
+        assert!(!state.auth.validate_session(&old_token).await);
+
+        let pull = handler
+            .handle_message(ClientMessage::ClientPull {
+                meet_id: "rotate-meet".to_string(),
+                session_token: new_token,
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(pull, ServerMessage::ServerPull { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_director_revokes_station() {
+        let (mut director, state, _temp_dir) = setup().await;
+        let director_token = state
+            .auth
+            .new_session_with_role(
+                "revoke-meet".to_string(),
+                "Director".to_string(),
+                1,
+                SessionRole::Director,
+            )
+            .await;
+
+        let mut station = WebSocketHandler::new(state.clone());
+        let (mut station_rx, station_close) = register_connection(&state, &station);
+        let station_token = join(&mut station, "revoke-meet").await;
+
+        // Stations cannot manage sessions
+        let denied = station
+            .handle_message(ClientMessage::ListSessions {
+                meet_id: "revoke-meet".to_string(),
+                session_token: station_token.clone(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(denied, ServerMessage::Forbidden { .. }));
+
+        match director
+            .handle_message(ClientMessage::ListSessions {
+                meet_id: "revoke-meet".to_string(),
+                session_token: director_token.clone(),
+            })
+            .await
+            .unwrap()
+        {
+            ServerMessage::SessionList { sessions, .. } => {
+                assert_eq!(sessions.len(), 2);
+                let marshal = sessions
+                    .iter()
+                    .find(|s| s.location_name == "Marshal")
+                    .unwrap();
+                assert_eq!(marshal.role, SessionRole::Station);
+                assert_eq!(marshal.connections, 1);
+            },
+            other => panic!("Expected SessionList, got {other:?}"),
+        }
+
+        let revoked = director
+            .handle_message(ClientMessage::RevokeSession {
+                meet_id: "revoke-meet".to_string(),
+                session_token: director_token.clone(),
+                location_name: Some("Marshal".to_string()),
+                target_token: None,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            revoked,
+            ServerMessage::SessionsRevoked { revoked: 1, .. }
+        ));
+
+        // The station's socket is told why and asked to close
+        assert!(matches!(
+            recv_message(&mut station_rx).await,
+            ServerMessage::SessionRevoked { .. }
+        ));
+        station_close.notified().await;
+        assert!(!state.auth.validate_session(&station_token).await);
+        assert!(state.auth.validate_session(&director_token).await);
+    }
+
+    #[tokio::test]
+    async fn test_change_meet_password() {
+        let (mut director, state, _temp_dir) = setup().await;
+        let (meet_id, director_token) = match director
+            .handle_message(ClientMessage::CreateMeet {
+                password: "Password123!".to_string(),
+                location_name: "Director".to_string(),
+                priority: 1,
+            })
+            .await
+            .unwrap()
+        {
+            ServerMessage::MeetCreated {
+                meet_id,
+                session_token,
+            } => (meet_id, session_token),
+            other => panic!("Expected MeetCreated, got {other:?}"),
+        };
+
+        let mut station = WebSocketHandler::new(state.clone());
+        let (mut station_rx, station_close) = register_connection(&state, &station);
+        let station_token = join(&mut station, &meet_id).await;
+
+        let change = |session_token: &str, old_password: &str| ClientMessage::ChangeMeetPassword {
+            meet_id: meet_id.clone(),
+            session_token: session_token.to_string(),
+            old_password: old_password.to_string(),
+            new_password: "Whiteboard#2024".to_string(),
+        };
+
+        // Only the director may change it, and only with the current password
+        let denied = station
+            .handle_message(change(&station_token, "Password123!"))
+            .await
+            .unwrap();
+        assert!(matches!(denied, ServerMessage::Forbidden { .. }));
+        match director
+            .handle_message(change(&director_token, "Wrong-Password1"))
+            .await
+            .unwrap()
+        {
+            ServerMessage::Error { code, .. } => assert_eq!(code, "INVALID_CREDENTIALS"),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+
+        let changed = director
+            .handle_message(change(&director_token, "Password123!"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            changed,
+            ServerMessage::MeetPasswordChanged {
+                sessions_invalidated: 1,
+                ..
+            }
+        ));
+
+        // The station is signed out and told why; the director stays in
+        assert!(matches!(
+            recv_message(&mut station_rx).await,
+            ServerMessage::SessionRevoked { .. }
+        ));
+        station_close.notified().await;
+        assert!(!state.auth.validate_session(&station_token).await);
+        assert!(state.auth.validate_session(&director_token).await);
+
+        // Joining again needs the new password
+        let mut rejoin = WebSocketHandler::new(state.clone());
+        match rejoin
+            .handle_message(ClientMessage::JoinMeet {
+                meet_id: meet_id.clone(),
+                password: "Password123!".to_string(),
+                location_name: "Marshal".to_string(),
+                priority: 2,
+            })
+            .await
+            .unwrap()
+        {
+            ServerMessage::Error { code, .. } => assert_eq!(code, "INVALID_CREDENTIALS"),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+        let rejoined = rejoin
+            .handle_message(ClientMessage::JoinMeet {
+                meet_id: meet_id.clone(),
+                password: "Whiteboard#2024".to_string(),
+                location_name: "Marshal".to_string(),
+                priority: 2,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(rejoined, ServerMessage::MeetJoined { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_resume_with_unknown_session() {
+        let (mut handler, _state, _temp_dir) = setup().await;
+
+        let response = handler
+            .handle_message(ClientMessage::Resume {
+                session_token: "not-a-session".to_string(),
+                last_server_seq: 0,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(response, ServerMessage::InvalidSession { .. }));
+        assert!(handler.subscribed_meet().is_none());
+    }
+
     #[tokio::test]
     async fn test_handle_invalid_session() {
         // We need to extract all three elements from setup
@@ -1326,10 +2561,12 @@ mod tests {
                     meet_id,
                     last_server_seq,
                     updates_relayed,
+                    has_more,
                 } => {
                     assert_eq!(meet_id, "test-meet");
                     assert_eq!(last_server_seq, 0);
                     assert!(updates_relayed.is_empty());
+                    assert!(!has_more);
                 },
                 other => panic!("Expected ServerPull, got {other:?}"),
             }
@@ -1491,7 +2728,6 @@ mod tests {
             // Create a meet first
             let create_result = handler
                 .handle_message(ClientMessage::CreateMeet {
-                    meet_id: "recovery-test".to_string(),
                     password: "Password123!".to_string(),
                     location_name: "Recovery Test".to_string(),
                     priority: 5,
@@ -1501,7 +2737,7 @@ mod tests {
 
             // Extract session token using let...else pattern
             let ServerMessage::MeetCreated {
-                meet_id: _,
+                meet_id: created_meet_id,
                 session_token,
             } = create_result
             else {
@@ -1525,7 +2761,7 @@ mod tests {
             // Send recovery response
             let recovery_result = handler
                 .handle_message(ClientMessage::StateRecoveryResponse {
-                    meet_id: "recovery-test".to_string(),
+                    meet_id: created_meet_id.clone(),
                     session_token: session_token.clone(),
                     last_seq_num: 0,
                     updates: initial_updates,
@@ -1541,7 +2777,7 @@ mod tests {
                     new_seq_num,
                     updates_recovered,
                 } => {
-                    assert_eq!(meet_id, "recovery-test");
+                    assert_eq!(meet_id, created_meet_id);
                     assert_eq!(new_seq_num, 2); // Two updates should have been processed
                     assert_eq!(updates_recovered, 2);
                 },
@@ -1567,7 +2803,7 @@ mod tests {
             // Send second recovery response
             let second_recovery_result = handler
                 .handle_message(ClientMessage::StateRecoveryResponse {
-                    meet_id: "recovery-test".to_string(),
+                    meet_id: created_meet_id.clone(),
                     session_token: session_token.clone(),
                     last_seq_num: 2,
                     updates: conflicting_updates,
@@ -1583,7 +2819,7 @@ mod tests {
                     new_seq_num,
                     updates_recovered,
                 } => {
-                    assert_eq!(meet_id, "recovery-test");
+                    assert_eq!(meet_id, created_meet_id);
                     assert_eq!(new_seq_num, 3); // Only one new update should have been processed
                     assert_eq!(updates_recovered, 1);
                 },
@@ -1603,7 +2839,7 @@ mod tests {
             // Send third recovery response with higher priority
             let third_recovery_result = handler
                 .handle_message(ClientMessage::StateRecoveryResponse {
-                    meet_id: "recovery-test".to_string(),
+                    meet_id: created_meet_id.clone(),
                     session_token,
                     last_seq_num: 3,
                     updates: higher_priority_updates,
@@ -1619,7 +2855,7 @@ mod tests {
                     new_seq_num: _,
                     updates_recovered,
                 } => {
-                    assert_eq!(meet_id, "recovery-test");
+                    assert_eq!(meet_id, created_meet_id);
                     assert_eq!(updates_recovered, 1); // The override should be accepted
                 },
                 _ => panic!("Expected StateRecovered response"),
@@ -4,8 +4,17 @@
 This module handles WebSocket connections and routes messages
 to the appropriate handlers. */
 use crate::{
+    bundle::MAX_BUNDLE_SIZE,
+    connections::ConnectionControl,
     error::AppError,
+    handlers::{
+        admin::admin_router,
+        bundle::{export_handler, import_handler},
+        health::{live_handler, ready_handler},
+    },
     messages::{ClientMessage, ServerMessage},
+    metrics::{self as metric_names, metrics_handler, LABEL_CONNECTION_ID, LABEL_MESSAGE_TYPE},
+    middleware::require_operator,
     storage::Storage,
     websocket::WebSocketHandler,
     AppState,
@@ -13,19 +22,25 @@ use crate::{
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        ConnectInfo, State, WebSocketUpgrade,
+        ConnectInfo, DefaultBodyLimit, State, WebSocketUpgrade,
     },
+    middleware,
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use dashmap::DashMap;
 use futures_util::{SinkExt, StreamExt};
-use metrics::{counter, gauge};
+use metrics::{counter, gauge, histogram};
 use std::net::SocketAddr;
-use std::sync::{Arc, LazyLock};
-use tokio::sync::mpsc;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, LazyLock,
+};
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{self, Duration, Instant};
 use tower_http::trace::TraceLayer;
+use tracing::{field, info_span, Instrument};
 
 static ACTIVITY_TIMES: LazyLock<DashMap<String, u64>> = LazyLock::new(DashMap::new);
 
@@ -35,16 +50,26 @@ pub fn create_router<S: Storage + Send + Sync + Clone + 'static>(
 ) -> Router {
     Router::new()
         .route("/ws", get(ws_handler))
-        .route("/health", get(health_handler))
+        .route("/health", get(live_handler))
+        .route("/health/live", get(live_handler))
+        .route("/health/ready", get(ready_handler))
+        .route(
+            "/metrics",
+            get(metrics_handler).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_operator::<S>,
+            )),
+        )
+        .route("/meets/{meet_id}/bundle", get(export_handler))
+        .route(
+            "/meets/import",
+            post(import_handler).layer(DefaultBodyLimit::max(MAX_BUNDLE_SIZE)),
+        )
+        .nest("/admin", admin_router(state.clone()))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
-/// Health check endpoint
-async fn health_handler() -> &'static str {
-    "Healthy"
-}
-
 /// Handle WebSocket connections
 async fn ws_handler<S: Storage + Send + Sync + Clone + 'static>(
     State(state): State<Arc<AppState<S>>>,
@@ -54,13 +79,24 @@ async fn ws_handler<S: Storage + Send + Sync + Clone + 'static>(
     tracing::debug!("WebSocket connection attempt from: {}", addr);
 
     // Create a handler - move it into the closure
-    let mut handler = WebSocketHandler::new(state);
+    let mut handler = WebSocketHandler::new(state.clone());
 
     // Set the client IP address for rate limiting
     handler.set_client_ip(addr.ip());
 
+    // Everything logged for this connection, including its relay forwarder,
+    // carries these fields; the meet and location are filled in once the
+    // client has a session
+    let span = info_span!(
+        "connection",
+        connection_id = %handler.client_id(),
+        client_ip = %addr.ip(),
+        meet_id = field::Empty,
+        location = field::Empty,
+    );
+
     // Upgrade the connection
-    ws.on_upgrade(move |socket| handle_socket(socket, handler, addr))
+    ws.on_upgrade(move |socket| handle_socket(socket, state, handler, addr).instrument(span))
 }
 
 /** Check state consistency for a meet
@@ -92,7 +128,7 @@ async fn check_state_consistency<S: Storage + Send + Sync + Clone + 'static>(
 
         // If last activity was more than 5 minutes ago, initiate recovery
         if current_time - *entry > 300 {
-            println!(
+            tracing::info!(
                 "Long inactivity detected for meet {meet_id}: {} seconds since last activity",
                 current_time - *entry
             );
@@ -115,9 +151,37 @@ async fn check_state_consistency<S: Storage + Send + Sync + Clone + 'static>(
     Ok(())
 }
 
+/// Heartbeat state shared between the send task and the receive loop
+#[derive(Default)]
+struct Heartbeat {
+    /// Pings sent since the last pong was received
+    outstanding: AtomicU32,
+    /// Signalled to stop the receive loop, either because the send side gave
+    /// up on the connection or because its session was revoked
+    close: Arc<Notify>,
+}
+
+/// Milliseconds since the Unix epoch, used as the ping payload
+fn epoch_millis() -> u64 {
+    u64::try_from(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis(),
+    )
+    .unwrap_or(u64::MAX)
+}
+
+/// Round-trip time for a pong echoing a ping payload created by `epoch_millis`
+fn pong_rtt(payload: &[u8]) -> Option<Duration> {
+    let sent = u64::from_be_bytes(payload.try_into().ok()?);
+    epoch_millis().checked_sub(sent).map(Duration::from_millis)
+}
+
 /// Handle a WebSocket connection
 async fn handle_socket<S: Storage + Send + Sync + Clone + 'static>(
     socket: WebSocket,
+    state: Arc<AppState<S>>,
     mut handler: WebSocketHandler<S>,
     addr: SocketAddr,
 ) {
@@ -126,37 +190,90 @@ async fn handle_socket<S: Storage + Send + Sync + Clone + 'static>(
 
     // Create a channel for sending messages back to the client
     let (tx, mut rx) = mpsc::channel::<ServerMessage>(32);
+    handler.set_client_tx(tx.clone());
 
-    // Track metrics
-    let _ = counter!("ws.connection", &[("value", "1")]);
-    let _ = gauge!("ws.active", &[("value", "1")]);
+    counter!(metric_names::WS_CONNECTION).increment(1);
+    gauge!(metric_names::WS_ACTIVE).increment(1.0);
 
     // Keep track of the meet_id for this connection
     let mut connected_meet_id = String::new();
 
+    let heartbeat = Arc::new(Heartbeat::default());
+    let client_id = handler.client_id().to_string();
+    state.connections.register(
+        &client_id,
+        addr.ip(),
+        ConnectionControl {
+            tx: tx.clone(),
+            close: heartbeat.close.clone(),
+        },
+    );
+
+    let ping_every = Duration::from_secs(state.settings.websocket.ping_interval_secs.max(1));
+    let max_missed_pongs = state.settings.websocket.max_missed_pongs.max(1);
+
     tracing::debug!("WebSocket connection established from: {}", addr);
 
-    // Spawn a task to forward messages from the channel to the client
-    let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            // Serialize the message to JSON
-            match serde_json::to_string(&msg) {
-                Ok(json) => {
-                    tracing::debug!("Sending message to client: {}", json);
-                    if sender.send(Message::Text(json.into())).await.is_err() {
-                        tracing::error!("Failed to send message to client");
+    // Spawn a task to forward messages from the channel to the client,
+    // interleaved with heartbeat pings
+    let send_heartbeat = heartbeat.clone();
+    let send = async move {
+        let mut ping = time::interval_at(Instant::now() + ping_every, ping_every);
+        loop {
+            tokio::select! {
+                msg = rx.recv() => {
+                    let Some(msg) = msg else {
+                        // The server is done with this connection
+                        let _ = sender.send(Message::Close(None)).await;
                         break;
+                    };
+                    // Serialize the message to JSON
+                    match serde_json::to_string(&msg) {
+                        Ok(json) => {
+                            tracing::debug!("Sending message to client: {}", json);
+                            if sender.send(Message::Text(json.into())).await.is_err() {
+                                tracing::error!("Failed to send message to client");
+                                break;
+                            }
+                        },
+                        Err(e) => {
+                            tracing::error!("Failed to serialize message: {}", e);
+                        },
                     }
                 },
-                Err(e) => {
-                    tracing::error!("Failed to serialize message: {}", e);
+                _ = ping.tick() => {
+                    let missed = send_heartbeat.outstanding.fetch_add(1, Ordering::SeqCst);
+                    if missed >= max_missed_pongs {
+                        tracing::warn!("Client {} missed {} heartbeats, closing", addr, missed);
+                        counter!(metric_names::WS_HEARTBEAT_TIMEOUT).increment(1);
+                        let _ = sender.send(Message::Close(None)).await;
+                        break;
+                    }
+                    let payload = epoch_millis().to_be_bytes().to_vec();
+                    if sender.send(Message::Ping(payload.into())).await.is_err() {
+                        break;
+                    }
                 },
             }
         }
-    });
+        // Make sure the receive loop stops too
+        send_heartbeat.close.notify_one();
+    };
+    let send_task = tokio::spawn(send.in_current_span());
+
+    // Process incoming messages until the client leaves or stops answering pings
+    loop {
+        let msg_result = tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            () = heartbeat.close.notified() => {
+                tracing::debug!("Closing connection from {}", addr);
+                break;
+            },
+        };
 
-    // Process incoming messages
-    while let Some(msg_result) = receiver.next().await {
         match msg_result {
             Ok(axum::extract::ws::Message::Text(text)) => {
                 tracing::debug!("Received message from client: {}", text);
@@ -167,46 +284,40 @@ async fn handle_socket<S: Storage + Send + Sync + Clone + 'static>(
                     Ok(client_msg) => {
                         tracing::debug!("Successfully parsed message: {:?}", client_msg);
 
-                        // Extract meet_id from message if present to update connected_meet_id
-                        let meet_id = match &client_msg {
-                            ClientMessage::CreateMeet { meet_id, .. }
-                            | ClientMessage::JoinMeet { meet_id, .. }
-                            | ClientMessage::UpdateInit { meet_id, .. }
-                            | ClientMessage::ClientPull { meet_id, .. }
-                            | ClientMessage::PublishMeet { meet_id, .. }
-                            | ClientMessage::StateRecoveryResponse { meet_id, .. } => {
-                                Some(meet_id.clone())
-                            },
+                        // Only check consistency for join/connect operations
+                        let check_meet = match &client_msg {
+                            ClientMessage::JoinMeet { meet_id, .. }
+                            | ClientMessage::ClientPull { meet_id, .. } => Some(meet_id.clone()),
+                            _ => None,
                         };
 
-                        if let Some(ref meet_id) = meet_id {
-                            // Always clone (first time) or clone_from (subsequent times)
-                            if connected_meet_id.is_empty() {
-                                #[allow(clippy::assigning_clones)]
-                                {
-                                    // First assignment needs clone
-                                    connected_meet_id = meet_id.clone();
-                                }
-                            } else {
-                                connected_meet_id.clone_from(meet_id);
+                        // Process the message
+                        let message_type = client_msg.message_type();
+                        let started = Instant::now();
+                        let result = handler.handle_message(client_msg).await;
+                        counter!(metric_names::WS_MESSAGES, LABEL_MESSAGE_TYPE => message_type)
+                            .increment(1);
+                        histogram!(
+                            metric_names::WS_MESSAGE_DURATION_MS,
+                            LABEL_MESSAGE_TYPE => message_type
+                        )
+                        .record(started.elapsed().as_secs_f64() * 1000.0);
+
+                        // Track the meet this connection ended up subscribed to
+                        if let Some(subscribed) = handler.subscribed_meet() {
+                            if subscribed != connected_meet_id {
+                                connected_meet_id = subscribed.to_string();
                             }
+                        }
 
-                            // Only do this for join/connect operations
-                            match &client_msg {
-                                ClientMessage::JoinMeet { .. }
-                                | ClientMessage::ClientPull { .. } => {
-                                    if let Err(e) =
-                                        check_state_consistency(&mut handler, meet_id).await
-                                    {
-                                        tracing::error!("Error checking state consistency: {}", e);
-                                    }
-                                },
-                                _ => {},
+                        // Only once the client has been admitted to the meet
+                        if let Some(meet_id) = check_meet.filter(|m| *m == connected_meet_id) {
+                            if let Err(e) = check_state_consistency(&mut handler, &meet_id).await {
+                                tracing::error!("Error checking state consistency: {}", e);
                             }
                         }
 
-                        // Process the message
-                        match handler.handle_message(client_msg).await {
+                        match result {
                             Ok(response) => {
                                 tracing::debug!("Handler produced response: {:?}", response);
                                 if tx.send(response).await.is_err() {
@@ -273,6 +384,14 @@ async fn handle_socket<S: Storage + Send + Sync + Clone + 'static>(
                 tracing::debug!("Client disconnected: {}", addr);
                 break;
             },
+            Ok(axum::extract::ws::Message::Pong(payload)) => {
+                heartbeat.outstanding.store(0, Ordering::SeqCst);
+                if let Some(rtt) = pong_rtt(&payload) {
+                    histogram!(metric_names::WS_RTT_MS, LABEL_CONNECTION_ID => client_id.clone())
+                        .record(rtt.as_secs_f64() * 1000.0);
+                    state.connections.record_rtt(&client_id, rtt);
+                }
+            },
             Ok(_) => {
                 // Ignore other message types
             },
@@ -289,11 +408,16 @@ async fn handle_socket<S: Storage + Send + Sync + Clone + 'static>(
         handler.unregister_client(&connected_meet_id);
     }
 
+    state.connections.remove(&client_id);
+
+    // Drop every sender so the send task sees the channel close
+    drop(handler);
+    drop(tx);
+
     // Wait for the send task to complete
     _ = send_task.await;
 
-    // Update metrics
-    let _ = gauge!("ws.active", &[("value", "-1")]);
+    gauge!(metric_names::WS_ACTIVE).decrement(1.0);
     tracing::debug!("WebSocket connection closed: {}", addr);
 }
 
@@ -378,7 +502,6 @@ mod tests {
 
         // Create a meet message
         let create_meet = ClientMessage::CreateMeet {
-            meet_id: "test-meet".to_string(),
             password: "Password123!".to_string(),
             location_name: "Test Location".to_string(),
             priority: 5,
@@ -397,7 +520,7 @@ mod tests {
                 meet_id,
                 session_token,
             } => {
-                assert_eq!(meet_id, "test-meet");
+                assert_eq!(meet_id.len(), 11);
                 assert!(!session_token.is_empty());
             },
             _ => panic!("Expected MeetCreated response, got {response:?}"),
@@ -428,7 +551,7 @@ mod tests {
     #[tokio::test]
     async fn test_validation_errors() {
         // Test validation
-        let invalid_meet = ClientMessage::CreateMeet {
+        let invalid_meet = ClientMessage::JoinMeet {
             meet_id: String::new(), // Invalid empty meet ID
             password: "Password123!".to_string(),
             location_name: "Test Location".to_string(),
@@ -453,7 +576,6 @@ mod tests {
             // Create a meet
             let create_result = handler
                 .handle_message(ClientMessage::CreateMeet {
-                    meet_id: "workflow-test".to_string(),
                     password: "Password123!".to_string(),
                     location_name: "Workflow Test".to_string(),
                     priority: 5,
@@ -462,18 +584,22 @@ mod tests {
                 .expect("Failed to handle create meet message");
 
             // Extract session token using let...else
-            let ServerMessage::MeetCreated { session_token, .. } = create_result else {
+            let ServerMessage::MeetCreated {
+                meet_id: created_meet_id,
+                session_token,
+            } = create_result
+            else {
                 panic!("Expected MeetCreated response, got {create_result:?}")
             };
 
             // Register a client channel
             let (tx, _rx) = mpsc::channel::<ServerMessage>(10);
-            handler.register_client("workflow-test", tx);
+            handler.register_client(&created_meet_id, tx);
 
             // Send an update
             let update_result = handler
                 .handle_message(ClientMessage::UpdateInit {
-                    meet_id: "workflow-test".to_string(),
+                    meet_id: created_meet_id.clone(),
                     session_token: session_token.clone(),
                     updates: vec![crate::messages::Update {
                         location: "test.item1".to_string(),
@@ -490,7 +616,7 @@ mod tests {
                     meet_id,
                     update_ids,
                 } => {
-                    assert_eq!(meet_id, "workflow-test");
+                    assert_eq!(meet_id, created_meet_id);
                     assert_eq!(update_ids.len(), 1);
                 },
                 _ => panic!("Expected UpdateAck response, got {update_result:?}"),
@@ -499,7 +625,7 @@ mod tests {
             // Pull updates
             let pull_result = handler
                 .handle_message(ClientMessage::ClientPull {
-                    meet_id: "workflow-test".to_string(),
+                    meet_id: created_meet_id.clone(),
                     session_token,
                     last_server_seq: 0,
                 })
@@ -513,7 +639,7 @@ mod tests {
                     last_server_seq,
                     ..
                 } => {
-                    assert_eq!(meet_id, "workflow-test");
+                    assert_eq!(meet_id, created_meet_id);
                     assert_eq!(last_server_seq, 0); // No updates yet in our implementation
                 },
                 _ => panic!("Expected ServerPull response, got {pull_result:?}"),
@@ -556,45 +682,62 @@ mod tests {
         // Use the setup helper
         let (state, _handler, _temp_dir) = setup_test_env().await;
 
-        // Create router with actual handlers
-        let router = Router::new()
-            .route("/health", get(|| async { "Healthy" }))
-            .with_state(state);
+        let router = create_router(state);
 
-        // Create a request
         let request = Request::builder()
             .uri("/health")
             .body(Body::empty())
             .unwrap();
-
-        // Execute the request
         let response = router.oneshot(request).await.unwrap();
 
-        // Verify the response
         assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "ok");
     }
 
     #[tokio::test]
-    async fn test_ws_router_metrics() {
-        // Use the setup helper
-        let (state, _handler, _temp_dir) = setup_test_env().await;
+    async fn test_ws_router_metrics_need_operator_token() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FlatFileStorage::new(temp_dir.path()).unwrap();
+        let mut settings = Settings::default();
+        settings.storage.path = temp_dir.path().to_path_buf();
+        settings.admin.token = Some("operator-token".to_string());
+        let state = Arc::new(AppState::new(storage, &settings).await.unwrap());
+        let router = create_router(state);
+
+        let scrape = |token: Option<&str>| {
+            let mut request = Request::builder().uri("/metrics");
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
 
-        // Create router with actual handlers
-        let router = Router::new()
-            .route("/metrics", get(|| async { "Metrics data" }))
-            .with_state(state);
+        let response = scrape(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = scrape(Some("wrong-token")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Tests never install the recorder, so an operator gets the 404 for
+        // a server without metrics rather than the 401
+        let response = scrape(Some("operator-token")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_ws_router_metrics_hidden_without_token() {
+        let (state, _handler, _temp_dir) = setup_test_env().await;
+        let router = create_router(state);
 
-        // Create a request
         let request = Request::builder()
             .uri("/metrics")
             .body(Body::empty())
             .unwrap();
-
-        // Execute the request
         let response = router.oneshot(request).await.unwrap();
-
-        // Verify the response
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
@@ -671,5 +814,16 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[test]
+    fn test_pong_rtt() {
+        let sent = epoch_millis() - 25;
+        let rtt = pong_rtt(&sent.to_be_bytes()).unwrap();
+        assert!(rtt >= Duration::from_millis(25));
+
+        // Pongs that don't echo one of our pings are ignored
+        assert!(pong_rtt(b"hello").is_none());
+        assert!(pong_rtt(&u64::MAX.to_be_bytes()).is_none());
+    }
+
     // todo: ... more tests ...
 }
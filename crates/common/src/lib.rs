@@ -140,8 +140,11 @@ pub enum ServerToClient {
     ServerPull {
         // Current server sequence number
         last_server_seq: Seq,
-        // Updates since client's last seen sequence
+        // Updates since client's last seen sequence, at most one page
         updates_relayed: Vec<UpdateWithServerSeq>,
+        // More updates follow; pull again from the last one relayed
+        #[serde(default)]
+        has_more: bool,
     },
     // Acknowledgment of meet publication
     PublishAck,
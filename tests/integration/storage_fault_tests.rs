@@ -0,0 +1,76 @@
+// =============
+// tests/integration/storage_fault_tests.rs
+// =============
+//! Integration tests for how the server behaves when storage fails.
+use backend_lib::{
+    config::{SessionBackend, Settings},
+    messages::{ClientMessage, ServerMessage},
+    storage::{Fault, FaultPlan, FaultyStorage, MemoryStorage, Storage, StorageOp},
+    websocket::WebSocketHandler,
+    AppState,
+};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+type Faulty = FaultyStorage<MemoryStorage>;
+
+async fn setup(plan: FaultPlan) -> (WebSocketHandler<Faulty>, Faulty, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = FaultyStorage::new(MemoryStorage::new(), plan);
+
+    let mut settings = Settings::default();
+    settings.storage.path = temp_dir.path().to_path_buf();
+    settings.sessions.backend = SessionBackend::Memory;
+
+    let state = Arc::new(AppState::new(storage.clone(), &settings).await.unwrap());
+    (WebSocketHandler::new(state), storage, temp_dir)
+}
+
+fn create_meet() -> ClientMessage {
+    ClientMessage::CreateMeet {
+        password: "Password123!".to_string(),
+        location_name: "Main Table".to_string(),
+        priority: 10,
+    }
+}
+
+#[tokio::test]
+async fn test_create_meet_reports_storage_failure() {
+    let plan = FaultPlan::new().on_call(
+        StorageOp::StoreMeetInfo,
+        1,
+        Fault::Error(ErrorKind::StorageFull),
+    );
+    let (mut handler, storage, _temp_dir) = setup(plan).await;
+
+    match handler.handle_message(create_meet()).await.unwrap() {
+        ServerMessage::Error { code, .. } => assert_eq!(code, "STORAGE_ERROR"),
+        other => panic!("Expected a storage error, got {other:?}"),
+    }
+    // The failed meet's ID was given back
+    assert!(storage.inner().list_meets().await.unwrap().is_empty());
+    assert_eq!(storage.calls(StorageOp::ReleaseMeetId), 1);
+
+    // The next attempt goes through once storage recovers
+    let ServerMessage::MeetCreated { meet_id, .. } =
+        handler.handle_message(create_meet()).await.unwrap()
+    else {
+        panic!("Expected the meet to be created");
+    };
+    assert!(storage.inner().get_meet_info(&meet_id).await.is_ok());
+    assert_eq!(storage.calls(StorageOp::StoreMeetInfo), 2);
+}
+
+#[tokio::test]
+async fn test_slow_storage_still_creates_meet() {
+    let plan = FaultPlan::new().always(
+        StorageOp::ReserveMeetId,
+        Fault::Latency(std::time::Duration::from_millis(20)),
+    );
+    let (mut handler, storage, _temp_dir) = setup(plan).await;
+
+    let response = handler.handle_message(create_meet()).await.unwrap();
+    assert!(matches!(response, ServerMessage::MeetCreated { .. }));
+    assert_eq!(storage.calls(StorageOp::ReserveMeetId), 1);
+}
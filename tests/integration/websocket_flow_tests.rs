@@ -54,9 +54,6 @@ async fn test_complete_flow() {
         let (addr, _state, _temp_dir) = crate::tests::setup_server().await;
         let url = format!("ws://{addr}/ws");
 
-        // Use unique meet ID
-        let meet_id = crate::tests::unique_meet_id("flow-meet");
-
         // Connect to the server
         let (mut ws_stream, _) = tokio_tungstenite::connect_async(url)
             .await
@@ -64,7 +61,6 @@ async fn test_complete_flow() {
 
         // 1. Create Meet
         let create_msg = ClientMessage::CreateMeet {
-            meet_id: meet_id.clone(),
             password: "Password123!".to_string(),
             location_name: "Flow Test".to_string(),
             priority: 1,
@@ -80,7 +76,11 @@ async fn test_complete_flow() {
             crate::tests::next_message_with_timeout(&mut ws_stream, 5, "Create meet").await;
         let create_result: ServerMessage =
             serde_json::from_str(create_response.to_text().unwrap()).unwrap();
-        let ServerMessage::MeetCreated { session_token, .. } = create_result else {
+        let ServerMessage::MeetCreated {
+            meet_id,
+            session_token,
+        } = create_result
+        else {
             panic!("Expected MeetCreated response")
         };
 
@@ -201,13 +201,9 @@ async fn test_broadcast_and_client_communication() {
         let (addr, _state, _temp_dir) = crate::tests::setup_server().await;
         let url = format!("ws://{addr}/ws");
 
-        // Use unique meet ID
-        let meet_id = crate::tests::unique_meet_id("broadcast-meet");
-
         // Connect client 1
         let (mut ws_stream1, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
         let create_msg = ClientMessage::CreateMeet {
-            meet_id: meet_id.clone(),
             password: "Password123!".to_string(),
             location_name: "Client 1".to_string(),
             priority: 1,
@@ -224,8 +220,8 @@ async fn test_broadcast_and_client_communication() {
         let create_result: ServerMessage =
             serde_json::from_str(create_response.to_text().unwrap()).unwrap();
         let ServerMessage::MeetCreated {
+            meet_id,
             session_token: session_token1,
-            ..
         } = create_result
         else {
             panic!("Expected MeetCreated response")
@@ -302,13 +298,11 @@ async fn test_broadcast_and_client_communication() {
 #[ignore = "These are end-to-end tests requiring a running server. Run with `cargo test -- --ignored` to execute."]
 async fn test_reconnection_and_retry() {
     let (mut handler, _temp_dir) = setup().await;
-    let meet_id = "test-reconnect-meet";
     let password = "ReconnectTest123!";
 
     // Step 1: Create a meet and get session token
     let create_result = handler
         .handle_message(ClientMessage::CreateMeet {
-            meet_id: meet_id.to_string(),
             password: password.to_string(),
             location_name: "Reconnect Test Location".to_string(),
             priority: 5,
@@ -316,7 +310,11 @@ async fn test_reconnection_and_retry() {
         .await
         .unwrap();
 
-    let ServerMessage::MeetCreated { session_token, .. } = create_result else {
+    let ServerMessage::MeetCreated {
+        meet_id,
+        session_token,
+    } = create_result
+    else {
         panic!("Expected MeetCreated response")
     };
 
@@ -324,7 +322,7 @@ async fn test_reconnection_and_retry() {
     let (tx, _rx) = mpsc::channel::<ServerMessage>(10);
 
     // Register the client
-    handler.register_client(meet_id, tx.clone());
+    handler.register_client(&meet_id, tx.clone());
 
     // Step 2: Simulate sending an update with an invalid session token
     // to trigger the reconnection logic
@@ -411,13 +409,9 @@ async fn test_state_recovery_scenarios() {
         let (addr, _state, _temp_dir) = crate::tests::setup_server().await;
         let url = format!("ws://{addr}/ws");
 
-        // Use unique meet ID
-        let meet_id = crate::tests::unique_meet_id("recovery-meet");
-
         // Connect client 1 (priority 8)
         let (mut ws_stream1, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
         let create_msg = ClientMessage::CreateMeet {
-            meet_id: meet_id.clone(),
             password: "Password123!".to_string(),
             location_name: "High Priority Client".to_string(),
             priority: 8, // Higher priority client
@@ -436,8 +430,8 @@ async fn test_state_recovery_scenarios() {
         let create_result: ServerMessage =
             serde_json::from_str(create_response.to_text().unwrap()).unwrap();
         let ServerMessage::MeetCreated {
+            meet_id,
             session_token: session_token1,
-            ..
         } = create_result
         else {
             panic!("Expected MeetCreated response")
@@ -531,13 +525,9 @@ async fn test_inactivity_recovery() {
         let (addr, _state, _temp_dir) = crate::tests::setup_server().await;
         let url = format!("ws://{addr}/ws");
 
-        // Use unique meet ID
-        let meet_id = crate::tests::unique_meet_id("inactivity-meet");
-
         // Connect client 1
         let (mut ws_stream1, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
         let create_msg = ClientMessage::CreateMeet {
-            meet_id: meet_id.clone(),
             password: "Password123!".to_string(),
             location_name: "Inactivity Test Client".to_string(),
             priority: 5,
@@ -554,8 +544,8 @@ async fn test_inactivity_recovery() {
         let create_result: ServerMessage =
             serde_json::from_str(create_response.to_text().unwrap()).unwrap();
         let ServerMessage::MeetCreated {
+            meet_id,
             session_token: session_token1,
-            ..
         } = create_result
         else {
             panic!("Expected MeetCreated response")
@@ -729,23 +719,12 @@ pub mod tests {
     use backend_lib::ws_router::create_router;
     use backend_lib::AppState;
     use futures_util::{SinkExt, StreamExt};
-    use rand;
     use std::fmt::Debug;
     use std::sync::Arc;
     use tempfile::TempDir;
     use tokio::time::Duration;
     use tokio_tungstenite::tungstenite::Message;
 
-    // Add a helper function to generate unique meet IDs
-    pub fn unique_meet_id(prefix: &str) -> String {
-        format!(
-            "{}-{}-{}",
-            prefix,
-            std::process::id(),
-            rand::random::<u16>()
-        )
-    }
-
     // Add allow attribute to the next_message_with_timeout function
     #[allow(clippy::match_wild_err_arm)]
     pub async fn next_message_with_timeout<S>(
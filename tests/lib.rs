@@ -23,6 +23,7 @@ mod unit {
 mod integration {
     // Integration tests
     mod auth_flow_tests;
+    mod storage_fault_tests;
 }
 
 #[cfg(test)]
@@ -10,7 +10,7 @@
 /// cargo test --release -- --ignored --nocapture performance::websocket_throughput
 /// ```
 #[tokio::test]
-#[ignore] // Ignored by default as it's a long-running performance test
+#[ignore = "long-running performance test"]
 async fn test_websocket_throughput() {
     println!("WebSocket Throughput Performance Test");
     println!("=====================================");
@@ -9,6 +9,7 @@
 
 use backend_lib::config::{
     RateLimitSettings, ServerSettings, Settings, SettingsManager, StorageSettings,
+    WebSocketSettings,
 };
 use std::fs;
 use std::path::PathBuf;
@@ -25,6 +26,39 @@ fn test_settings_default() {
     assert_eq!(settings.storage.path, PathBuf::from("data"));
     assert_eq!(settings.rate_limit.max_requests, 100);
     assert_eq!(settings.rate_limit.window_secs, 60);
+    assert_eq!(settings.websocket, WebSocketSettings::default());
+}
+
+#[test]
+fn test_websocket_settings_from_file() {
+    let temp_dir = tempdir().unwrap();
+    let config_path = temp_dir.path().join("websocket.toml");
+
+    let config_content = r#"
+        [server]
+        host = "127.0.0.1"
+        port = 3000
+
+        [storage]
+        path = "data"
+
+        [rate_limit]
+        window_secs = 60
+        max_requests = 100
+
+        [websocket]
+        relay_batch_window_ms = 20
+    "#;
+    fs::write(&config_path, config_content).unwrap();
+
+    let settings = Settings::load_from(config_path.to_str().unwrap()).unwrap();
+
+    // Keys that are not given fall back to their defaults
+    assert_eq!(settings.websocket.relay_batch_window_ms, 20);
+    assert_eq!(
+        settings.websocket.relay_max_batch_size,
+        WebSocketSettings::default().relay_max_batch_size
+    );
 }
 
 #[test]
@@ -34,14 +68,17 @@ fn test_settings_custom() {
         server: ServerSettings {
             host: "0.0.0.0".to_string(),
             port: 9000,
+            drain_secs: 5,
         },
         storage: StorageSettings {
             path: PathBuf::from("custom_data"),
+            ..StorageSettings::default()
         },
         rate_limit: RateLimitSettings {
             window_secs: 120,
             max_requests: 200,
         },
+        ..Settings::default()
     };
 
     // Verify custom values
@@ -96,14 +133,17 @@ fn test_load_config_from_file() {
         server: ServerSettings {
             host: "192.168.1.1".to_string(),
             port: 8888,
+            drain_secs: 5,
         },
         storage: StorageSettings {
             path: PathBuf::from("test_data"),
+            ..StorageSettings::default()
         },
         rate_limit: RateLimitSettings {
             window_secs: 30,
             max_requests: 50,
         },
+        ..Settings::default()
     };
 
     assert_eq!(custom_settings.server.host, "192.168.1.1");
@@ -173,14 +213,17 @@ fn test_load_settings_from_environment() {
         server: ServerSettings {
             host: "env.override".to_string(), // This would come from an env var
             port: 1234,
+            drain_secs: 5,
         },
         storage: StorageSettings {
             path: PathBuf::from("default_path"),
+            ..StorageSettings::default()
         },
         rate_limit: RateLimitSettings {
             window_secs: 5,
             max_requests: 10,
         },
+        ..Settings::default()
     };
 
     // Verify that our settings structure works as expected
@@ -200,14 +243,17 @@ fn test_settings_with_custom_values() {
         server: ServerSettings {
             host: "127.0.0.1".to_string(),
             port: 3000,
+            drain_secs: 5,
         },
         storage: StorageSettings {
             path: PathBuf::from("data"),
+            ..StorageSettings::default()
         },
         rate_limit: RateLimitSettings {
             window_secs: 60,
             max_requests: 100,
         },
+        ..Settings::default()
     };
 
     // Verify the settings
@@ -265,14 +311,17 @@ fn test_environment_overrides() {
         server: ServerSettings {
             host: "127.0.0.1".to_string(),
             port: 3000,
+            drain_secs: 5,
         },
         storage: StorageSettings {
             path: PathBuf::from("test_data"),
+            ..StorageSettings::default()
         },
         rate_limit: RateLimitSettings {
             window_secs: 60,
             max_requests: 100,
         },
+        ..Settings::default()
     };
 
     // Verify the settings
@@ -53,6 +53,10 @@ fn test_app_error_status_codes() {
         .status_code(),
         StatusCode::CONFLICT
     );
+    assert_eq!(
+        AppError::MeetExists("test".to_string()).status_code(),
+        StatusCode::CONFLICT
+    );
 }
 
 #[test]
@@ -79,6 +83,18 @@ fn test_app_error_error_codes() {
         .error_code(),
         "RECOVERY_001"
     );
+    assert_eq!(
+        AppError::CorruptLog {
+            meet_id: "test".to_string(),
+            offset: 42
+        }
+        .error_code(),
+        "STORE_001"
+    );
+    assert_eq!(
+        AppError::MeetExists("test".to_string()).error_code(),
+        "MEET_003"
+    );
 }
 
 #[test]
@@ -515,11 +515,13 @@ async fn test_live_client_pull() {
             ServerToClient::ServerPull {
                 last_server_seq,
                 updates_relayed,
+                has_more,
             } => {
                 assert_eq!(
                     last_server_seq, 0,
                     "Last server sequence number should match what we sent"
                 );
+                assert!(!has_more, "A single update fits in one page");
 
                 // We should have at least one update (the one we added)
                 assert!(
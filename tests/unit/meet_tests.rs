@@ -5,7 +5,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use backend_lib::meet::MeetManager;
-use backend_lib::storage::Storage;
+use backend_lib::storage::{MeetActivity, MeetData, Storage};
 
 // Mock storage for testing
 #[derive(Clone)]
@@ -28,10 +28,41 @@ impl Storage for MockStorage {
         Ok(vec![])
     }
 
+    async fn read_updates_from(
+        &self,
+        _meet_id: &str,
+        _first_seq: u64,
+        _limit: usize,
+    ) -> Result<Vec<String>, backend_lib::error::AppError> {
+        Ok(vec![])
+    }
+
+    async fn replace_updates(
+        &self,
+        _meet_id: &str,
+        _values: &[String],
+    ) -> Result<(), backend_lib::error::AppError> {
+        Ok(())
+    }
+
     async fn archive_meet(&self, _meet_id: &str) -> Result<(), backend_lib::error::AppError> {
         Ok(())
     }
 
+    async fn list_meets(
+        &self,
+    ) -> Result<Vec<backend_lib::meet_registry::MeetRecord>, backend_lib::error::AppError> {
+        Ok(vec![])
+    }
+
+    async fn reserve_meet_id(&self, _meet_id: &str) -> Result<bool, backend_lib::error::AppError> {
+        Ok(true)
+    }
+
+    async fn release_meet_id(&self, _meet_id: &str) -> Result<(), backend_lib::error::AppError> {
+        Ok(())
+    }
+
     async fn store_meet_info(
         &self,
         _meet_id: &str,
@@ -59,6 +90,51 @@ impl Storage for MockStorage {
     ) -> Result<(), backend_lib::error::AppError> {
         Ok(())
     }
+
+    async fn get_meet_csv(
+        &self,
+        _meet_id: &str,
+    ) -> Result<Option<(String, String)>, backend_lib::error::AppError> {
+        Ok(None)
+    }
+
+    async fn meet_activity(
+        &self,
+        _meet_id: &str,
+    ) -> Result<Option<MeetActivity>, backend_lib::error::AppError> {
+        Ok(None)
+    }
+
+    async fn purge_meet(&self, _meet_id: &str) -> Result<(), backend_lib::error::AppError> {
+        Ok(())
+    }
+
+    async fn purge_meet_csv(&self, _meet_id: &str) -> Result<(), backend_lib::error::AppError> {
+        Ok(())
+    }
+
+    async fn read_finished_meet(
+        &self,
+        _meet_id: &str,
+    ) -> Result<Option<MeetData>, backend_lib::error::AppError> {
+        Ok(None)
+    }
+
+    async fn rewrite_finished_meet(
+        &self,
+        _meet_id: &str,
+        _data: &MeetData,
+    ) -> Result<(), backend_lib::error::AppError> {
+        Err(backend_lib::error::AppError::MeetNotFound)
+    }
+
+    async fn replace_meet(
+        &self,
+        _meet_id: &str,
+        _data: &MeetData,
+    ) -> Result<(), backend_lib::error::AppError> {
+        Ok(())
+    }
 }
 
 #[tokio::test]
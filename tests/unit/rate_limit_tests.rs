@@ -8,7 +8,7 @@ use std::net::{IpAddr, Ipv4Addr};
 #[test]
 fn test_rate_limiter_allows_initial_attempts() {
     let rate_limiter = AuthRateLimiter::default();
-    let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
 
     // First attempt should be allowed
     assert!(rate_limiter.check_rate_limit(ip));